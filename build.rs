@@ -0,0 +1,23 @@
+fn main() {
+    #[cfg(feature = "cdylib")]
+    generate_header();
+}
+
+/// Writes the header of the C API to `qkd.h` in `OUT_DIR`.
+///
+/// The source tree is left untouched, so that read-only and vendored sources
+/// build. The header distributed in `include/qkd.h` is a copy of this one, and
+/// `tests/ffi.rs` checks that it is up to date.
+#[cfg(feature = "cdylib")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .with_config(cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap())
+        .generate()
+        .expect("Could not generate the C header")
+        .write_to_file(format!("{}/qkd.h", out_dir));
+}
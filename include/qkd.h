@@ -0,0 +1,183 @@
+/* C API of the qkd simulator. Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#ifndef QKD_H
+#define QKD_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Version of the C API, increased on every incompatible change.
+#define QKD_ABI_VERSION 1
+
+// Outcome of a call to the C API.
+typedef enum QkdStatus {
+  // The call succeeded.
+  QKD_STATUS_OK = 0,
+  // A required pointer argument is null.
+  QKD_STATUS_NULL_POINTER = 1,
+  // A numeric argument is out of range or a string is not valid UTF-8.
+  QKD_STATUS_INVALID_ARGUMENT = 2,
+  // The protocol name is not registered.
+  QKD_STATUS_UNKNOWN_PROTOCOL = 3,
+  // The simulator panicked. The handles passed to the call are left unchanged.
+  QKD_STATUS_PANIC = 4,
+} QkdStatus;
+
+// Protocol configured through the C API. Opaque to C callers.
+typedef struct QkdProtocol QkdProtocol;
+
+// Result of an execution run through the C API, with its final key. Opaque to C callers.
+typedef struct QkdRun QkdRun;
+
+// Metrics of an execution, mirroring `QKDResult`.
+typedef struct QkdResult {
+  // Execution time in microseconds.
+  uint64_t elapsed_time_us;
+  // Whether the check bits were accepted.
+  bool is_considered_secure;
+  // Length of the sifted key, or 0 if the protocol was aborted.
+  size_t key_length;
+  // QBER of the check bits, or NaN if it is not defined.
+  double quantum_bit_error_rate;
+  // Fraction of the sifted key known by Eve.
+  double eve_knowledge;
+} QkdResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the version of the C API the library was built with.
+uint32_t qkd_abi_version(void);
+
+// Creates a protocol handle with 1000 qubits, no eavesdropper and ideal hardware.
+//
+// # Arguments
+//
+// * `name` - Null-terminated name of a registered protocol.
+// * `protocol` - Where the new handle is written, to be released with `qkd_protocol_free`.
+//
+// # Safety
+//
+// `name` must be a valid null-terminated string and `protocol` a valid pointer.
+enum QkdStatus qkd_protocol_new(const char *name, struct QkdProtocol **protocol);
+
+// Releases a protocol handle. Null handles are ignored.
+//
+// # Safety
+//
+// `protocol` must be null or a handle created by `qkd_protocol_new` and not released yet.
+void qkd_protocol_free(struct QkdProtocol *protocol);
+
+// Sets the number of qubits sent by Alice.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_number_of_qubits(struct QkdProtocol *protocol,
+                                                 size_t number_of_qubits);
+
+// Sets the probability (0.0 to 1.0) that Eve intercepts a qubit.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_interception_rate(struct QkdProtocol *protocol,
+                                                  double interception_rate);
+
+// Sets the probability (0.0 to 1.0) that the channel depolarizes a qubit.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_depolarization(struct QkdProtocol *protocol, double depolarization);
+
+// Sets the probability (0.0 to 1.0) that a photon is lost in the channel,
+// in addition to the fiber attenuation.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_loss(struct QkdProtocol *protocol, double loss);
+
+// Sets the length in kilometers of the fiber between Alice and Bob.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_distance_km(struct QkdProtocol *protocol, double distance_km);
+
+// Sets the efficiency and the dark count probability (0.0 to 1.0) of Bob's detector.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_detector(struct QkdProtocol *protocol,
+                                         double efficiency,
+                                         double dark_count_probability);
+
+// Sets the maximum fraction (0.0 to 1.0) of mismatched check bits tolerated before aborting.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_qber_threshold(struct QkdProtocol *protocol, double qber_threshold);
+
+// Seeds every following run, which makes them reproducible. Runs are seeded
+// from the operating system entropy source until a seed is set.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle.
+enum QkdStatus qkd_protocol_set_seed(struct QkdProtocol *protocol, uint64_t seed);
+
+// Executes the protocol and, if it is not aborted, reconciles and amplifies
+// the sifted key into the final key.
+//
+// # Arguments
+//
+// * `protocol` - Configured protocol handle.
+// * `run` - Where the new run handle is written, to be released with `qkd_run_free`.
+//
+// # Safety
+//
+// `protocol` must be null or a valid handle, and `run` a valid pointer.
+enum QkdStatus qkd_protocol_run(const struct QkdProtocol *protocol, struct QkdRun **run);
+
+// Releases a run handle and its key. Null handles are ignored.
+//
+// # Safety
+//
+// `run` must be null or a handle created by `qkd_protocol_run` and not released yet.
+void qkd_run_free(struct QkdRun *run);
+
+// Reads the metrics of a run.
+//
+// # Safety
+//
+// `run` must be null or a valid handle, and `result` a valid pointer.
+enum QkdStatus qkd_run_result(const struct QkdRun *run, struct QkdResult *result);
+
+// Borrows the final key of a run, packed most significant bit first.
+//
+// The key is empty if the protocol was aborted, the reconciliation failed
+// or no secret bits remain after the privacy amplification.
+//
+// # Arguments
+//
+// * `run` - Run handle.
+// * `key` - Where the pointer to the key bytes is written. It remains valid
+//   until the run is released, and must not be freed by the caller.
+// * `length` - Where the number of key bytes is written.
+//
+// # Safety
+//
+// `run` must be null or a valid handle, and `key` and `length` valid pointers.
+enum QkdStatus qkd_run_key(const struct QkdRun *run, const uint8_t **key, size_t *length);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* QKD_H */
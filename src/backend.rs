@@ -0,0 +1,312 @@
+use crate::participants::{Receiver, Sender};
+use crate::protocol::QExecutionResult;
+use crate::types::{ComplexMatrix, Qubit};
+use crate::utils::{rand_float, rand_index, X};
+use clap::ValueEnum;
+use rand::RngCore;
+
+/// Simulation backend used to execute the quantum phase of a protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Backend {
+    /// Evolves the state vector of every qubit with complex matrix multiplications.
+    #[default]
+    StateVector,
+    /// Samples measurement outcomes from precomputed basis-overlap probability tables.
+    ///
+    /// Only available when every participant uses a strategy with a statistical
+    /// model. Otherwise, the state-vector backend is used instead.
+    Statistical,
+}
+
+/// Statistical model of a preparation strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatisticalPreparation {
+    /// A uniformly random bit `b` encoded as |b⟩ in a uniformly random basis.
+    RandomBit,
+    /// The |0⟩ state with bit value `false`, encoded in a uniformly random basis.
+    FixedZero,
+}
+
+/// Statistical model of a measurement strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatisticalMeasurement {
+    /// Projective measurement in a uniformly random basis, restoring the qubit
+    /// with the inverse of the basis matrix after measuring.
+    RandomBasis,
+}
+
+/// Probabilities of measuring |1⟩ for every state that can travel through the channel.
+///
+/// Every state is given by a matrix applied to |0⟩ or |1⟩, so the tables are
+/// indexed by `[source basis][bit][measurement basis]`.
+#[derive(Clone, Debug)]
+pub(crate) struct ProbabilityTables {
+    /// State prepared by Alice and measured by Eve.
+    alice_to_eve: Vec<[Vec<f64>; 2]>,
+    /// State prepared by Alice and measured by Bob.
+    alice_to_bob: Vec<[Vec<f64>; 2]>,
+    /// State restored by Eve and measured by Bob.
+    eve_to_bob: Vec<[Vec<f64>; 2]>,
+    /// Statistical model of Alice's preparation.
+    preparation: StatisticalPreparation,
+}
+
+impl ProbabilityTables {
+    /// Precomputes the tables for the given participants.
+    ///
+    /// # Returns
+    ///
+    /// `None` if any participant uses a strategy without a statistical model,
+    /// or more bases than `PackedRounds::MAX_BASIS`.
+    pub(crate) fn new(alice: &Sender, bob: &Receiver, eve: &Receiver) -> Option<Self> {
+        if [&alice.posible_basis, &bob.posible_basis, &eve.posible_basis]
+            .iter()
+            .any(|basis| basis.len() > PackedRounds::MAX_BASIS)
+        {
+            return None;
+        }
+        let preparation = alice.strategy.statistical_model()?;
+        bob.strategy.statistical_model()?;
+        eve.strategy.statistical_model()?;
+
+        let eve_restore = eve
+            .posible_basis
+            .iter()
+            .map(|matrix| matrix.invert().unwrap())
+            .collect::<Vec<ComplexMatrix>>();
+
+        Some(ProbabilityTables {
+            alice_to_eve: overlap_table(&alice.posible_basis, &eve.posible_basis),
+            alice_to_bob: overlap_table(&alice.posible_basis, &bob.posible_basis),
+            eve_to_bob: overlap_table(&eve_restore, &bob.posible_basis),
+            preparation,
+        })
+    }
+
+    /// Samples a block of up to `ROUNDS_PER_WORD` rounds of the protocol.
+    ///
+    /// Fair bits, such as Alice's random values, the bases of power-of-two
+    /// sets and the outcomes of unbiased measurements, are taken from whole
+    /// random words. Deterministic outcomes draw no random number at all.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - Number of rounds of the block.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `rng` - Random number generator used to sample the rounds.
+    ///
+    /// # Returns
+    ///
+    /// The sampled rounds, packed in a `PackedRounds` block.
+    pub(crate) fn sample(
+        &self,
+        len: usize,
+        interception_rate: f64,
+        rng: &mut dyn RngCore,
+    ) -> PackedRounds {
+        debug_assert!(len <= ROUNDS_PER_WORD);
+        let mask = u64::MAX >> (ROUNDS_PER_WORD - len);
+        let intercepted = bernoulli_word(mask, rng, |_| interception_rate);
+        let alice_bases = sample_bases(self.alice_to_bob.len(), len, rng);
+        let alice_values = match self.preparation {
+            StatisticalPreparation::RandomBit => rng.next_u64() & mask,
+            StatisticalPreparation::FixedZero => 0,
+        };
+        let alice_state = |round: usize| (alice_values >> round & 1) as usize;
+
+        let eve_bases = sample_bases(self.eve_to_bob.len(), len, rng);
+        let eve_values = bernoulli_word(intercepted, rng, |round| {
+            self.alice_to_eve[alice_bases[round] as usize][alice_state(round)]
+                [eve_bases[round] as usize]
+        });
+
+        let bob_bases = sample_bases(self.alice_to_bob[0][0].len(), len, rng);
+        let bob_values = bernoulli_word(mask, rng, |round| {
+            let bob_basis = bob_bases[round] as usize;
+            if intercepted >> round & 1 != 0 {
+                let eve_value = (eve_values >> round & 1) as usize;
+                self.eve_to_bob[eve_bases[round] as usize][eve_value][bob_basis]
+            } else {
+                self.alice_to_bob[alice_bases[round] as usize][alice_state(round)][bob_basis]
+            }
+        });
+
+        PackedRounds {
+            len,
+            alice_values,
+            bob_values,
+            intercepted,
+            eve_values,
+            detected: mask,
+            alice_bases,
+            bob_bases,
+            eve_bases,
+        }
+    }
+}
+
+/// Number of rounds packed in a `PackedRounds` block, one per bit of a `u64`.
+pub const ROUNDS_PER_WORD: usize = u64::BITS as usize;
+
+/// Outcome probabilities closer than this to 0, 0.5 or 1 are sampled as exact.
+const PROBABILITY_TOLERANCE: f64 = 1e-12;
+
+/// A block of up to `ROUNDS_PER_WORD` rounds sampled by the statistical backend.
+///
+/// Every boolean of the rounds is stored in a `u64` word, with round `i` in
+/// bit `i`, and every basis in a byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedRounds {
+    /// Number of rounds in the block.
+    len: usize,
+    /// Values prepared by Alice.
+    alice_values: u64,
+    /// Values measured by Bob.
+    bob_values: u64,
+    /// Rounds in which Eve intercepted the qubit.
+    intercepted: u64,
+    /// Values measured by Eve, only meaningful in the intercepted rounds.
+    eve_values: u64,
+    /// Rounds in which Bob's detector clicked.
+    detected: u64,
+    /// Bases chosen by Alice.
+    alice_bases: [u8; ROUNDS_PER_WORD],
+    /// Bases chosen by Bob.
+    bob_bases: [u8; ROUNDS_PER_WORD],
+    /// Bases chosen by Eve, only meaningful in the intercepted rounds.
+    eve_bases: [u8; ROUNDS_PER_WORD],
+}
+
+impl PackedRounds {
+    /// Maximum number of bases that can be packed per participant.
+    pub const MAX_BASIS: usize = 1 << u8::BITS;
+
+    /// Returns the number of rounds in the block, detected or not.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the block has no rounds.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Passes Bob's value of every round through his detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `detect` - Returns the value registered by the detector for an ideal
+    ///   value, or `None` if there was no click.
+    pub fn detect(&mut self, mut detect: impl FnMut(bool) -> Option<bool>) {
+        for round in 0..self.len {
+            let bit = 1 << round;
+            match detect(self.bob_values & bit != 0) {
+                Some(value) => {
+                    self.bob_values = self.bob_values & !bit | (value as u64) << round;
+                }
+                None => self.detected &= !bit,
+            }
+        }
+    }
+
+    /// Returns the detected rounds of the block, unpacked.
+    pub fn rounds(&self) -> impl Iterator<Item = QExecutionResult> + '_ {
+        (0..self.len)
+            .filter(|round| self.detected >> round & 1 != 0)
+            .map(|round| {
+                let bit = |word: u64| word >> round & 1 != 0;
+                let intercepted = bit(self.intercepted);
+                QExecutionResult::new(
+                    bit(self.alice_values),
+                    self.alice_bases[round] as usize,
+                    bit(self.bob_values),
+                    self.bob_bases[round] as usize,
+                    intercepted.then(|| bit(self.eve_values)),
+                    intercepted.then_some(self.eve_bases[round] as usize),
+                )
+            })
+    }
+}
+
+/// Samples one bit per round of `mask`, each set with its own probability.
+///
+/// Certain bits are set without drawing random numbers, and all the fair bits
+/// are taken from a single random word.
+fn bernoulli_word(mask: u64, rng: &mut dyn RngCore, probability: impl Fn(usize) -> f64) -> u64 {
+    let (mut word, mut fair) = (0, 0);
+    for round in 0..ROUNDS_PER_WORD {
+        let bit = 1 << round;
+        if mask & bit == 0 {
+            continue;
+        }
+        let probability = probability(round);
+        if probability >= 1.0 - PROBABILITY_TOLERANCE {
+            word |= bit;
+        } else if (probability - 0.5).abs() < PROBABILITY_TOLERANCE {
+            fair |= bit;
+        } else if probability > PROBABILITY_TOLERANCE && rand_float(rng) < probability {
+            word |= bit;
+        }
+    }
+    if fair != 0 {
+        word |= fair & rng.next_u64();
+    }
+    word
+}
+
+/// Samples `len` uniformly random bases out of `count`.
+///
+/// When `count` is a power of two, the bases are sliced from random words.
+fn sample_bases(count: usize, len: usize, rng: &mut dyn RngCore) -> [u8; ROUNDS_PER_WORD] {
+    let mut bases = [0; ROUNDS_PER_WORD];
+    if count.is_power_of_two() {
+        let bits = count.trailing_zeros();
+        if bits == 0 {
+            return bases;
+        }
+        let (mut word, mut available) = (0, 0);
+        for basis in &mut bases[..len] {
+            if available < bits {
+                word = rng.next_u64();
+                available = u64::BITS;
+            }
+            *basis = (word & (count as u64 - 1)) as u8;
+            word >>= bits;
+            available -= bits;
+        }
+    } else {
+        for basis in &mut bases[..len] {
+            *basis = rand_index(count, rng) as u8;
+        }
+    }
+    bases
+}
+
+/// Builds the table of probabilities of measuring |1⟩ in every basis of
+/// `measurement_basis`, for the states `M|0⟩` and `M|1⟩` of every `M` in `source_basis`.
+fn overlap_table(
+    source_basis: &[ComplexMatrix],
+    measurement_basis: &[ComplexMatrix],
+) -> Vec<[Vec<f64>; 2]> {
+    source_basis
+        .iter()
+        .map(|source| {
+            [false, true].map(|bit| {
+                measurement_basis
+                    .iter()
+                    .map(|measurement| {
+                        let mut qubit = Qubit::new();
+                        if bit {
+                            qubit.apply_transformation(&X);
+                        }
+                        qubit.apply_transformation(source);
+                        qubit.apply_transformation(measurement);
+                        qubit.get_one_coef().norm_sqr()
+                    })
+                    .collect()
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,198 @@
+use crate::output::{OutputRecord, ResultRecord, RunMetadata};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// State of a sweep saved after every completed configuration, so that an
+/// interrupted sweep can be resumed without simulating them again.
+///
+/// The state is written as JSON Lines: a header with the seed and configuration
+/// of the sweep, followed by one line with the results of every completed
+/// configuration. Lines are only appended, so the file stays valid if the
+/// process dies, except for a possibly truncated last line that is discarded
+/// when the checkpoint is opened.
+#[derive(Debug)]
+pub struct Checkpoint {
+    /// Seed and configuration of the sweep.
+    pub metadata: RunMetadata,
+    /// Number of runs (repetitions) of every configuration.
+    pub runs_per_configuration: usize,
+    /// Results of every completed configuration, by configuration index.
+    pub completed: BTreeMap<usize, Vec<ResultRecord>>,
+    pending: HashMap<usize, Vec<ResultRecord>>,
+    file: File,
+}
+
+impl Checkpoint {
+    /// Returns the path of the checkpoint of an output file, next to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Path of the output file of the sweep.
+    ///
+    /// # Returns
+    ///
+    /// The path of the output with `.checkpoint` appended, e.g. `results.csv.checkpoint`.
+    pub fn path_for(output: &Path) -> PathBuf {
+        let mut path = output.as_os_str().to_owned();
+        path.push(".checkpoint");
+        PathBuf::from(path)
+    }
+
+    /// Creates an empty checkpoint.
+    ///
+    /// A previous file at `path` is never replaced, since it may hold the
+    /// progress of an interrupted sweep: the caller must remove it first.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the checkpoint file.
+    /// * `metadata` - Seed and configuration of the sweep.
+    /// * `runs_per_configuration` - Number of runs of every configuration.
+    ///
+    /// # Returns
+    ///
+    /// The checkpoint, or an `AlreadyExists` error if there is a file at `path`.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        metadata: RunMetadata,
+        runs_per_configuration: usize,
+    ) -> io::Result<Self> {
+        let mut checkpoint = Checkpoint {
+            metadata,
+            runs_per_configuration,
+            completed: BTreeMap::new(),
+            pending: HashMap::new(),
+            file: OpenOptions::new().write(true).create_new(true).open(path)?,
+        };
+        checkpoint.append(&checkpoint.header())?;
+        Ok(checkpoint)
+    }
+
+    /// Opens the checkpoint of an interrupted sweep to resume it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the checkpoint file.
+    ///
+    /// # Returns
+    ///
+    /// The checkpoint with the results of its completed configurations, or an
+    /// `InvalidData` error if the file is not a checkpoint.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let content = std::fs::read_to_string(&path)?;
+        let invalid_data = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut lines = content.lines().enumerate().peekable();
+
+        let header = lines
+            .next()
+            .and_then(|(_, line)| serde_json::from_str::<Value>(line).ok())
+            .and_then(|json| json.get("checkpoint").cloned())
+            .ok_or_else(|| invalid_data("Missing checkpoint header".to_string()))?;
+        let seed = header.get("seed").and_then(Value::as_u64);
+        let runs_per_configuration = header.get("runs_per_configuration").and_then(Value::as_u64);
+        let (Some(seed), Some(runs_per_configuration)) = (seed, runs_per_configuration) else {
+            return Err(invalid_data(format!(
+                "Invalid checkpoint header {}",
+                header
+            )));
+        };
+        let mut metadata = RunMetadata::new(seed, header["configuration"].clone());
+        if let Some(version) = header.get("crate_version").and_then(Value::as_str) {
+            metadata.crate_version = version.to_string();
+        }
+
+        let mut completed = BTreeMap::new();
+        let mut length = content.len();
+        while let Some((number, line)) = lines.next() {
+            let is_truncated = lines.peek().is_none() && !content.ends_with('\n');
+            match Self::parse_configuration(line) {
+                Ok((configuration, records)) => {
+                    completed.insert(configuration, records);
+                }
+                Err(_) if is_truncated => length -= line.len(),
+                Err(message) => {
+                    return Err(invalid_data(format!("Line {}: {}", number + 1, message)))
+                }
+            }
+        }
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        file.set_len(length as u64)?;
+        Ok(Checkpoint {
+            metadata,
+            runs_per_configuration: runs_per_configuration as usize,
+            completed,
+            pending: HashMap::new(),
+            file,
+        })
+    }
+
+    /// Adds a completed run, saving its configuration once all its runs are completed.
+    ///
+    /// Runs can be added in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - Result of the run, whose id determines its configuration.
+    pub fn record(&mut self, record: &ResultRecord) -> io::Result<()> {
+        let configuration = record.id / self.runs_per_configuration.max(1);
+        let runs = self.pending.entry(configuration).or_default();
+        runs.push(record.clone());
+        if runs.len() < self.runs_per_configuration {
+            return Ok(());
+        }
+        let mut records = self.pending.remove(&configuration).unwrap_or_default();
+        records.sort_by_key(|record| record.id);
+        self.complete(configuration, records)
+    }
+
+    /// Returns the results of every completed configuration, in order of id.
+    pub fn records(&self) -> Vec<ResultRecord> {
+        self.completed.values().flatten().cloned().collect()
+    }
+
+    fn complete(&mut self, configuration: usize, records: Vec<ResultRecord>) -> io::Result<()> {
+        self.append(&json!({
+            "configuration": configuration,
+            "results": records.iter().map(OutputRecord::to_json).collect::<Vec<Value>>(),
+        }))?;
+        self.completed.insert(configuration, records);
+        Ok(())
+    }
+
+    fn header(&self) -> Value {
+        json!({
+            "checkpoint": {
+                "crate_version": self.metadata.crate_version,
+                "seed": self.metadata.seed,
+                "runs_per_configuration": self.runs_per_configuration,
+                "configuration": self.metadata.configuration,
+            }
+        })
+    }
+
+    /// Writes a line at once and flushes it to the disk.
+    fn append(&mut self, json: &Value) -> io::Result<()> {
+        self.file.write_all(format!("{}\n", json).as_bytes())?;
+        self.file.sync_data()
+    }
+
+    fn parse_configuration(line: &str) -> Result<(usize, Vec<ResultRecord>), String> {
+        let json = serde_json::from_str::<Value>(line).map_err(|error| error.to_string())?;
+        let configuration = json
+            .get("configuration")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| "Missing configuration index".to_string())?;
+        let records = json
+            .get("results")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "Missing `results` array".to_string())?
+            .iter()
+            .map(ResultRecord::from_json)
+            .collect::<Result<Vec<ResultRecord>, String>>()?;
+        Ok((configuration as usize, records))
+    }
+}
@@ -0,0 +1,1595 @@
+use crate::backend::Backend;
+use crate::checkpoint::Checkpoint;
+#[cfg(feature = "toml")]
+use crate::config::ExperimentFile;
+use crate::cvqkd::{CvParameters, GG02};
+use crate::detection::{roc_figure, DetectionAnalysis, DETECTION_TABLE_HEADER};
+use crate::explain::Explanation;
+use crate::monitor::MonitoredConfiguration;
+#[cfg(feature = "tui")]
+use crate::monitor::SweepProgress;
+use crate::output::{
+    read_records, AggregateRecord, OutputFormat, OutputRecord, ResultRecord, ResultWriter,
+    RunMetadata, AGGREGATE_TABLE_HEADER, CSV_HEADER,
+};
+use crate::physical::{Channel, PhysicalLayer};
+use crate::plot::Chart;
+use crate::postprocessing::{key_bytes, PostProcessing, SiftedKey};
+use crate::protocol::{QKDResult, QKD};
+use crate::protocols::{ProtocolRegistry, QkdProtocol};
+use crate::sweep::Sweep;
+use crate::theory::{ComparisonRecord, THEORY_TABLE_HEADER};
+use crate::transcript::Transcript;
+#[cfg(feature = "tui")]
+use crate::tui;
+use crate::utils::{derive_seed, random_seed};
+
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "tui")]
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+/// QKD Simulator CLI
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "A Quantum Key Distribution simulator developed in Rust",
+    long_about = None,
+    arg_required_else_help = true
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a single configuration or the scenarios described in an experiment file
+    Run(RunArgs),
+    /// Run every combination of protocols and parameter sweeps
+    Sweep(SweepArgs),
+    /// Estimate the probability of detecting Eve and the ROC of the abort test
+    Analyze(AnalyzeArgs),
+    /// Render SVG charts from a CSV, JSON or JSON Lines results file
+    Plot(PlotArgs),
+    /// Reconcile and amplify the sifted key of a recorded transcript
+    Postprocess(PostprocessArgs),
+    /// Simulate a protocol and write the final secret key to a file
+    Keygen(KeygenArgs),
+    /// List the registered protocols with their descriptions and parameters
+    Protocols,
+    /// Walk through a small execution round by round, for teaching purposes
+    Explain(ExplainArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// Experiment file in TOML format, used instead of the single-configuration options
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = [
+            "protocol", "number_of_qubits", "interception_rate", "distance", "repetitions",
+            "seed", "block_size", "backend", "transcript", "output", "format", "theory",
+            "excess_noise", "modulation_variance",
+        ]
+    )]
+    config: Option<PathBuf>,
+
+    /// Name of protocol to simulate (registered)
+    #[arg(short, long, required_unless_present = "config")]
+    protocol: Option<String>,
+
+    /// Number of qubits to send
+    #[arg(short, long, default_value_t = 1000)]
+    number_of_qubits: usize,
+
+    /// Rate of intercepted qubits by Eve
+    #[arg(short, long, default_value_t = 0.0, value_parser = parse_probability)]
+    interception_rate: f64,
+
+    /// Length in kilometers of the fiber between Alice and Bob
+    #[arg(short, long, value_parser = parse_distance)]
+    distance: Option<f64>,
+
+    #[command(flatten)]
+    simulation: SimulationArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    statistics: StatisticsArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct SweepArgs {
+    /// Names of protocols to simulate (registered)
+    #[arg(short, long, required = true, num_args = 1..)]
+    protocol: Vec<String>,
+
+    /// Number of qubits to send (values, `start:end:step`, `linspace(..)` or `logspace(..)`)
+    #[arg(short, long, default_value = "1000", num_args = 1..)]
+    number_of_qubits: Vec<Sweep<usize>>,
+
+    /// Rate of intercepted qubits by Eve (values, `start:end:step`, `linspace(..)` or `logspace(..)`)
+    #[arg(short, long, default_value = "0.0", num_args = 1.., value_parser = parse_rates)]
+    interception_rate: Vec<Sweep<f64>>,
+
+    /// Length in kilometers of the fiber between Alice and Bob (values or sweep expressions)
+    #[arg(short, long, num_args = 1.., value_parser = parse_distances)]
+    distance: Vec<Sweep<f64>>,
+
+    /// Show the live progress in a terminal dashboard, which can be cancelled
+    #[arg(long, default_value_t = false)]
+    tui: bool,
+
+    /// Skip the configurations completed by a previous run of the same sweep, saved next to `--output`
+    #[arg(long, default_value_t = false, requires = "output")]
+    resume: bool,
+
+    /// Start over, replacing the checkpoint of a previous run of a sweep saved next to `--output`
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "output",
+        conflicts_with = "resume"
+    )]
+    force: bool,
+
+    #[command(flatten)]
+    simulation: SimulationArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    statistics: StatisticsArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct SimulationArgs {
+    /// Number of repetitions by experiment
+    #[arg(short, long, default_value_t = 1)]
+    repetitions: usize,
+
+    /// Base seed of the simulation (random if omitted)
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Process qubits in blocks of this size to bound memory usage
+    #[arg(short, long)]
+    block_size: Option<usize>,
+
+    /// Backend used to simulate the quantum phase
+    #[arg(long, value_enum, default_value_t = Backend::StateVector)]
+    backend: Backend,
+
+    /// Directory where the transcript of every experiment is written
+    #[arg(long, conflicts_with = "block_size")]
+    transcript: Option<PathBuf>,
+
+    /// Number of worker threads (0 uses all available cores)
+    #[arg(short, long, default_value_t = 0)]
+    threads: usize,
+
+    /// Excess noise of the channel in shot-noise units, for continuous-variable protocols (values or sweep expressions)
+    #[arg(long, default_value = "0", num_args = 1.., value_parser = parse_variances)]
+    excess_noise: Vec<Sweep<f64>>,
+
+    /// Variance of Alice's Gaussian modulation in shot-noise units, for continuous-variable protocols (values or sweep expressions)
+    #[arg(long, default_value = "4", num_args = 1.., value_parser = parse_variances)]
+    modulation_variance: Vec<Sweep<f64>>,
+}
+
+#[derive(clap::Args, Debug)]
+struct OutputArgs {
+    /// Do not print results
+    #[arg(short, long, default_value_t = false)]
+    quiet: bool,
+
+    /// Output file path
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Format of the output file
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group = ArgGroup::new("statistics").args(["aggregate", "theory"]))]
+struct StatisticsArgs {
+    /// Report statistics of the repetitions of every configuration instead of every run
+    #[arg(long, default_value_t = false)]
+    aggregate: bool,
+
+    /// Compare every metric with the analytic prediction of its protocol and flag significant deviations
+    #[arg(long, default_value_t = false)]
+    theory: bool,
+
+    /// Confidence level of the aggregated or theoretical comparison intervals
+    #[arg(long, default_value_t = 0.95, value_parser = parse_confidence, requires = "statistics")]
+    confidence: f64,
+}
+
+#[derive(clap::Args, Debug)]
+struct PlotArgs {
+    /// Results file written by the simulator
+    #[arg(short, long)]
+    input: PathBuf,
+
+    /// Directory where the charts are written
+    #[arg(short, long, default_value = ".")]
+    output: PathBuf,
+
+    /// Charts to render (all charts with data if omitted)
+    #[arg(short, long, value_enum, num_args = 1..)]
+    chart: Vec<Chart>,
+
+    /// Confidence level of the error bars
+    #[arg(long, default_value_t = 0.95, value_parser = parse_confidence)]
+    confidence: f64,
+}
+
+#[derive(clap::Args, Debug)]
+struct AnalyzeArgs {
+    /// Name of protocol to analyze
+    #[arg(short, long, required = true, num_args = 1..)]
+    protocol: Vec<String>,
+
+    /// Rate of intercepted qubits by Eve (values or sweep expressions)
+    #[arg(short, long, default_value = "0.05", num_args = 1.., value_parser = parse_rates)]
+    interception_rate: Vec<Sweep<f64>>,
+
+    /// Number of check bits compared publicly (values or sweep expressions)
+    #[arg(short = 'k', long, default_value = "100", num_args = 1..)]
+    sample_size: Vec<Sweep<usize>>,
+
+    /// Maximum fraction of mismatched check bits tolerated (values or sweep expressions)
+    #[arg(long, default_value = "0:0.2:0.01", num_args = 1.., value_parser = parse_rates)]
+    threshold: Vec<Sweep<f64>>,
+
+    /// Depolarization probability of the channel, the honest noise causing false aborts (values or sweep expressions)
+    #[arg(long, default_value = "0", num_args = 1.., value_parser = parse_rates)]
+    noise: Vec<Sweep<f64>>,
+
+    /// Number of trials per interception rate
+    #[arg(short, long, default_value_t = 1000)]
+    repetitions: usize,
+
+    /// Base seed of the simulation (random if omitted)
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Backend used to simulate the quantum phase
+    #[arg(long, value_enum, default_value_t = Backend::Statistical)]
+    backend: Backend,
+
+    /// Confidence level of the intervals
+    #[arg(long, default_value_t = 0.95, value_parser = parse_confidence)]
+    confidence: f64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// SVG file where the ROC curves are written
+    #[arg(long)]
+    roc: Option<PathBuf>,
+
+    /// Number of worker threads (0 uses all available cores)
+    #[arg(short, long, default_value_t = 0)]
+    threads: usize,
+}
+
+#[derive(clap::Args, Debug)]
+struct PostprocessArgs {
+    /// Transcript file written by `run` or `sweep` with `--transcript`
+    #[arg(long)]
+    transcript: PathBuf,
+
+    /// File where the final key is written
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct KeygenArgs {
+    /// Name of protocol to simulate
+    #[arg(short, long)]
+    protocol: String,
+
+    /// Number of qubits to send
+    #[arg(short, long, default_value_t = 10000)]
+    number_of_qubits: usize,
+
+    /// Rate of intercepted qubits by Eve
+    #[arg(short, long, default_value_t = 0.0, value_parser = parse_probability)]
+    interception_rate: f64,
+
+    /// Length in kilometers of the fiber between Alice and Bob
+    #[arg(short, long, value_parser = parse_distance)]
+    distance: Option<f64>,
+
+    /// Base seed of the simulation (random if omitted)
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Backend used to simulate the quantum phase
+    #[arg(long, value_enum, default_value_t = Backend::StateVector)]
+    backend: Backend,
+
+    /// File where the final key is written
+    #[arg(short, long)]
+    output: PathBuf,
+
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct ExplainArgs {
+    /// Name of protocol to explain
+    #[arg(short, long)]
+    protocol: String,
+
+    /// Number of qubits to send
+    #[arg(short, long, default_value_t = 12)]
+    number_of_qubits: usize,
+
+    /// Rate of intercepted qubits by Eve
+    #[arg(short, long, default_value_t = 0.0, value_parser = parse_probability)]
+    interception_rate: f64,
+
+    /// Base seed of the simulation (random if omitted)
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Maximum fraction of mismatched check bits tolerated (the protocol default if omitted)
+    #[arg(long, value_parser = parse_probability)]
+    qber_threshold: Option<f64>,
+
+    /// Wait for Enter after every step
+    #[arg(long, default_value_t = false)]
+    step: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct KeyArgs {
+    /// Maximum fraction of mismatched check bits tolerated (the protocol default if omitted)
+    #[arg(long, value_parser = parse_probability)]
+    qber_threshold: Option<f64>,
+
+    /// Number of passes of the error reconciliation
+    #[arg(long, default_value_t = 4)]
+    passes: usize,
+
+    /// Failure probability of the privacy amplification
+    #[arg(long, default_value_t = 1e-10, value_parser = parse_epsilon)]
+    epsilon: f64,
+
+    /// Encoding of the key file (whole bytes, so trailing bits are dropped)
+    #[arg(short, long, value_enum, default_value_t = KeyEncoding::Hex)]
+    encoding: KeyEncoding,
+
+    /// Do not print the summary of the post-processing
+    #[arg(short, long, default_value_t = false)]
+    quiet: bool,
+}
+
+/// Encodings of the key files.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum KeyEncoding {
+    /// Hexadecimal digits followed by a newline
+    Hex,
+    /// Raw bytes
+    Binary,
+}
+
+/// Values of every parameter swept by a simulation.
+struct Grid {
+    protocols: Vec<String>,
+    number_of_qubits: Vec<usize>,
+    interception_rates: Vec<f64>,
+    distances: Vec<Option<f64>>,
+    /// Only swept for continuous-variable protocols.
+    excess_noises: Vec<f64>,
+    /// Only swept for continuous-variable protocols.
+    modulation_variances: Vec<f64>,
+}
+
+impl Grid {
+    /// Builds the grid of the given values, with the parameters of
+    /// continuous-variable protocols taken from the simulation arguments.
+    fn new(
+        protocols: Vec<String>,
+        number_of_qubits: Vec<usize>,
+        interception_rates: Vec<f64>,
+        distances: Vec<Option<f64>>,
+        args: &SimulationArgs,
+    ) -> Self {
+        Grid {
+            protocols,
+            number_of_qubits,
+            interception_rates,
+            distances,
+            excess_noises: Sweep::concat(&args.excess_noise),
+            modulation_variances: Sweep::concat(&args.modulation_variance),
+        }
+    }
+
+    /// Returns the parameters swept for a protocol: every combination of
+    /// excess noise and modulation variance for a continuous-variable
+    /// protocol, and a single `None` otherwise.
+    fn parameters(&self, is_continuous: bool) -> Vec<Option<CvParameters>> {
+        if !is_continuous {
+            return vec![None];
+        }
+        self.excess_noises
+            .iter()
+            .flat_map(|&excess_noise| {
+                self.modulation_variances
+                    .iter()
+                    .map(move |&modulation_variance| {
+                        Some(CvParameters {
+                            excess_noise,
+                            modulation_variance,
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns every configuration of the grid, in the order they are simulated.
+    fn configurations(
+        &self,
+        repetitions: usize,
+        registry: &ProtocolRegistry,
+    ) -> Vec<MonitoredConfiguration> {
+        let mut configurations = Vec::new();
+        for protocol in &self.protocols {
+            let parameters = self.parameters(registry.get_continuous(protocol).is_some());
+            for &number_of_qubits in &self.number_of_qubits {
+                for &interception_rate in &self.interception_rates {
+                    for &distance_km in &self.distances {
+                        for &parameters in &parameters {
+                            configurations.push(MonitoredConfiguration {
+                                protocol: protocol.clone(),
+                                number_of_qubits,
+                                interception_rate,
+                                distance_km,
+                                parameters,
+                                runs: repetitions,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        configurations
+    }
+
+    /// Returns the parameters that determine the results of a simulation of the grid.
+    fn configuration(&self, args: &SimulationArgs, registry: &ProtocolRegistry) -> Value {
+        let mut configuration = json!({
+            "protocol": self.protocols,
+            "number_of_qubits": self.number_of_qubits,
+            "interception_rate": self.interception_rates,
+            "distance_km": self.distances.iter().flatten().collect::<Vec<&f64>>(),
+            "repetitions": args.repetitions,
+            "backend": args.backend.to_possible_value().map(|v| v.get_name().to_string()),
+            "block_size": args.block_size,
+        });
+        if self
+            .protocols
+            .iter()
+            .any(|tag| registry.get_continuous(tag).is_some())
+        {
+            configuration["excess_noise"] = json!(self.excess_noises);
+            configuration["modulation_variance"] = json!(self.modulation_variances);
+        }
+        configuration
+    }
+}
+
+impl OutputArgs {
+    /// Returns the output files, checking that there is at least one when quiet.
+    fn outputs(&self) -> Result<Vec<(PathBuf, OutputFormat)>, String> {
+        if self.quiet && self.output.is_none() {
+            return Err("The `--output` argument is required when `--quiet` is enabled.".into());
+        }
+        Ok(self
+            .output
+            .iter()
+            .map(|path| (path.clone(), self.format))
+            .collect())
+    }
+}
+
+/// Metadata, results and output files of a run.
+type RunOutcome = (RunMetadata, Vec<ResultRecord>, Vec<(PathBuf, OutputFormat)>);
+
+fn get_protocol(name: &str, registry: &ProtocolRegistry) -> Result<Arc<dyn QkdProtocol>, String> {
+    if registry.get_continuous(name).is_some() {
+        return Err(format!(
+            "`{}` is a continuous-variable protocol, which is only supported by `run` and `sweep`",
+            name
+        ));
+    }
+    registry
+        .get(name)
+        .ok_or_else(|| unregistered_protocol(name, registry))
+}
+
+fn unregistered_protocol(name: &str, registry: &ProtocolRegistry) -> String {
+    format!(
+        "`{}` is not a registered protocol. Registered protocols are: {:?}",
+        name,
+        registry
+            .names()
+            .into_iter()
+            .chain(registry.continuous_names())
+            .collect::<Vec<&str>>()
+    )
+}
+
+fn parse_rates(s: &str) -> Result<Sweep<f64>, String> {
+    let rates = s.parse::<Sweep<f64>>()?;
+    if rates.0.iter().all(|rate| (0.0..=1.0).contains(rate)) {
+        Ok(rates)
+    } else {
+        Err("All rates must be between 0.0 and 1.0".to_string())
+    }
+}
+
+fn parse_probability(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(probability) if (0.0..=1.0).contains(&probability) => Ok(probability),
+        _ => Err("The probability must be between 0.0 and 1.0".to_string()),
+    }
+}
+
+fn parse_distance(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(distance) if distance >= 0.0 => Ok(distance),
+        _ => Err("The distance must be a non-negative number".to_string()),
+    }
+}
+
+fn parse_distances(s: &str) -> Result<Sweep<f64>, String> {
+    let distances = s.parse::<Sweep<f64>>()?;
+    if distances.0.iter().all(|distance| *distance >= 0.0) {
+        Ok(distances)
+    } else {
+        Err("All distances must be non-negative".to_string())
+    }
+}
+
+fn parse_variances(s: &str) -> Result<Sweep<f64>, String> {
+    let variances = s.parse::<Sweep<f64>>()?;
+    if variances.0.iter().all(|variance| *variance >= 0.0) {
+        Ok(variances)
+    } else {
+        Err("All variances must be non-negative".to_string())
+    }
+}
+
+fn parse_confidence(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(confidence) if confidence > 0.0 && confidence < 1.0 => Ok(confidence),
+        _ => Err("The confidence level must be between 0.0 and 1.0 (exclusive)".to_string()),
+    }
+}
+
+fn parse_epsilon(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(epsilon) if epsilon > 0.0 && epsilon < 1.0 => Ok(epsilon),
+        _ => Err("The failure probability must be between 0.0 and 1.0 (exclusive)".to_string()),
+    }
+}
+
+fn init_thread_pool(threads: usize) -> Result<(), String> {
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|error| format!("Could not initialize the thread pool: {}", error))
+}
+
+fn fiber(distance_km: f64) -> PhysicalLayer {
+    PhysicalLayer {
+        channel: Channel::fiber(distance_km),
+        ..PhysicalLayer::default()
+    }
+}
+
+/// Protocol simulated by `simulate`: a registered discrete-variable or
+/// continuous-variable protocol.
+enum SimulatedProtocol {
+    Discrete(Arc<dyn QkdProtocol>),
+    Continuous(GG02),
+}
+
+impl SimulatedProtocol {
+    /// Returns the protocol named `tag`.
+    ///
+    /// Options that only apply to discrete-variable protocols are rejected for
+    /// continuous-variable protocols, whose parameters are set for every
+    /// configuration of the grid.
+    fn resolve(
+        tag: &str,
+        args: &SimulationArgs,
+        registry: &ProtocolRegistry,
+    ) -> Result<Self, String> {
+        if let Some(protocol) = registry.get(tag) {
+            return Ok(SimulatedProtocol::Discrete(protocol));
+        }
+        let Some(protocol) = registry.get_continuous(tag) else {
+            return Err(unregistered_protocol(tag, registry));
+        };
+        let unsupported = [
+            ("--transcript", args.transcript.is_some()),
+            ("--block-size", args.block_size.is_some()),
+            ("--backend", args.backend != Backend::StateVector),
+        ];
+        if let Some((option, _)) = unsupported.iter().find(|(_, used)| *used) {
+            return Err(format!("`{}` cannot be used with `{}`", option, tag));
+        }
+        Ok(SimulatedProtocol::Continuous(protocol.build()))
+    }
+}
+
+/// Simulates every configuration of the grid, calling `on_record` after every
+/// run. Runs that have not started when `cancelled` is set are skipped, as
+/// well as the configurations with `previous` results, which are returned
+/// together with the new ones in order of id.
+fn simulate(
+    grid: &Grid,
+    args: &SimulationArgs,
+    registry: &ProtocolRegistry,
+    base_seed: u64,
+    previous: &[ResultRecord],
+    cancelled: &AtomicBool,
+    on_record: &(dyn Fn(&ResultRecord) -> Result<(), String> + Sync),
+) -> Result<(RunMetadata, Vec<ResultRecord>), String> {
+    let protocols = grid
+        .protocols
+        .iter()
+        .map(|tag| Ok((tag, SimulatedProtocol::resolve(tag, args, registry)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    if let Some(directory) = &args.transcript {
+        std::fs::create_dir_all(directory).map_err(|error| {
+            format!(
+                "Could not create directory `{}`: {}",
+                directory.display(),
+                error
+            )
+        })?;
+    }
+
+    let completed = previous
+        .iter()
+        .map(|record| record.id / args.repetitions.max(1))
+        .collect::<HashSet<usize>>();
+    let mut experiments = Vec::new();
+    let mut next_id = 0;
+    for (protocol_tag, protocol) in &protocols {
+        let parameters = grid.parameters(matches!(protocol, SimulatedProtocol::Continuous(_)));
+        for &n_qubits in &grid.number_of_qubits {
+            for &interception_rate in &grid.interception_rates {
+                for &distance_km in &grid.distances {
+                    for &parameters in &parameters {
+                        for _ in 0..args.repetitions {
+                            let id = next_id;
+                            next_id += 1;
+                            if completed.contains(&(id / args.repetitions)) {
+                                continue;
+                            }
+                            experiments.push((
+                                id,
+                                protocol_tag,
+                                protocol,
+                                n_qubits,
+                                interception_rate,
+                                distance_km,
+                                parameters,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let records = experiments
+        .par_iter()
+        .map(
+            |&(
+                id,
+                protocol_tag,
+                protocol,
+                n_qubits,
+                interception_rate,
+                distance_km,
+                parameters,
+            )| {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Ok(None);
+                }
+                let seed = derive_seed(base_seed, id as u64);
+                let (result, estimate) = match protocol {
+                    SimulatedProtocol::Continuous(gg02) => {
+                        let mut gg02 = gg02.clone();
+                        if let Some(parameters) = parameters {
+                            gg02 = gg02
+                                .with_excess_noise(parameters.excess_noise)
+                                .with_modulation_variance(parameters.modulation_variance);
+                        }
+                        if let Some(distance_km) = distance_km {
+                            gg02 = gg02.with_physical_layer(fiber(distance_km));
+                        }
+                        let result = gg02.run_with_seed(n_qubits, interception_rate, seed);
+                        let estimate = result.estimate;
+                        (result.into(), estimate)
+                    }
+                    SimulatedProtocol::Discrete(protocol) => {
+                        let mut qkd = protocol.build().with_backend(args.backend);
+                        if let Some(distance_km) = distance_km {
+                            qkd = qkd.with_physical_layer(fiber(distance_km));
+                        }
+                        let result = if let Some(directory) = &args.transcript {
+                            let (result, transcript) = Transcript::record(
+                                protocol_tag,
+                                &qkd,
+                                n_qubits,
+                                interception_rate,
+                                seed,
+                            );
+                            let path = directory.join(format!("transcript_{}.csv", id));
+                            transcript.write_to_path(&path).map_err(|error| {
+                                format!(
+                                    "Could not write transcript `{}`: {}",
+                                    path.display(),
+                                    error
+                                )
+                            })?;
+                            result
+                        } else {
+                            match args.block_size {
+                                Some(block_size) => qkd.run_streaming_with_seed(
+                                    n_qubits,
+                                    interception_rate,
+                                    seed,
+                                    block_size,
+                                ),
+                                None => qkd.run_with_seed(n_qubits, interception_rate, seed),
+                            }
+                        };
+                        (result, None)
+                    }
+                };
+                let record = ResultRecord {
+                    id,
+                    scenario: None,
+                    protocol: protocol_tag.to_string(),
+                    number_of_qubits: n_qubits,
+                    interception_rate,
+                    distance_km,
+                    parameters,
+                    result,
+                    estimate,
+                };
+                on_record(&record)?;
+                Ok(Some(record))
+            },
+        )
+        .collect::<Result<Vec<Option<ResultRecord>>, String>>()?;
+    let mut records = previous
+        .iter()
+        .cloned()
+        .chain(records.into_iter().flatten())
+        .collect::<Vec<ResultRecord>>();
+    records.sort_by_key(|record| record.id);
+
+    let mut configuration = grid.configuration(args, registry);
+    configuration["transcript"] = json!(args.transcript);
+    configuration["threads"] = json!(args.threads);
+    Ok((RunMetadata::new(base_seed, configuration), records))
+}
+
+#[cfg(feature = "toml")]
+fn run_experiment_file(
+    path: &Path,
+    quiet: bool,
+    registry: &ProtocolRegistry,
+) -> Result<RunOutcome, String> {
+    let file = ExperimentFile::read_from_path(path, registry)?;
+    if quiet && file.outputs.is_empty() {
+        return Err("At least one `[[output]]` is required when `--quiet` is enabled.".into());
+    }
+
+    let registry = file.registry(registry);
+    let base_seed = file.seed.unwrap_or_else(random_seed);
+    let experiments = file
+        .scenarios
+        .iter()
+        .flat_map(|scenario| std::iter::repeat_n(scenario, scenario.repetitions))
+        .enumerate()
+        .collect::<Vec<_>>();
+
+    let records = experiments
+        .par_iter()
+        .map(|&(id, scenario)| {
+            let seed = derive_seed(base_seed, id as u64);
+            let (result, parameters, estimate) = if scenario.is_continuous(&registry) {
+                let gg02 = scenario.build_continuous(&registry)?;
+                let result = gg02.run_with_seed(
+                    scenario.number_of_qubits,
+                    scenario.eavesdropper.interception_rate,
+                    seed,
+                );
+                let estimate = result.estimate;
+                (result.into(), Some(gg02.parameters()), estimate)
+            } else {
+                (scenario.run_with_seed(&registry, seed)?, None, None)
+            };
+            Ok(ResultRecord {
+                id,
+                scenario: Some(scenario.name.clone()),
+                protocol: scenario.protocol.clone(),
+                number_of_qubits: scenario.number_of_qubits,
+                interception_rate: scenario.eavesdropper.interception_rate,
+                distance_km: Some(scenario.channel.distance_km),
+                parameters,
+                result,
+                estimate,
+            })
+        })
+        .collect::<Result<Vec<ResultRecord>, String>>()?;
+
+    let configuration = serde_json::to_value(&file).map_err(|error| error.to_string())?;
+    let outputs = file
+        .outputs
+        .into_iter()
+        .map(|output| (output.path, output.format))
+        .collect();
+    Ok((RunMetadata::new(base_seed, configuration), records, outputs))
+}
+
+#[cfg(not(feature = "toml"))]
+fn run_experiment_file(_: &Path, _: bool, _: &ProtocolRegistry) -> Result<RunOutcome, String> {
+    Err("Experiment files require the `toml` feature".to_string())
+}
+
+fn run_command(args: &RunArgs, registry: &ProtocolRegistry) -> Result<(), String> {
+    init_thread_pool(args.simulation.threads)?;
+    let (metadata, records, outputs) = match (&args.config, &args.protocol) {
+        (Some(path), _) => run_experiment_file(path, args.output.quiet, registry)?,
+        (None, protocol) => {
+            let outputs = args.output.outputs()?;
+            let grid = Grid::new(
+                protocol.iter().cloned().collect(),
+                vec![args.number_of_qubits],
+                vec![args.interception_rate],
+                vec![args.distance],
+                &args.simulation,
+            );
+            let (metadata, records) = simulate(
+                &grid,
+                &args.simulation,
+                registry,
+                args.simulation.seed.unwrap_or_else(random_seed),
+                &[],
+                &AtomicBool::new(false),
+                &|_| Ok(()),
+            )?;
+            (metadata, records, outputs)
+        }
+    };
+    report(
+        metadata,
+        &records,
+        &outputs,
+        &args.statistics,
+        args.output.quiet,
+        registry,
+    )
+}
+
+fn sweep(args: &SweepArgs, registry: &ProtocolRegistry) -> Result<(), String> {
+    let outputs = args.output.outputs()?;
+    init_thread_pool(args.simulation.threads)?;
+    let grid = Grid::new(
+        args.protocol.clone(),
+        Sweep::concat(&args.number_of_qubits),
+        Sweep::concat(&args.interception_rate),
+        match Sweep::concat(&args.distance) {
+            distances if distances.is_empty() => vec![None],
+            distances => distances.into_iter().map(Some).collect(),
+        },
+        &args.simulation,
+    );
+    for tag in &grid.protocols {
+        SimulatedProtocol::resolve(tag, &args.simulation, registry)?;
+    }
+    let path = args.output.output.as_deref().map(Checkpoint::path_for);
+    let checkpoint = path
+        .as_deref()
+        .map(|path| open_checkpoint(path, &grid, args, registry))
+        .transpose()?;
+    let base_seed = match &checkpoint {
+        Some(checkpoint) => checkpoint.metadata.seed,
+        None => args.simulation.seed.unwrap_or_else(random_seed),
+    };
+    let previous = checkpoint
+        .as_ref()
+        .map(Checkpoint::records)
+        .unwrap_or_default();
+    let checkpoint = checkpoint.map(Mutex::new);
+    let save = |record: &ResultRecord| match (&checkpoint, &path) {
+        (Some(checkpoint), Some(path)) => checkpoint
+            .lock()
+            .map_err(|_| "The checkpoint is poisoned".to_string())?
+            .record(record)
+            .map_err(|error| format!("Could not write checkpoint `{}`: {}", path.display(), error)),
+        _ => Ok(()),
+    };
+
+    let (mut metadata, records, cancelled) = if args.tui {
+        simulate_with_dashboard(
+            &grid,
+            &args.simulation,
+            registry,
+            base_seed,
+            &previous,
+            &save,
+        )?
+    } else {
+        let (metadata, records) = simulate(
+            &grid,
+            &args.simulation,
+            registry,
+            base_seed,
+            &previous,
+            &AtomicBool::new(false),
+            &save,
+        )?;
+        (metadata, records, false)
+    };
+    if cancelled {
+        metadata.configuration["cancelled"] = json!(true);
+    }
+    report(
+        metadata,
+        &records,
+        &outputs,
+        &args.statistics,
+        args.output.quiet,
+        registry,
+    )?;
+    if cancelled {
+        return Err(format!(
+            "The sweep was cancelled after {} of {} runs{}",
+            records.len(),
+            grid.configurations(args.simulation.repetitions, registry)
+                .len()
+                * args.simulation.repetitions,
+            if path.is_some() {
+                ", continue it with `--resume`"
+            } else {
+                ""
+            }
+        ));
+    }
+    if let Some(path) = path {
+        std::fs::remove_file(&path).map_err(|error| {
+            format!(
+                "Could not remove checkpoint `{}`: {}",
+                path.display(),
+                error
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Creates the checkpoint of a sweep, or opens it to resume the sweep after
+/// checking that it was created with the same configuration and seed.
+///
+/// An existing checkpoint is only replaced with `--force`.
+fn open_checkpoint(
+    path: &Path,
+    grid: &Grid,
+    args: &SweepArgs,
+    registry: &ProtocolRegistry,
+) -> Result<Checkpoint, String> {
+    let simulation = &args.simulation;
+    let configuration = grid.configuration(simulation, registry);
+    if !args.resume {
+        if args.force {
+            match std::fs::remove_file(path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    return Err(format!(
+                        "Could not remove checkpoint `{}`: {}",
+                        path.display(),
+                        error
+                    ))
+                }
+                _ => {}
+            }
+        }
+        let metadata = RunMetadata::new(simulation.seed.unwrap_or_else(random_seed), configuration);
+        return match Checkpoint::create(path, metadata, simulation.repetitions) {
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => Err(format!(
+                "The checkpoint `{}` of a previous sweep exists, \
+                 continue it with `--resume` or replace it with `--force`",
+                path.display()
+            )),
+            result => result.map_err(|error| {
+                format!(
+                    "Could not create checkpoint `{}`: {}",
+                    path.display(),
+                    error
+                )
+            }),
+        };
+    }
+
+    let checkpoint = Checkpoint::open(path)
+        .map_err(|error| format!("Could not resume from `{}`: {}", path.display(), error))?;
+    if checkpoint.metadata.configuration != configuration {
+        return Err(format!(
+            "The checkpoint `{}` was created by a sweep with a different configuration: {}",
+            path.display(),
+            checkpoint.metadata.configuration
+        ));
+    }
+    if simulation
+        .seed
+        .is_some_and(|seed| seed != checkpoint.metadata.seed)
+    {
+        return Err(format!(
+            "The checkpoint `{}` was created with seed {}",
+            path.display(),
+            checkpoint.metadata.seed
+        ));
+    }
+    Ok(checkpoint)
+}
+
+/// Simulates the grid while showing its progress in the terminal dashboard.
+///
+/// # Returns
+///
+/// The metadata and the records of the completed runs, and whether the sweep was cancelled.
+#[cfg(feature = "tui")]
+fn simulate_with_dashboard(
+    grid: &Grid,
+    args: &SimulationArgs,
+    registry: &ProtocolRegistry,
+    base_seed: u64,
+    previous: &[ResultRecord],
+    on_record: &(dyn Fn(&ResultRecord) -> Result<(), String> + Sync),
+) -> Result<(RunMetadata, Vec<ResultRecord>, bool), String> {
+    let mut progress = SweepProgress::new(grid.configurations(args.repetitions, registry));
+    let repetitions = args.repetitions.max(1);
+    for record in previous {
+        progress.record(record.id / repetitions, record);
+    }
+    let cancelled = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+
+    let (simulation, dashboard) = std::thread::scope(|scope| {
+        let cancelled = &cancelled;
+        let simulation = scope.spawn(move || {
+            simulate(
+                grid,
+                args,
+                registry,
+                base_seed,
+                previous,
+                cancelled,
+                &|record| {
+                    // The dashboard only stops receiving if it failed, which cancels the sweep.
+                    let _ = sender.send((record.id / repetitions, record.clone()));
+                    on_record(record)
+                },
+            )
+        });
+        let dashboard = tui::run_dashboard(progress, receiver, cancelled);
+        if dashboard.is_err() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        (simulation.join(), dashboard)
+    });
+    dashboard.map_err(|error| format!("Could not run the dashboard: {}", error))?;
+    let (metadata, records) =
+        simulation.map_err(|_| "The simulation thread panicked".to_string())??;
+    Ok((metadata, records, cancelled.into_inner()))
+}
+
+#[cfg(not(feature = "tui"))]
+fn simulate_with_dashboard(
+    _: &Grid,
+    _: &SimulationArgs,
+    _: &ProtocolRegistry,
+    _: u64,
+    _: &[ResultRecord],
+    _: &(dyn Fn(&ResultRecord) -> Result<(), String> + Sync),
+) -> Result<(RunMetadata, Vec<ResultRecord>, bool), String> {
+    Err("The `--tui` flag requires the `tui` feature".to_string())
+}
+
+/// Prints and writes the results of a simulation, either per run, aggregated
+/// or compared with the theoretical predictions.
+fn report(
+    mut metadata: RunMetadata,
+    records: &[ResultRecord],
+    outputs: &[(PathBuf, OutputFormat)],
+    statistics: &StatisticsArgs,
+    quiet: bool,
+    registry: &ProtocolRegistry,
+) -> Result<(), String> {
+    if statistics.theory {
+        metadata.configuration["confidence"] = json!(statistics.confidence);
+        let comparisons = ComparisonRecord::compare(records, registry, statistics.confidence);
+        if comparisons.is_empty() {
+            return Err("None of the protocols has a theoretical prediction".to_string());
+        }
+        if !quiet {
+            let rows = comparisons
+                .iter()
+                .map(|comparison| comparison.to_table_row().to_vec())
+                .collect::<Vec<Vec<String>>>();
+            print_table(&THEORY_TABLE_HEADER, &rows);
+        }
+        for (path, format) in outputs {
+            write_results(path, *format, &metadata, &comparisons)?;
+        }
+        return Ok(());
+    }
+
+    if statistics.aggregate {
+        metadata.configuration["confidence"] = json!(statistics.confidence);
+        let aggregates = AggregateRecord::group(records, statistics.confidence);
+        if !quiet {
+            let rows = aggregates
+                .iter()
+                .map(|aggregate| aggregate.to_table_row().to_vec())
+                .collect::<Vec<Vec<String>>>();
+            print_table(&AGGREGATE_TABLE_HEADER, &rows);
+        }
+        for (path, format) in outputs {
+            write_results(path, *format, &metadata, &aggregates)?;
+        }
+        return Ok(());
+    }
+
+    if !quiet {
+        let rows = records
+            .iter()
+            .map(OutputRecord::to_csv_row)
+            .collect::<Vec<Vec<String>>>();
+        print_table(&CSV_HEADER, &rows);
+    }
+    for (path, format) in outputs {
+        write_results(path, *format, &metadata, records)?;
+    }
+    Ok(())
+}
+
+fn plot(args: &PlotArgs) -> Result<(), String> {
+    let records = read_records(&args.input)
+        .map_err(|error| format!("Could not read `{}`: {}", args.input.display(), error))?;
+    let charts = if args.chart.is_empty() {
+        Chart::ALL.to_vec()
+    } else {
+        args.chart.clone()
+    };
+    std::fs::create_dir_all(&args.output)
+        .map_err(|error| format!("Could not create `{}`: {}", args.output.display(), error))?;
+
+    for chart in charts {
+        let Some(figure) = chart.figure(&records, args.confidence) else {
+            let message = format!(
+                "`{}` has no data for the `{}` chart",
+                args.input.display(),
+                chart.name()
+            );
+            if args.chart.is_empty() {
+                eprintln!("Warning: {}", message);
+                continue;
+            }
+            return Err(message);
+        };
+        let path = args.output.join(format!("{}.svg", chart.name()));
+        std::fs::write(&path, figure.to_svg())
+            .map_err(|error| format!("Could not write `{}`: {}", path.display(), error))?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn analyze(args: &AnalyzeArgs, registry: &ProtocolRegistry) -> Result<(), String> {
+    if args.output.quiet && args.output.output.is_none() && args.roc.is_none() {
+        return Err(
+            "The `--output` or `--roc` argument is required when `--quiet` is enabled.".into(),
+        );
+    }
+    let protocols = args
+        .protocol
+        .iter()
+        .map(|tag| get_protocol(tag, registry))
+        .collect::<Result<Vec<_>, String>>()?;
+    init_thread_pool(args.threads)?;
+
+    let seed = args.seed.unwrap_or_else(random_seed);
+    let analysis = DetectionAnalysis::builder()
+        .interception_rates(Sweep::concat(&args.interception_rate))
+        .sample_sizes(Sweep::concat(&args.sample_size))
+        .thresholds(Sweep::concat(&args.threshold))
+        .trials(args.repetitions.max(1))
+        .confidence(args.confidence)
+        .build();
+    let protocols = args
+        .protocol
+        .iter()
+        .zip(&protocols)
+        .map(|(tag, protocol)| {
+            let protocol = protocol.build().with_backend(args.backend);
+            DetectionAnalysis::check_protocol(tag, &protocol).map(|_| protocol)
+        })
+        .collect::<Result<Vec<QKD>, String>>()?;
+
+    let mut records = Vec::new();
+    for (j, &noise) in Sweep::concat(&args.noise).iter().enumerate() {
+        let physical_layer = PhysicalLayer {
+            channel: Channel {
+                depolarization: noise,
+                ..Channel::default()
+            },
+            ..PhysicalLayer::default()
+        };
+        for (i, (tag, protocol)) in args.protocol.iter().zip(&protocols).enumerate() {
+            let protocol = protocol.clone().with_physical_layer(physical_layer);
+            let stream = (j * protocols.len() + i) as u64;
+            records.extend(analysis.run(tag, &protocol, derive_seed(seed, stream))?);
+        }
+    }
+
+    if !args.output.quiet {
+        let rows = records
+            .iter()
+            .map(|record| record.to_table_row().to_vec())
+            .collect::<Vec<Vec<String>>>();
+        print_table(&DETECTION_TABLE_HEADER, &rows);
+    }
+    if let Some(path) = &args.output.output {
+        let metadata = RunMetadata::new(
+            seed,
+            json!({
+                "protocols": args.protocol,
+                "interception_rates": Sweep::concat(&args.interception_rate),
+                "sample_sizes": Sweep::concat(&args.sample_size),
+                "thresholds": Sweep::concat(&args.threshold),
+                "noise": Sweep::concat(&args.noise),
+                "trials": args.repetitions,
+                "backend": args.backend.to_possible_value().map(|v| v.get_name().to_string()),
+                "confidence": args.confidence,
+            }),
+        );
+        write_results(path, args.output.format, &metadata, &records)?;
+    }
+    if let Some(path) = &args.roc {
+        let figure = roc_figure(&records).ok_or("There are no results to plot")?;
+        std::fs::write(path, figure.to_svg())
+            .map_err(|error| format!("Could not write `{}`: {}", path.display(), error))?;
+    }
+    Ok(())
+}
+
+fn write_results<R: OutputRecord>(
+    path: &Path,
+    format: OutputFormat,
+    metadata: &RunMetadata,
+    records: &[R],
+) -> Result<(), String> {
+    let error_message = |error| format!("Could not write `{}`: {}", path.display(), error);
+    let mut writer = ResultWriter::create(path, format, metadata).map_err(error_message)?;
+    for record in records {
+        writer.write(record).map_err(error_message)?;
+    }
+    writer.finish().map_err(error_message)
+}
+
+fn print_table<S: AsRef<str>>(header: &[&str], rows: &[Vec<S>]) {
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].as_ref().chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<usize>>();
+    let print_row = |columns: Vec<&str>| {
+        let cells = columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, &width)| format!("{:>width$}", column))
+            .collect::<Vec<String>>();
+        println!("{}", cells.join("  "));
+    };
+
+    print_row(header.to_vec());
+    for row in rows {
+        print_row(row.iter().map(AsRef::as_ref).collect());
+    }
+}
+
+fn postprocess(args: &PostprocessArgs, registry: &ProtocolRegistry) -> Result<(), String> {
+    if args.key.quiet && args.output.is_none() {
+        return Err("The `--output` argument is required when `--quiet` is enabled.".into());
+    }
+    let transcript = Transcript::read_from_path(&args.transcript).map_err(|error| {
+        format!(
+            "Could not read transcript `{}`: {}",
+            args.transcript.display(),
+            error
+        )
+    })?;
+    let qkd = args.key.configure(
+        &transcript.protocol,
+        get_protocol(&transcript.protocol, registry)?.build(),
+    )?;
+    let replay_error = |error| {
+        format!(
+            "Could not replay transcript `{}`: {}",
+            args.transcript.display(),
+            error
+        )
+    };
+    let result = transcript.replay(&qkd).map_err(replay_error)?;
+    let sifted_key = transcript.sifted_key(&qkd).map_err(replay_error)?;
+    finish_key(
+        &transcript.protocol,
+        &result,
+        &sifted_key,
+        transcript.seed,
+        &args.key,
+        args.output.as_deref(),
+    )
+}
+
+fn keygen(args: &KeygenArgs, registry: &ProtocolRegistry) -> Result<(), String> {
+    let seed = args.seed.unwrap_or_else(random_seed);
+    let mut qkd = get_protocol(&args.protocol, registry)?
+        .build()
+        .with_backend(args.backend);
+    if let Some(distance_km) = args.distance {
+        qkd = qkd.with_physical_layer(fiber(distance_km));
+    }
+    let qkd = args.key.configure(&args.protocol, qkd)?;
+    let (result, transcript) = Transcript::record(
+        &args.protocol,
+        &qkd,
+        args.number_of_qubits,
+        args.interception_rate,
+        seed,
+    );
+    let sifted_key = transcript
+        .sifted_key(&qkd)
+        .map_err(|error| error.to_string())?;
+    finish_key(
+        &args.protocol,
+        &result,
+        &sifted_key,
+        seed,
+        &args.key,
+        Some(&args.output),
+    )
+}
+
+impl KeyArgs {
+    /// Applies the abort threshold, if any, to the protocol.
+    fn configure(&self, protocol: &str, qkd: QKD) -> Result<QKD, String> {
+        apply_qber_threshold(protocol, qkd, self.qber_threshold)
+    }
+}
+
+/// Applies a `--qber-threshold`, if any, rejecting the protocols whose security
+/// check ignores it.
+fn apply_qber_threshold(
+    protocol: &str,
+    qkd: QKD,
+    qber_threshold: Option<f64>,
+) -> Result<QKD, String> {
+    match qber_threshold {
+        Some(_) if !qkd.uses_qber_threshold() => Err(format!(
+            "`{}` does not abort based on a QBER threshold, so `--qber-threshold` cannot be used",
+            protocol
+        )),
+        Some(qber_threshold) => Ok(qkd.with_qber_threshold(qber_threshold)),
+        None => Ok(qkd),
+    }
+}
+
+/// Reconciles and amplifies the sifted key of an accepted execution, and
+/// writes the final key if a path is given.
+fn finish_key(
+    protocol: &str,
+    result: &QKDResult,
+    sifted_key: &SiftedKey,
+    seed: u64,
+    args: &KeyArgs,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    if !result.is_considered_secure {
+        return Err(format!(
+            "`{}` was aborted: {:.4} of the {} check bits are mismatched",
+            protocol, sifted_key.estimated_qber, sifted_key.check_bits
+        ));
+    }
+    let processed = PostProcessing::builder()
+        .passes(args.passes)
+        .epsilon(args.epsilon)
+        .build()
+        .run(sifted_key, seed);
+    if !processed.keys_match {
+        return Err(format!(
+            "The reconciliation left {} mismatched bits, so the keys of Alice and Bob differ",
+            processed.residual_errors
+        ));
+    }
+    let bytes = key_bytes(&processed.key);
+    if bytes.is_empty() {
+        return Err(format!(
+            "No secret key remains from {} sifted bits after leaking {} bits in the reconciliation",
+            processed.sifted_length, processed.leaked_bits
+        ));
+    }
+
+    if let Some(path) = output {
+        let content = match args.encoding {
+            KeyEncoding::Hex => {
+                let mut hex = bytes
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>();
+                hex.push('\n');
+                hex.into_bytes()
+            }
+            KeyEncoding::Binary => bytes.clone(),
+        };
+        std::fs::write(path, content)
+            .map_err(|error| format!("Could not write `{}`: {}", path.display(), error))?;
+    }
+    if !args.quiet {
+        print_summary(&[
+            ("protocol", protocol.to_string()),
+            ("seed", seed.to_string()),
+            ("sifted_bits", processed.sifted_length.to_string()),
+            ("check_bits", sifted_key.check_bits.to_string()),
+            ("estimated_qber", format!("{:.4}", processed.estimated_qber)),
+            ("mismatched_bits", processed.mismatched_bits.to_string()),
+            ("leaked_bits", processed.leaked_bits.to_string()),
+            ("residual_errors", processed.residual_errors.to_string()),
+            ("key_bits", (8 * bytes.len()).to_string()),
+        ]);
+    }
+    Ok(())
+}
+
+fn list_protocols(registry: &ProtocolRegistry) {
+    for protocol in registry.iter() {
+        let qkd = protocol.build();
+        let (alice_bases, bob_bases) = qkd.number_of_bases();
+        println!("{}", protocol.name());
+        println!("    {}", protocol.description());
+        println!("    bases: {} (Alice), {} (Bob)", alice_bases, bob_bases);
+        println!(
+            "    statistical backend: {}",
+            if qkd.supports_statistical_backend() {
+                "supported"
+            } else {
+                "not supported"
+            }
+        );
+        if let (Some(honest), Some(attacked)) = (
+            protocol.theoretical_prediction(0.0),
+            protocol.theoretical_prediction(1.0),
+        ) {
+            println!(
+                "    prediction: sift rate {:.4} without Eve; QBER {:.4} and Eve's knowledge {:.4} when intercepting every qubit",
+                honest.sift_rate, attacked.quantum_bit_error_rate, attacked.eve_knowledge
+            );
+        }
+    }
+    for protocol in registry.iter_continuous() {
+        let honest = protocol.build().estimate(1.0, 0.0);
+        println!("{}", protocol.name());
+        println!("    {} (run and sweep only)", protocol.description());
+        println!(
+            "    prediction: key rate {:.4} bits per pulse without losses nor excess noise",
+            honest.key_rate
+        );
+    }
+}
+
+fn explain(args: &ExplainArgs, registry: &ProtocolRegistry) -> Result<(), String> {
+    let seed = args.seed.unwrap_or_else(random_seed);
+    let qkd = apply_qber_threshold(
+        &args.protocol,
+        get_protocol(&args.protocol, registry)?.build(),
+        args.qber_threshold,
+    )?;
+    let explanation = Explanation::new(
+        &args.protocol,
+        &qkd,
+        args.number_of_qubits,
+        args.interception_rate,
+        seed,
+    );
+
+    println!(
+        "{} with {} qubits (seed {})\n",
+        args.protocol, args.number_of_qubits, seed
+    );
+    let steps = explanation.steps();
+    for (i, step) in steps.iter().enumerate() {
+        println!("{}\n", step);
+        if args.step && i + 1 < steps.len() {
+            print!("Press Enter to continue...");
+            io::stdout()
+                .flush()
+                .and_then(|_| io::stdin().read_line(&mut String::new()))
+                .map_err(|error| format!("Could not read the standard input: {}", error))?;
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn print_summary(rows: &[(&str, String)]) {
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, value) in rows {
+        println!("{:<width$}  {}", name, value);
+    }
+}
+
+fn exit_with_error(message: String) -> ! {
+    eprintln!("Error: {}", message);
+    process::exit(1);
+}
+
+/// Runs the command-line interface with the protocols of the given registry.
+///
+/// Downstream crates can build their own binary by registering additional
+/// protocols and calling this function from `main`.
+pub fn run(registry: &ProtocolRegistry) {
+    let args = Args::parse();
+    let outcome = match &args.command {
+        Command::Run(run_args) => run_command(run_args, registry),
+        Command::Sweep(sweep_args) => sweep(sweep_args, registry),
+        Command::Analyze(analyze_args) => analyze(analyze_args, registry),
+        Command::Plot(plot_args) => plot(plot_args),
+        Command::Postprocess(postprocess_args) => postprocess(postprocess_args, registry),
+        Command::Keygen(keygen_args) => keygen(keygen_args, registry),
+        Command::Protocols => {
+            list_protocols(registry);
+            Ok(())
+        }
+        Command::Explain(explain_args) => explain(explain_args, registry),
+    };
+    outcome.unwrap_or_else(|message| exit_with_error(message));
+}
@@ -0,0 +1,721 @@
+use crate::backend::Backend;
+use crate::cvqkd::{CvQkdResult, GG02};
+use crate::output::OutputFormat;
+use crate::participants::{PreparationStrategy, RandomPreparation, Receiver, Sender};
+use crate::physical::{Channel, Detector, PhysicalLayer, Source};
+use crate::protocol::{BasisMatchSifting, QKDResult, SiftingStrategy, QKD};
+use crate::protocols::{B92Preparation, B92Sifting, ProtocolRegistry, QkdProtocol};
+use crate::transcript::Transcript;
+use crate::types::ComplexMatrix;
+use crate::utils::random_seed;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Preparation performed by Alice in a protocol described by a `ProtocolConfig`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PreparationConfig {
+    /// A random bit `b` prepared as |b⟩ (see `RandomPreparation`).
+    #[default]
+    RandomBit,
+    /// Always |0⟩, so the bit is given by the basis (see `B92Preparation`).
+    FixedZero,
+}
+
+/// Sifting performed in a protocol described by a `ProtocolConfig`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SiftingConfig {
+    /// Keep rounds where Alice and Bob used the same basis (see `BasisMatchSifting`).
+    #[default]
+    BasisMatch,
+    /// Keep conclusive rounds of the B92 protocol (see `B92Sifting`).
+    B92,
+}
+
+/// Declarative description of a protocol through the bases of its participants.
+///
+/// With the `serde` feature, protocols can be loaded from configuration files.
+/// Field names are part of the public schema and are kept stable.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ProtocolConfig {
+    /// Unique name used to select the protocol.
+    pub name: String,
+    /// Short human-readable description of the protocol.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: String,
+    /// Bases available to Alice.
+    pub alice_basis: Vec<ComplexMatrix>,
+    /// Bases available to Bob.
+    pub bob_basis: Vec<ComplexMatrix>,
+    /// Bases available to Eve. If omitted, Eve uses the default I and H bases.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eve_basis: Option<Vec<ComplexMatrix>>,
+    /// Preparation performed by Alice.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub preparation: PreparationConfig,
+    /// Sifting performed during the public discussion.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sifting: SiftingConfig,
+}
+
+impl QkdProtocol for ProtocolConfig {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn build(&self) -> QKD {
+        let preparation: Arc<dyn PreparationStrategy> = match self.preparation {
+            PreparationConfig::RandomBit => Arc::new(RandomPreparation),
+            PreparationConfig::FixedZero => Arc::new(B92Preparation),
+        };
+        let sifting: Arc<dyn SiftingStrategy> = match self.sifting {
+            SiftingConfig::BasisMatch => Arc::new(BasisMatchSifting),
+            SiftingConfig::B92 => Arc::new(B92Sifting),
+        };
+
+        let alice = Sender::builder()
+            .posible_basis(self.alice_basis.clone())
+            .strategy(preparation)
+            .build();
+        let bob = Receiver::builder()
+            .posible_basis(self.bob_basis.clone())
+            .build();
+        let eve = self
+            .eve_basis
+            .clone()
+            .map(|posible_basis| Receiver::builder().posible_basis(posible_basis).build());
+
+        QKD::builder()
+            .alice(alice)
+            .bob(bob)
+            .maybe_eve(eve)
+            .sifting(sifting)
+            .build()
+    }
+}
+
+/// Declarative description of a single experiment.
+///
+/// With the `serde` feature, experiments can be loaded from configuration files.
+/// Field names are part of the public schema and are kept stable.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ExperimentConfig {
+    /// Name of the protocol in the registry.
+    pub protocol: String,
+    /// Number of qubits to send.
+    pub number_of_qubits: usize,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interception_rate: f64,
+    /// Base seed of the simulation. If omitted, a random seed is used.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seed: Option<u64>,
+    /// Backend used to simulate the quantum phase.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backend: Backend,
+    /// If present, qubits are processed in blocks of this size.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_size: Option<usize>,
+}
+
+impl ExperimentConfig {
+    /// Runs the experiment with a protocol from the given registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocol is looked up.
+    ///
+    /// # Returns
+    ///
+    /// The `QKDResult` of the experiment, or an error message if the
+    /// configuration is not valid.
+    pub fn run(&self, registry: &ProtocolRegistry) -> Result<QKDResult, String> {
+        let qkd = self.build(registry)?;
+        let seed = self.seed.unwrap_or_else(random_seed);
+        Ok(match self.block_size {
+            Some(block_size) => qkd.run_streaming_with_seed(
+                self.number_of_qubits,
+                self.interception_rate,
+                seed,
+                block_size,
+            ),
+            None => qkd.run_with_seed(self.number_of_qubits, self.interception_rate, seed),
+        })
+    }
+
+    /// Runs the experiment and records its per-round transcript.
+    ///
+    /// The `block_size` is ignored, since a transcript covers the whole
+    /// execution.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocol is looked up.
+    ///
+    /// # Returns
+    ///
+    /// The `QKDResult` of the experiment together with its `Transcript`, or an
+    /// error message if the configuration is not valid.
+    pub fn record(&self, registry: &ProtocolRegistry) -> Result<(QKDResult, Transcript), String> {
+        let qkd = self.build(registry)?;
+        let seed = self.seed.unwrap_or_else(random_seed);
+        Ok(Transcript::record(
+            &self.protocol,
+            &qkd,
+            self.number_of_qubits,
+            self.interception_rate,
+            seed,
+        ))
+    }
+
+    /// Looks up and builds the protocol of the experiment after validating it.
+    fn build(&self, registry: &ProtocolRegistry) -> Result<QKD, String> {
+        let protocol = registry.get(&self.protocol).ok_or_else(|| {
+            format!(
+                "`{}` is not an allowed protocol. Allowed protocols are: {:?}",
+                self.protocol,
+                registry.names()
+            )
+        })?;
+        if !(0.0..=1.0).contains(&self.interception_rate) {
+            return Err("All rates must be between 0.0 and 1.0".to_string());
+        }
+        Ok(protocol.build().with_backend(self.backend))
+    }
+}
+
+/// Eavesdropper of a `ScenarioConfig`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct EavesdropperConfig {
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Bases available to Eve. If omitted, Eve uses the bases of the protocol.
+    pub basis: Option<Vec<ComplexMatrix>>,
+}
+
+/// Post-processing of a `ScenarioConfig`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct PostProcessingConfig {
+    /// Maximum fraction (0.0 to 1.0) of mismatched check bits tolerated before aborting.
+    pub qber_threshold: f64,
+    /// If present, qubits are processed in blocks of this size.
+    pub block_size: Option<usize>,
+}
+
+/// Parameters of a `ScenarioConfig` that only apply to continuous-variable protocols.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ContinuousVariableConfig {
+    /// Excess noise of the channel, in shot-noise units. If omitted, the
+    /// protocol default is used.
+    pub excess_noise: Option<f64>,
+    /// Variance of Alice's modulation of each quadrature, in shot-noise units.
+    /// If omitted, the protocol default is used.
+    pub modulation_variance: Option<f64>,
+}
+
+/// Named scenario of an `ExperimentFile`, repeated a number of times.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ScenarioConfig {
+    /// Unique name of the scenario.
+    pub name: String,
+    /// Name of the protocol in the registry.
+    pub protocol: String,
+    /// Number of qubits to send.
+    pub number_of_qubits: usize,
+    /// Number of repetitions of the scenario.
+    #[cfg_attr(feature = "serde", serde(default = "default_repetitions"))]
+    pub repetitions: usize,
+    /// Backend used to simulate the quantum phase.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backend: Backend,
+    /// Photon source used by Alice.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: Source,
+    /// Quantum channel between Alice and Bob.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub channel: Channel,
+    /// Single-photon detector used by Bob.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub detector: Detector,
+    /// Eavesdropper acting on the channel.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eavesdropper: EavesdropperConfig,
+    /// Post-processing applied to the sifted key.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub post_processing: PostProcessingConfig,
+    /// Parameters of continuous-variable protocols.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub continuous_variable: ContinuousVariableConfig,
+}
+
+#[cfg(feature = "serde")]
+fn default_repetitions() -> usize {
+    1
+}
+
+impl ScenarioConfig {
+    /// Builds the protocol instance described by the scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocol is looked up.
+    ///
+    /// # Returns
+    ///
+    /// The `QKD` instance, or an error message if the protocol is not registered.
+    pub fn build(&self, registry: &ProtocolRegistry) -> Result<QKD, String> {
+        let protocol = registry.get(&self.protocol).ok_or_else(|| {
+            format!(
+                "`{}` is not an allowed protocol. Allowed protocols are: {:?}",
+                self.protocol,
+                registry.names()
+            )
+        })?;
+
+        let mut qkd = protocol
+            .build()
+            .with_backend(self.backend)
+            .with_physical_layer(self.physical_layer())
+            .with_qber_threshold(self.post_processing.qber_threshold);
+        if let Some(posible_basis) = &self.eavesdropper.basis {
+            qkd = qkd.with_eve(
+                Receiver::builder()
+                    .posible_basis(posible_basis.clone())
+                    .build(),
+            );
+        }
+        Ok(qkd)
+    }
+
+    /// Returns the physical layer described by the scenario.
+    pub fn physical_layer(&self) -> PhysicalLayer {
+        PhysicalLayer {
+            source: self.source,
+            channel: self.channel,
+            detector: self.detector,
+        }
+    }
+
+    /// Runs a single repetition of the scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocol is looked up.
+    /// * `seed` - Seed of the repetition.
+    ///
+    /// # Returns
+    ///
+    /// The `QKDResult` of the repetition, or an error message if the protocol
+    /// is not registered.
+    pub fn run_with_seed(
+        &self,
+        registry: &ProtocolRegistry,
+        seed: u64,
+    ) -> Result<QKDResult, String> {
+        let qkd = self.build(registry)?;
+        let interception_rate = self.eavesdropper.interception_rate;
+        Ok(match self.post_processing.block_size {
+            Some(block_size) => qkd.run_streaming_with_seed(
+                self.number_of_qubits,
+                interception_rate,
+                seed,
+                block_size,
+            ),
+            None => qkd.run_with_seed(self.number_of_qubits, interception_rate, seed),
+        })
+    }
+
+    /// Returns `true` if the protocol of the scenario is a continuous-variable
+    /// protocol of the registry.
+    pub fn is_continuous(&self, registry: &ProtocolRegistry) -> bool {
+        registry.get_continuous(&self.protocol).is_some()
+    }
+
+    /// Builds the continuous-variable protocol instance described by the scenario.
+    ///
+    /// Only the transmittance of the channel and the efficiency of the detector
+    /// apply to continuous-variable protocols.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocol is looked up.
+    ///
+    /// # Returns
+    ///
+    /// The `GG02` instance, or an error message if the protocol is not a
+    /// registered continuous-variable protocol.
+    pub fn build_continuous(&self, registry: &ProtocolRegistry) -> Result<GG02, String> {
+        let protocol = registry.get_continuous(&self.protocol).ok_or_else(|| {
+            format!(
+                "`{}` is not an allowed continuous-variable protocol. Allowed protocols are: {:?}",
+                self.protocol,
+                registry.continuous_names()
+            )
+        })?;
+
+        let mut gg02 = protocol.build().with_physical_layer(self.physical_layer());
+        if let Some(excess_noise) = self.continuous_variable.excess_noise {
+            gg02 = gg02.with_excess_noise(excess_noise);
+        }
+        if let Some(modulation_variance) = self.continuous_variable.modulation_variance {
+            gg02 = gg02.with_modulation_variance(modulation_variance);
+        }
+        Ok(gg02)
+    }
+
+    /// Runs a single repetition of a scenario with a continuous-variable protocol.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocol is looked up.
+    /// * `seed` - Seed of the repetition.
+    ///
+    /// # Returns
+    ///
+    /// The `CvQkdResult` of the repetition, or an error message if the protocol
+    /// is not a registered continuous-variable protocol.
+    pub fn run_continuous_with_seed(
+        &self,
+        registry: &ProtocolRegistry,
+        seed: u64,
+    ) -> Result<CvQkdResult, String> {
+        let gg02 = self.build_continuous(registry)?;
+        Ok(gg02.run_with_seed(
+            self.number_of_qubits,
+            self.eavesdropper.interception_rate,
+            seed,
+        ))
+    }
+
+    /// Returns every problem found in the scenario, prefixed by its name.
+    fn validate(&self, registry: &ProtocolRegistry) -> Vec<String> {
+        let mut errors = Vec::new();
+        let is_continuous = self.is_continuous(registry);
+        if registry.get(&self.protocol).is_none() && !is_continuous {
+            errors.push(format!(
+                "`protocol` `{}` is not registered. Allowed protocols are: {:?}",
+                self.protocol,
+                registry
+                    .names()
+                    .into_iter()
+                    .chain(registry.continuous_names())
+                    .collect::<Vec<&str>>()
+            ));
+        }
+        let discrete_only = [
+            ("backend", self.backend != Backend::default()),
+            ("eavesdropper.basis", self.eavesdropper.basis.is_some()),
+            (
+                "post_processing",
+                self.post_processing != PostProcessingConfig::default(),
+            ),
+        ];
+        for (field, is_set) in discrete_only {
+            if is_continuous && is_set {
+                errors.push(format!(
+                    "`{}` does not apply to the continuous-variable protocol `{}`",
+                    field, self.protocol
+                ));
+            }
+        }
+        if !is_continuous && self.continuous_variable != ContinuousVariableConfig::default() {
+            errors.push(format!(
+                "`continuous_variable` only applies to continuous-variable protocols, not `{}`",
+                self.protocol
+            ));
+        }
+        for (field, value) in [
+            (
+                "continuous_variable.excess_noise",
+                self.continuous_variable.excess_noise,
+            ),
+            (
+                "continuous_variable.modulation_variance",
+                self.continuous_variable.modulation_variance,
+            ),
+        ] {
+            if let Some(value) = value.filter(|value| !(value.is_finite() && *value >= 0.0)) {
+                errors.push(format!(
+                    "`{}` must be a non-negative number, found {}",
+                    field, value
+                ));
+            }
+        }
+        if self.number_of_qubits == 0 {
+            errors.push("`number_of_qubits` must be greater than 0".to_string());
+        }
+        if self.repetitions == 0 {
+            errors.push("`repetitions` must be greater than 0".to_string());
+        }
+        if let Some(mean_photon_number) = self.source.mean_photon_number {
+            if !(mean_photon_number.is_finite() && mean_photon_number > 0.0) {
+                errors.push(format!(
+                    "`source.mean_photon_number` must be a positive number, found {}",
+                    mean_photon_number
+                ));
+            }
+        }
+        for (field, value) in [
+            ("channel.distance_km", self.channel.distance_km),
+            (
+                "channel.attenuation_db_per_km",
+                self.channel.attenuation_db_per_km,
+            ),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                errors.push(format!(
+                    "`{}` must be a non-negative number, found {}",
+                    field, value
+                ));
+            }
+        }
+        for (field, value) in [
+            ("channel.loss", self.channel.loss),
+            ("channel.depolarization", self.channel.depolarization),
+            ("detector.efficiency", self.detector.efficiency),
+            (
+                "detector.dark_count_probability",
+                self.detector.dark_count_probability,
+            ),
+            (
+                "eavesdropper.interception_rate",
+                self.eavesdropper.interception_rate,
+            ),
+            (
+                "post_processing.qber_threshold",
+                self.post_processing.qber_threshold,
+            ),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                errors.push(format!(
+                    "`{}` must be between 0.0 and 1.0, found {}",
+                    field, value
+                ));
+            }
+        }
+        if !self.channel.rotation_rad.is_finite() {
+            errors.push(format!(
+                "`channel.rotation_rad` must be a finite number, found {}",
+                self.channel.rotation_rad
+            ));
+        }
+        if let Some(basis) = &self.eavesdropper.basis {
+            errors.extend(validate_basis("eavesdropper.basis", basis));
+        }
+        if self.post_processing.block_size == Some(0) {
+            errors.push("`post_processing.block_size` must be greater than 0".to_string());
+        }
+
+        errors
+            .into_iter()
+            .map(|error| format!("scenario `{}`: {}", self.name, error))
+            .collect()
+    }
+}
+
+/// File where the results of an `ExperimentFile` are written.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct OutputConfig {
+    /// Path of the file to create.
+    pub path: PathBuf,
+    /// Format of the file.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: OutputFormat,
+}
+
+/// Declarative description of a set of experiments.
+///
+/// With the `toml` feature, it can be read from TOML files where custom
+/// protocols, scenarios and outputs are given as `[[protocol]]`, `[[scenario]]`
+/// and `[[output]]` tables. Field names are part of the public schema and are kept stable.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ExperimentFile {
+    /// Base seed of the experiments. If omitted, a random seed is used.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seed: Option<u64>,
+    /// Custom protocols available to the scenarios, in addition to the registry.
+    #[cfg_attr(feature = "serde", serde(default, rename = "protocol"))]
+    pub protocols: Vec<ProtocolConfig>,
+    /// Scenarios to run, in order.
+    #[cfg_attr(feature = "serde", serde(default, rename = "scenario"))]
+    pub scenarios: Vec<ScenarioConfig>,
+    /// Files where the results are written.
+    #[cfg_attr(feature = "serde", serde(default, rename = "output"))]
+    pub outputs: Vec<OutputConfig>,
+}
+
+impl ExperimentFile {
+    /// Parses and validates an experiment file in TOML format.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Content of the file.
+    /// * `registry` - Registry where the protocols of the scenarios are looked up.
+    ///
+    /// # Returns
+    ///
+    /// The experiment file, or an error message describing every problem found.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(content: &str, registry: &ProtocolRegistry) -> Result<Self, String> {
+        let file: ExperimentFile = toml::from_str(content).map_err(|error| error.to_string())?;
+        file.validate(registry)?;
+        Ok(file)
+    }
+
+    /// Reads and validates an experiment file in TOML format.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to read.
+    /// * `registry` - Registry where the protocols of the scenarios are looked up.
+    ///
+    /// # Returns
+    ///
+    /// The experiment file, or an error message describing every problem found.
+    #[cfg(feature = "toml")]
+    pub fn read_from_path<P: AsRef<std::path::Path>>(
+        path: P,
+        registry: &ProtocolRegistry,
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|error| format!("Could not read `{}`: {}", path.display(), error))?;
+        Self::from_toml(&content, registry)
+            .map_err(|error| format!("Invalid experiment file `{}`:\n{}", path.display(), error))
+    }
+
+    /// Returns a copy of `registry` including the custom protocols of the file.
+    pub fn registry(&self, registry: &ProtocolRegistry) -> ProtocolRegistry {
+        let mut registry = registry.clone();
+        for protocol in &self.protocols {
+            registry.register(protocol.clone());
+        }
+        registry
+    }
+
+    /// Checks that every protocol, scenario and output of the file is valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - Registry where the protocols of the scenarios are looked up.
+    ///
+    /// # Returns
+    ///
+    /// An error message with one line per problem found, if any.
+    pub fn validate(&self, registry: &ProtocolRegistry) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        let mut protocol_names = HashSet::new();
+        for protocol in &self.protocols {
+            if !protocol_names.insert(protocol.name.as_str()) {
+                errors.push(format!("protocol `{}` is defined twice", protocol.name));
+            }
+            if registry.get(&protocol.name).is_some()
+                || registry.get_continuous(&protocol.name).is_some()
+            {
+                errors.push(format!(
+                    "protocol `{}` is already registered and cannot be redefined",
+                    protocol.name
+                ));
+            }
+            if protocol.sifting == SiftingConfig::B92 {
+                for (field, basis) in [
+                    ("alice_basis", &protocol.alice_basis),
+                    ("bob_basis", &protocol.bob_basis),
+                ] {
+                    if basis.len() != 2 {
+                        errors.push(format!(
+                            "protocol `{}`: `{}` must contain exactly 2 bases with `b92` sifting, found {}",
+                            protocol.name,
+                            field,
+                            basis.len()
+                        ));
+                    }
+                }
+            }
+            for (field, basis) in [
+                ("alice_basis", &protocol.alice_basis),
+                ("bob_basis", &protocol.bob_basis),
+            ]
+            .into_iter()
+            .chain(
+                protocol
+                    .eve_basis
+                    .as_ref()
+                    .map(|basis| ("eve_basis", basis)),
+            ) {
+                errors.extend(
+                    validate_basis(field, basis)
+                        .into_iter()
+                        .map(|error| format!("protocol `{}`: {}", protocol.name, error)),
+                );
+            }
+        }
+
+        if self.scenarios.is_empty() {
+            errors.push("at least one `[[scenario]]` must be defined".to_string());
+        }
+        let registry = self.registry(registry);
+        let mut scenario_names = HashSet::new();
+        for scenario in &self.scenarios {
+            if !scenario_names.insert(scenario.name.as_str()) {
+                errors.push(format!("scenario `{}` is defined twice", scenario.name));
+            }
+            errors.extend(scenario.validate(&registry));
+        }
+
+        let mut output_paths = HashSet::new();
+        for output in &self.outputs {
+            if !output_paths.insert(&output.path) {
+                errors.push(format!(
+                    "output `{}` is defined twice",
+                    output.path.display()
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+}
+
+/// Returns the problems found in a list of bases, prefixed by the field name.
+fn validate_basis(field: &str, basis: &[ComplexMatrix]) -> Vec<String> {
+    let mut errors = Vec::new();
+    if basis.is_empty() {
+        errors.push(format!("`{}` must contain at least one basis", field));
+    }
+    for (i, matrix) in basis.iter().enumerate() {
+        if matrix.invert().is_none() {
+            errors.push(format!("`{}[{}]` is not an invertible matrix", field, i));
+        }
+    }
+    errors
+}
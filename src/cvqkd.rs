@@ -0,0 +1,447 @@
+use crate::physical::PhysicalLayer;
+use crate::protocol::{QKDResult, ROUNDS_PER_CHUNK};
+use crate::utils::{rand_float, rand_gaussian, random_seed, seeded_rng};
+use bon::Builder;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use std::f64::consts::SQRT_2;
+use std::time::Duration;
+use web_time::Instant;
+
+/// A named continuous-variable QKD protocol that can be built into a runnable
+/// `GG02` instance.
+///
+/// The continuous-variable counterpart of `QkdProtocol`: downstream crates can
+/// implement it and add their protocols to a `ProtocolRegistry` with
+/// `register_continuous` to make them available to the CLI.
+pub trait CvQkdProtocol: Send + Sync {
+    /// Unique name used to select the protocol (e.g. `GG02-homodyne`).
+    fn name(&self) -> &str;
+
+    /// Short human-readable description of the protocol.
+    fn description(&self) -> &str;
+
+    /// Builds the modulation, detection and reconciliation of the protocol.
+    fn build(&self) -> GG02;
+}
+
+/// GG02 protocol with homodyne detection.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GG02Homodyne;
+
+impl CvQkdProtocol for GG02Homodyne {
+    fn name(&self) -> &str {
+        "GG02-homodyne"
+    }
+
+    fn description(&self) -> &str {
+        "Continuous-variable protocol with Gaussian-modulated coherent states and homodyne detection"
+    }
+
+    fn build(&self) -> GG02 {
+        GG02::builder().detection(Detection::Homodyne).build()
+    }
+}
+
+/// GG02 protocol with heterodyne detection.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GG02Heterodyne;
+
+impl CvQkdProtocol for GG02Heterodyne {
+    fn name(&self) -> &str {
+        "GG02-heterodyne"
+    }
+
+    fn description(&self) -> &str {
+        "Continuous-variable protocol with Gaussian-modulated coherent states and heterodyne detection"
+    }
+
+    fn build(&self) -> GG02 {
+        GG02::builder().detection(Detection::Heterodyne).build()
+    }
+}
+
+/// Measurement performed by Bob on every coherent state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Detection {
+    /// Measures one quadrature, chosen at random and announced afterwards.
+    #[default]
+    Homodyne,
+    /// Measures both quadratures at once, each with one unit of added vacuum noise.
+    Heterodyne,
+}
+
+/// GG02 continuous-variable QKD protocol with Gaussian-modulated coherent states.
+///
+/// Alice draws both quadratures of every coherent state from a centered normal
+/// distribution and Bob measures them with a homodyne or heterodyne detector.
+/// Half of the pulses are disclosed to estimate the transmittance and the
+/// excess noise of the channel, from which the asymptotic secret key rate with
+/// reverse reconciliation is bounded by `β·I(A:B) − χ(B:E)`, with the Holevo
+/// information `χ(B:E)` of an eavesdropper performing a collective attack.
+///
+/// Variances are expressed in shot-noise units (SNU), so that the vacuum has
+/// variance 1. Bob's detector is trusted: its inefficiency and electronic noise
+/// are not attributed to Eve.
+#[derive(Builder, Clone, Debug)]
+pub struct GG02 {
+    /// Measurement performed by Bob.
+    #[builder(default)]
+    detection: Detection,
+    /// Variance `V_A` of Alice's modulation of each quadrature, in SNU.
+    #[builder(default = 4.0)]
+    modulation_variance: f64,
+    /// Excess noise `ξ` added by the channel, referred to its input, in SNU.
+    #[builder(default)]
+    excess_noise: f64,
+    /// Electronic noise `v_el` of Bob's detector, in SNU.
+    #[builder(default)]
+    electronic_noise: f64,
+    /// Efficiency `β` (0.0 to 1.0) of the reconciliation of Alice's and Bob's data.
+    #[builder(default = 0.95)]
+    reconciliation_efficiency: f64,
+    /// Channel and detector between Alice and Bob. Only the transmittance of the
+    /// channel and the efficiency of the detector apply to coherent states.
+    #[builder(default)]
+    physical_layer: PhysicalLayer,
+}
+
+/// Parameters of a GG02 run that are set by the experiment rather than
+/// estimated, in SNU.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CvParameters {
+    /// Excess noise `ξ` added by the channel, referred to its input.
+    pub excess_noise: f64,
+    /// Variance `V_A` of Alice's modulation of each quadrature.
+    pub modulation_variance: f64,
+}
+
+/// Parameters estimated from the disclosed pulses of a GG02 run, together
+/// with the secret key rate they allow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HolevoEstimate {
+    /// Transmittance `T` of the channel.
+    pub transmittance: f64,
+    /// Excess noise `ξ` of the channel, referred to its input, in SNU.
+    pub excess_noise: f64,
+    /// Mutual information `I(A:B)` between Alice and Bob, in bits per pulse.
+    pub mutual_information: f64,
+    /// Holevo bound `χ(B:E)` on the information of Eve about Bob's data, in bits per pulse.
+    pub holevo_bound: f64,
+    /// Secret key rate `β·I(A:B) − χ(B:E)`, in bits per pulse. Negative if no key can be distilled.
+    pub key_rate: f64,
+}
+
+/// Results of the execution of a continuous-variable QKD protocol.
+///
+/// It mirrors `QKDResult`, into which it can be converted to be compared with
+/// discrete-variable protocols, and adds the estimated channel parameters.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CvQkdResult {
+    /// Total duration of the simulation, from initialization to completion.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "elapsed_time_ns",
+            with = "crate::utils::serde_duration_nanos"
+        )
+    )]
+    pub elapsed_time: Duration,
+
+    /// Indicates whether the communication is considered secure, i.e. the
+    /// estimated key rate is positive. If `false`, the protocol was aborted.
+    pub is_considered_secure: bool,
+
+    /// Length of the final key in bits, the key rate times the number of key pulses.
+    /// If the protocol is aborted, this is `None`.
+    pub key_length: Option<usize>,
+
+    /// Parameters estimated from the disclosed pulses.
+    /// If there were not enough pulses to estimate them, this is `None`.
+    pub estimate: Option<HolevoEstimate>,
+
+    /// Upper bound on the fraction of the reconciled key known by Eve,
+    /// `χ(B:E) / (β·I(A:B))`. If the protocol is aborted, this is 0.0.
+    pub eve_knowledge: f64,
+}
+
+impl From<CvQkdResult> for QKDResult {
+    /// Converts the result for comparison with discrete-variable protocols.
+    /// Continuous-variable protocols have no bit error rate, so
+    /// `quantum_bit_error_rate` is `None`.
+    fn from(result: CvQkdResult) -> Self {
+        QKDResult {
+            elapsed_time: result.elapsed_time,
+            is_considered_secure: result.is_considered_secure,
+            key_length: result.key_length,
+            quantum_bit_error_rate: None,
+            eve_knowledge: result.eve_knowledge,
+        }
+    }
+}
+
+/// Sums of the products of Alice's quadratures `a` and Bob's outcomes `y`.
+#[derive(Clone, Copy, Debug, Default)]
+struct Moments {
+    samples: usize,
+    aa: f64,
+    ay: f64,
+    yy: f64,
+}
+
+impl Moments {
+    fn add(&mut self, a: f64, y: f64) {
+        self.samples += 1;
+        self.aa += a * a;
+        self.ay += a * y;
+        self.yy += y * y;
+    }
+
+    fn merge(mut self, other: Moments) -> Self {
+        self.samples += other.samples;
+        self.aa += other.aa;
+        self.ay += other.ay;
+        self.yy += other.yy;
+        self
+    }
+}
+
+impl GG02 {
+    /// Returns the same protocol using the given physical layer.
+    pub fn with_physical_layer(mut self, physical_layer: PhysicalLayer) -> Self {
+        self.physical_layer = physical_layer;
+        self
+    }
+
+    /// Returns the same protocol with the given excess noise, in SNU.
+    pub fn with_excess_noise(mut self, excess_noise: f64) -> Self {
+        self.excess_noise = excess_noise;
+        self
+    }
+
+    /// Returns the same protocol with the given modulation variance, in SNU.
+    pub fn with_modulation_variance(mut self, modulation_variance: f64) -> Self {
+        self.modulation_variance = modulation_variance;
+        self
+    }
+
+    /// Returns the excess noise and the modulation variance of the protocol.
+    pub fn parameters(&self) -> CvParameters {
+        CvParameters {
+            excess_noise: self.excess_noise,
+            modulation_variance: self.modulation_variance,
+        }
+    }
+
+    /// Executes the protocol for a given number of pulses and interception rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_pulses` - Number of coherent states sent by Alice.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a pulse.
+    ///
+    /// # Returns
+    ///
+    /// A `CvQkdResult` with the estimated parameters and the key length.
+    pub fn run(&self, number_of_pulses: usize, interception_rate: f64) -> CvQkdResult {
+        self.run_with_seed(number_of_pulses, interception_rate, random_seed())
+    }
+
+    /// Executes the protocol drawing all randomness from the given seed.
+    ///
+    /// The first half of the pulses is disclosed for parameter estimation and
+    /// the second half forms the key. Disclosed pulses are simulated in parallel
+    /// in blocks of `ROUNDS_PER_CHUNK`, each with its own random stream derived
+    /// from `seed`, so the outcome does not depend on the number of threads.
+    ///
+    /// Eve performs an intercept-resend attack: she measures the intercepted
+    /// pulses with a heterodyne detector and resends coherent states centered
+    /// on her outcomes, which adds 2 SNU of excess noise to those pulses.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_pulses` - Number of coherent states sent by Alice.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a pulse.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// A `CvQkdResult` with the estimated parameters and the key length.
+    pub fn run_with_seed(
+        &self,
+        number_of_pulses: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> CvQkdResult {
+        let start_time = Instant::now();
+        let check_pulses = number_of_pulses / 2;
+        let key_pulses = number_of_pulses - check_pulses;
+
+        let moments = (0..check_pulses.div_ceil(ROUNDS_PER_CHUNK))
+            .into_par_iter()
+            .map(|chunk| {
+                let mut rng = seeded_rng(seed, chunk as u64);
+                let pulses = ROUNDS_PER_CHUNK.min(check_pulses - chunk * ROUNDS_PER_CHUNK);
+                let mut moments = Moments::default();
+                for _ in 0..pulses {
+                    self.transmit(interception_rate, &mut rng, &mut moments);
+                }
+                moments
+            })
+            .collect::<Vec<Moments>>()
+            .into_iter()
+            .fold(Moments::default(), Moments::merge);
+
+        let estimate = self.estimate_from(&moments);
+        let is_considered_secure = estimate.is_some_and(|estimate| estimate.key_rate > 0.0);
+        let (mut key_length, mut eve_knowledge) = (None, 0.0);
+        if let (true, Some(estimate)) = (is_considered_secure, estimate) {
+            key_length = Some((estimate.key_rate * key_pulses as f64).floor() as usize);
+            eve_knowledge = (estimate.holevo_bound
+                / (self.reconciliation_efficiency * estimate.mutual_information))
+                .clamp(0.0, 1.0);
+        }
+
+        CvQkdResult {
+            elapsed_time: start_time.elapsed(),
+            is_considered_secure,
+            key_length,
+            estimate,
+            eve_knowledge,
+        }
+    }
+
+    /// Computes the mutual information, the Holevo bound and the key rate of
+    /// the protocol over a channel with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `transmittance` - Transmittance `T` (0.0 to 1.0) of the channel.
+    /// * `excess_noise` - Excess noise `ξ` of the channel, referred to its input, in SNU.
+    ///
+    /// # Returns
+    ///
+    /// A `HolevoEstimate` with the asymptotic key rate under collective attacks.
+    pub fn estimate(&self, transmittance: f64, excess_noise: f64) -> HolevoEstimate {
+        let (t, xi) = (transmittance, excess_noise);
+        let v = self.modulation_variance + 1.0;
+        let eta = self.physical_layer.detector.efficiency;
+        let chi_detection = match self.detection {
+            Detection::Homodyne => (1.0 - eta + self.electronic_noise) / eta,
+            Detection::Heterodyne => (2.0 - eta + 2.0 * self.electronic_noise) / eta,
+        };
+        let chi_line = 1.0 / t - 1.0 + xi;
+        let chi_total = chi_line + chi_detection / t;
+
+        let signal_to_noise = (v + chi_total) / (1.0 + chi_total);
+        let mutual_information = match self.detection {
+            Detection::Homodyne => 0.5 * signal_to_noise.log2(),
+            Detection::Heterodyne => signal_to_noise.log2(),
+        };
+
+        let a = v * v * (1.0 - 2.0 * t) + 2.0 * t + (t * (v + chi_line)).powi(2);
+        let b = (t * (v * chi_line + 1.0)).powi(2);
+        let (c, d) = match self.detection {
+            Detection::Homodyne => (
+                (v * b.sqrt() + t * (v + chi_line) + a * chi_detection) / (t * (v + chi_total)),
+                b.sqrt() * (v + b.sqrt() * chi_detection) / (t * (v + chi_total)),
+            ),
+            Detection::Heterodyne => (
+                (a * chi_detection.powi(2)
+                    + b
+                    + 1.0
+                    + 2.0 * chi_detection * (v * b.sqrt() + t * (v + chi_line))
+                    + 2.0 * t * (v * v - 1.0))
+                    / (t * (v + chi_total)).powi(2),
+                ((v + b.sqrt() * chi_detection) / (t * (v + chi_total))).powi(2),
+            ),
+        };
+        let eve_entropy = symplectic_entropy(a, b);
+        let conditional_entropy = symplectic_entropy(c, d);
+        let holevo_bound = eve_entropy - conditional_entropy;
+
+        HolevoEstimate {
+            transmittance,
+            excess_noise,
+            mutual_information,
+            holevo_bound,
+            key_rate: self.reconciliation_efficiency * mutual_information - holevo_bound,
+        }
+    }
+
+    /// Sends one disclosed pulse and adds Alice's and Bob's data to the moments.
+    ///
+    /// Both quadratures are statistically identical, so the homodyne detector
+    /// only simulates the one chosen by Bob.
+    fn transmit(&self, interception_rate: f64, rng: &mut StdRng, moments: &mut Moments) {
+        let transmittance = self.physical_layer.channel.transmittance();
+        let is_intercepted = rand_float(rng) < interception_rate;
+        let gain = self.gain();
+        let quadratures = match self.detection {
+            Detection::Homodyne => 1,
+            Detection::Heterodyne => 2,
+        };
+
+        for _ in 0..quadratures {
+            let alice = self.modulation_variance.sqrt() * rand_gaussian(rng);
+            let mut sent = alice + rand_gaussian(rng);
+            if is_intercepted {
+                sent += SQRT_2 * rand_gaussian(rng);
+            }
+            sent += self.excess_noise.sqrt() * rand_gaussian(rng);
+            let received =
+                transmittance.sqrt() * sent + (1.0 - transmittance).sqrt() * rand_gaussian(rng);
+            let bob = gain.sqrt() * received
+                + (1.0 - gain + self.electronic_noise).sqrt() * rand_gaussian(rng);
+            moments.add(alice, bob);
+        }
+    }
+
+    /// Returns the fraction of the signal reaching each quadrature measured by Bob.
+    fn gain(&self) -> f64 {
+        match self.detection {
+            Detection::Homodyne => self.physical_layer.detector.efficiency,
+            Detection::Heterodyne => self.physical_layer.detector.efficiency / 2.0,
+        }
+    }
+
+    /// Estimates the channel parameters from the moments of the disclosed pulses.
+    ///
+    /// Bob's outcomes follow `y = t·a + z`, with `t² = gain·T` and the variance
+    /// of `z` equal to `1 + v_el + t²·ξ`, from which `T` and `ξ` are recovered.
+    fn estimate_from(&self, moments: &Moments) -> Option<HolevoEstimate> {
+        if moments.samples < 2 || moments.aa <= 0.0 || moments.ay <= 0.0 {
+            return None;
+        }
+        let gain = self.gain();
+        let samples = moments.samples as f64;
+        let t = moments.ay / moments.aa;
+        let noise_variance = (moments.yy - t * moments.ay) / samples;
+        let transmittance = (t * t / gain).min(1.0);
+        let excess_noise = ((noise_variance - 1.0 - self.electronic_noise) / (t * t)).max(0.0);
+        Some(self.estimate(transmittance, excess_noise))
+    }
+}
+
+/// Von Neumann entropy of the two modes with symplectic eigenvalues
+/// `λ² = (a ± √(a² − 4b)) / 2`, in bits.
+fn symplectic_entropy(a: f64, b: f64) -> f64 {
+    let discriminant = (a * a - 4.0 * b).max(0.0).sqrt();
+    [(a + discriminant) / 2.0, (a - discriminant) / 2.0]
+        .iter()
+        .map(|&squared| bosonic_entropy((squared.max(0.0).sqrt() - 1.0) / 2.0))
+        .sum()
+}
+
+/// Entropy `g(x) = (x + 1)·log2(x + 1) − x·log2(x)` of a thermal state with mean photon number `x`.
+fn bosonic_entropy(x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    (x + 1.0) * (x + 1.0).log2() - x * x.log2()
+}
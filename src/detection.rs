@@ -0,0 +1,351 @@
+use crate::output::{Column, ColumnType, OutputRecord};
+use crate::plot::{Figure, Point, Series};
+use crate::protocol::QKD;
+use crate::statistics::Proportion;
+use crate::utils::derive_seed;
+use bon::Builder;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+
+/// Maximum number of qubits simulated in a single trial.
+pub const MAX_QUBITS_PER_TRIAL: usize = 1 << 28;
+
+/// Monte Carlo estimation of the probability of detecting an eavesdropper.
+///
+/// Every trial simulates the protocol until enough bits are sifted and takes a
+/// random test sample of each size. A test sample of size `k` aborts the protocol
+/// if more than `threshold * k` of its bits are mismatched, as in `QKD`. Trials
+/// without Eve give the rate of false aborts caused by honest noise, which is
+/// configured in the physical layer of the protocol.
+#[derive(Builder, Clone, Debug)]
+pub struct DetectionAnalysis {
+    /// Interception rates of Eve. Rates of zero are ignored, since trials
+    /// without Eve are always simulated.
+    interception_rates: Vec<f64>,
+    /// Numbers of check bits publicly compared by Alice and Bob.
+    sample_sizes: Vec<usize>,
+    /// Maximum fractions (0.0 to 1.0) of mismatched check bits tolerated.
+    thresholds: Vec<f64>,
+    /// Number of trials per interception rate.
+    #[builder(default = 1000)]
+    trials: usize,
+    /// Confidence level (0.0 to 1.0) of the intervals.
+    #[builder(default = 0.95)]
+    confidence: f64,
+}
+
+impl DetectionAnalysis {
+    /// Checks that the abort test of a protocol is the comparison of check bits
+    /// against a QBER threshold, which is the test modelled by the analysis.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the protocol, reported in the error message.
+    /// * `protocol` - Protocol to analyze.
+    ///
+    /// # Returns
+    ///
+    /// An error message if the protocol has its own security check, like RFI.
+    pub fn check_protocol(name: &str, protocol: &QKD) -> Result<(), String> {
+        if protocol.uses_qber_threshold() {
+            Ok(())
+        } else {
+            Err(format!(
+                "`{}` does not abort based on a QBER threshold, so it cannot be analyzed",
+                name
+            ))
+        }
+    }
+
+    /// Estimates the detection and false-abort probabilities of a protocol.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the protocol, reported in the records.
+    /// * `protocol` - Protocol to analyze, including its physical layer.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// One record per interception rate, sample size and threshold, or an error
+    /// message if the protocol does not sift enough bits or does not abort
+    /// based on a QBER threshold.
+    pub fn run(
+        &self,
+        name: &str,
+        protocol: &QKD,
+        seed: u64,
+    ) -> Result<Vec<DetectionRecord>, String> {
+        let rates = self
+            .interception_rates
+            .iter()
+            .copied()
+            .filter(|&rate| rate > 0.0)
+            .collect::<Vec<f64>>();
+        if rates.is_empty() {
+            return Err("At least one positive interception rate is required".to_string());
+        }
+        if self.sample_sizes.contains(&0) {
+            return Err("Sample sizes must be greater than zero".to_string());
+        }
+        Self::check_protocol(name, protocol)?;
+
+        let honest = self.abort_counts(name, protocol, 0.0, derive_seed(seed, 0))?;
+        let mut records = Vec::new();
+        for (i, &rate) in rates.iter().enumerate() {
+            let attacked =
+                self.abort_counts(name, protocol, rate, derive_seed(seed, i as u64 + 1))?;
+            for (j, &sample_size) in self.sample_sizes.iter().enumerate() {
+                for (l, &threshold) in self.thresholds.iter().enumerate() {
+                    let index = j * self.thresholds.len() + l;
+                    records.push(DetectionRecord {
+                        protocol: name.to_string(),
+                        interception_rate: rate,
+                        noise: protocol.physical_layer().channel.depolarization,
+                        sample_size,
+                        threshold,
+                        detection: Proportion::wilson(
+                            attacked[index],
+                            self.trials,
+                            self.confidence,
+                        ),
+                        false_abort: Proportion::wilson(
+                            honest[index],
+                            self.trials,
+                            self.confidence,
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    /// Counts the trials that abort the protocol, for every sample size and
+    /// threshold in row-major order.
+    fn abort_counts(
+        &self,
+        name: &str,
+        protocol: &QKD,
+        interception_rate: f64,
+        seed: u64,
+    ) -> Result<Vec<usize>, String> {
+        let largest = self.sample_sizes.iter().copied().max().unwrap_or(1);
+        // The first trial finds how many qubits are needed, so that the other
+        // trials rarely have to simulate more.
+        let (first, number_of_qubits) =
+            self.sample_errors(name, protocol, interception_rate, seed, 4 * largest)?;
+        let others = (1..self.trials)
+            .into_par_iter()
+            .map(|trial| {
+                let seed = derive_seed(seed, trial as u64);
+                let number_of_qubits = number_of_qubits + number_of_qubits / 4;
+                self.sample_errors(name, protocol, interception_rate, seed, number_of_qubits)
+                    .map(|(errors, _)| errors)
+            })
+            .collect::<Result<Vec<Vec<usize>>, String>>()?;
+
+        let mut counts = vec![0; self.sample_sizes.len() * self.thresholds.len()];
+        for errors in std::iter::once(first).chain(others) {
+            for (j, (&sample_size, &mismatched)) in
+                self.sample_sizes.iter().zip(&errors).enumerate()
+            {
+                for (l, &threshold) in self.thresholds.iter().enumerate() {
+                    if mismatched as f64 > threshold * sample_size as f64 {
+                        counts[j * self.thresholds.len() + l] += 1;
+                    }
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Simulates a trial and counts the mismatched bits of the test sample of every size.
+    ///
+    /// # Returns
+    ///
+    /// The number of mismatched bits per sample size and the number of qubits simulated.
+    fn sample_errors(
+        &self,
+        name: &str,
+        protocol: &QKD,
+        interception_rate: f64,
+        seed: u64,
+        number_of_qubits: usize,
+    ) -> Result<(Vec<usize>, usize), String> {
+        let largest = self.sample_sizes.iter().copied().max().unwrap_or(1);
+        let mut number_of_qubits = number_of_qubits.max(1);
+        loop {
+            let errors = protocol.sifted_errors(number_of_qubits, interception_rate, seed);
+            if errors.len() >= largest {
+                let counts = self
+                    .sample_sizes
+                    .iter()
+                    .map(|&sample_size| errors[..sample_size].iter().filter(|&&e| e).count())
+                    .collect();
+                return Ok((counts, number_of_qubits));
+            }
+            if number_of_qubits >= MAX_QUBITS_PER_TRIAL {
+                return Err(format!(
+                    "`{}` sifted {} bits out of {} qubits, fewer than the sample size {}",
+                    name,
+                    errors.len(),
+                    number_of_qubits,
+                    largest
+                ));
+            }
+            number_of_qubits = (2 * number_of_qubits).min(MAX_QUBITS_PER_TRIAL);
+        }
+    }
+}
+
+/// Detection and false-abort probabilities of a protocol for a test sample size and threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetectionRecord {
+    /// Name of the analyzed protocol.
+    pub protocol: String,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Depolarization probability of the channel, the honest noise causing false aborts.
+    pub noise: f64,
+    /// Number of check bits publicly compared.
+    pub sample_size: usize,
+    /// Maximum fraction of mismatched check bits tolerated.
+    pub threshold: f64,
+    /// Probability that the protocol is aborted when Eve is present.
+    pub detection: Proportion,
+    /// Probability that the protocol is aborted without Eve.
+    pub false_abort: Proportion,
+}
+
+impl DetectionRecord {
+    /// Returns the probability that Eve is present and the protocol is not aborted.
+    pub fn missed_detection(&self) -> f64 {
+        1.0 - self.detection.estimate
+    }
+
+    /// Returns the record formatted for the terminal, in the order of
+    /// `DETECTION_TABLE_HEADER`.
+    pub fn to_table_row(&self) -> [String; 8] {
+        let format_proportion = |proportion: &Proportion| {
+            let (low, high) = proportion.confidence_interval;
+            format!("{:.4} [{:.4}, {:.4}]", proportion.estimate, low, high)
+        };
+        [
+            self.protocol.clone(),
+            self.interception_rate.to_string(),
+            self.noise.to_string(),
+            self.sample_size.to_string(),
+            self.threshold.to_string(),
+            format_proportion(&self.detection),
+            format!("{:.4}", self.missed_detection()),
+            format_proportion(&self.false_abort),
+        ]
+    }
+}
+
+/// Header of the detection table printed in the terminal.
+pub const DETECTION_TABLE_HEADER: [&str; 8] = [
+    "PROTOCOL",
+    "interception_rate",
+    "noise",
+    "sample_size",
+    "threshold",
+    "detection_probability",
+    "missed_detection",
+    "false_abort_rate",
+];
+
+impl OutputRecord for DetectionRecord {
+    const COLUMNS: &'static [Column] = &[
+        Column::new("protocol", ColumnType::Text, false),
+        Column::new("interception_rate", ColumnType::Float, false),
+        Column::new("noise", ColumnType::Float, false),
+        Column::new("sample_size", ColumnType::Integer, false),
+        Column::new("threshold", ColumnType::Float, false),
+        Column::new("trials", ColumnType::Integer, false),
+        Column::new("detection_probability", ColumnType::Float, false),
+        Column::new("detection_ci_low", ColumnType::Float, false),
+        Column::new("detection_ci_high", ColumnType::Float, false),
+        Column::new("missed_detection_rate", ColumnType::Float, false),
+        Column::new("false_abort_rate", ColumnType::Float, false),
+        Column::new("false_abort_ci_low", ColumnType::Float, false),
+        Column::new("false_abort_ci_high", ColumnType::Float, false),
+    ];
+
+    fn to_csv_row(&self) -> Vec<String> {
+        let json = self.to_json();
+        Self::COLUMNS
+            .iter()
+            .map(|column| match &json[column.name] {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "protocol": self.protocol,
+            "interception_rate": self.interception_rate,
+            "noise": self.noise,
+            "sample_size": self.sample_size,
+            "threshold": self.threshold,
+            "trials": self.detection.trials,
+            "detection_probability": self.detection.estimate,
+            "detection_ci_low": self.detection.confidence_interval.0,
+            "detection_ci_high": self.detection.confidence_interval.1,
+            "missed_detection_rate": self.missed_detection(),
+            "false_abort_rate": self.false_abort.estimate,
+            "false_abort_ci_low": self.false_abort.confidence_interval.0,
+            "false_abort_ci_high": self.false_abort.confidence_interval.1,
+        })
+    }
+}
+
+/// Builds the ROC curves of the records: the false-abort rate against the
+/// missed-detection rate as the threshold varies, with one curve per protocol,
+/// noise, sample size and interception rate.
+///
+/// # Returns
+///
+/// The figure, or `None` if there are no records.
+pub fn roc_figure(records: &[DetectionRecord]) -> Option<Figure> {
+    let mut series: Vec<Series> = Vec::new();
+    for record in records {
+        let label = format!(
+            "{}, noise={}, k={}, rate={}",
+            record.protocol, record.noise, record.sample_size, record.interception_rate
+        );
+        let (low, high) = record.detection.confidence_interval;
+        let point = Point {
+            x: record.false_abort.estimate,
+            y: record.missed_detection(),
+            x_interval: Some(record.false_abort.confidence_interval),
+            y_interval: Some((1.0 - high, 1.0 - low)),
+        };
+        match series.iter_mut().find(|series| series.label == label) {
+            Some(series) => series.points.push(point),
+            None => series.push(Series {
+                label,
+                points: vec![point],
+            }),
+        }
+    }
+    if series.is_empty() {
+        return None;
+    }
+    for series in &mut series {
+        series
+            .points
+            .sort_by(|a, b| a.x.total_cmp(&b.x).then(b.y.total_cmp(&a.y)));
+    }
+
+    Some(Figure {
+        title: "ROC of the abort test".to_string(),
+        x_label: "False-abort rate (no Eve)".to_string(),
+        y_label: "Missed-detection rate (Eve present)".to_string(),
+        log_x: false,
+        series,
+    })
+}
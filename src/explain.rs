@@ -0,0 +1,578 @@
+use crate::mdi::BellState;
+use crate::protocol::QKD;
+use crate::transcript::RoundRole;
+use crate::types::{ComplexMatrix, Qubit};
+use crate::utils::X;
+use num_complex::Complex64;
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::fmt;
+
+/// Tolerance used to recognize the states of the common bases.
+const TOLERANCE: f64 = 1e-9;
+
+/// Named states of the Z, X and Y bases, with the name of their basis.
+const NAMED_STATES: [(&str, &str, (Complex64, Complex64)); 6] = [
+    (
+        "|0⟩",
+        "Z",
+        (Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)),
+    ),
+    (
+        "|1⟩",
+        "Z",
+        (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)),
+    ),
+    (
+        "|+⟩",
+        "X",
+        (
+            Complex64::new(FRAC_1_SQRT_2, 0.0),
+            Complex64::new(FRAC_1_SQRT_2, 0.0),
+        ),
+    ),
+    (
+        "|−⟩",
+        "X",
+        (
+            Complex64::new(FRAC_1_SQRT_2, 0.0),
+            Complex64::new(-FRAC_1_SQRT_2, 0.0),
+        ),
+    ),
+    (
+        "|+i⟩",
+        "Y",
+        (
+            Complex64::new(FRAC_1_SQRT_2, 0.0),
+            Complex64::new(0.0, FRAC_1_SQRT_2),
+        ),
+    ),
+    (
+        "|−i⟩",
+        "Y",
+        (
+            Complex64::new(FRAC_1_SQRT_2, 0.0),
+            Complex64::new(0.0, -FRAC_1_SQRT_2),
+        ),
+    ),
+];
+
+/// What Eve did with the qubit of a round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EveAction {
+    /// Basis used by Eve to measure.
+    pub basis: String,
+    /// Bit measured by Eve.
+    pub value: bool,
+    /// State resent by Eve to Bob, as a ket, or `None` if Eve is the relay,
+    /// which announces a Bell state instead.
+    pub resent_state: Option<String>,
+}
+
+/// A round of the quantum phase, described for teaching purposes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExplainedRound {
+    /// Bit encoded by Alice, as derived by the sifting strategy (for B92,
+    /// from her basis).
+    pub alice_value: bool,
+    /// Basis chosen by Alice.
+    pub alice_basis: String,
+    /// State sent by Alice, as a ket.
+    pub sent_state: String,
+    /// Interception by Eve, if any.
+    pub eve: Option<EveAction>,
+    /// Basis chosen by Bob to measure, or to prepare his qubit if the
+    /// protocol has a relay.
+    pub bob_basis: String,
+    /// Bit measured by Bob, or prepared by him if the protocol has a relay.
+    pub bob_value: bool,
+    /// State sent by Bob to the relay, as a ket, if the protocol has one.
+    pub bob_sent_state: Option<String>,
+    /// Bell state announced by the relay, if the protocol has one.
+    pub announcement: Option<BellState>,
+    /// Role assigned to the round during the public discussion phase.
+    pub role: RoundRole,
+}
+
+/// A sifted bit compared publicly or kept for the key, with the values
+/// derived by the sifting strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SiftedBit {
+    /// Index of the round of the bit.
+    pub round: usize,
+    /// Bit of Alice.
+    pub alice_value: bool,
+    /// Bit of Bob.
+    pub bob_value: bool,
+    /// Bit measured by Eve, if she intercepted the round.
+    pub eve_value: Option<bool>,
+}
+
+impl SiftedBit {
+    /// Returns `true` if Eve knows the bit and Alice and Bob agree on it,
+    /// as counted by the `eve_knowledge` of `QKDResult`.
+    pub fn is_known_by_eve(&self) -> bool {
+        self.alice_value == self.bob_value && self.eve_value == Some(self.alice_value)
+    }
+}
+
+/// Round-by-round walkthrough of a small protocol execution.
+///
+/// The rounds are simulated and sifted exactly as in `QKD::run_with_seed`,
+/// so the explanation describes the same execution. The `Display`
+/// implementation prints every step of the walkthrough.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    /// Name of the explained protocol.
+    pub protocol: String,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Maximum fraction of mismatched check bits tolerated.
+    pub qber_threshold: f64,
+    /// Whether Alice and Bob send their qubits to a relay instead of Bob
+    /// measuring Alice's.
+    pub has_relay: bool,
+    /// How the sifting strategy chooses the check and key bits.
+    pub sifting_rules: String,
+    /// Security check of the sifting strategy applied to the check bits.
+    pub security_check: String,
+    /// Every round of the quantum phase, in order.
+    pub rounds: Vec<ExplainedRound>,
+    /// Bits compared publicly to detect Eve.
+    pub check_bits: Vec<SiftedBit>,
+    /// Bits kept for the key.
+    pub key_bits: Vec<SiftedBit>,
+    /// Whether the check bits passed the security check.
+    pub is_considered_secure: bool,
+}
+
+impl Explanation {
+    /// Executes a protocol and describes every round.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - Name of the protocol, reported in the walkthrough.
+    /// * `qkd` - Protocol instance to execute.
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// The `Explanation` of the execution.
+    pub fn new(
+        protocol: &str,
+        qkd: &QKD,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> Self {
+        let results = qkd.simulate(number_of_qubits, interception_rate, seed);
+        let (alice_bases, bob_bases, eve_bases) = qkd.bases();
+        let has_relay = qkd.has_relay();
+        let mut rounds = results
+            .iter()
+            .map(|result| {
+                let alice_matrix = &alice_bases[result.alice_basis];
+                let bob_matrix = &bob_bases[result.bob_basis];
+                let sent_state = apply(alice_matrix, result.alice_value);
+                let eve = result
+                    .eve_value
+                    .zip(result.eve_basis)
+                    .map(|(value, basis)| {
+                        let restore = eve_bases[basis].invert().unwrap();
+                        EveAction {
+                            basis: measurement_basis_name(&eve_bases[basis], basis),
+                            value,
+                            resent_state: (!has_relay).then(|| ket(apply(&restore, value))),
+                        }
+                    });
+                let bob_basis = if has_relay {
+                    preparation_basis_name(bob_matrix, result.bob_basis)
+                } else {
+                    measurement_basis_name(bob_matrix, result.bob_basis)
+                };
+                ExplainedRound {
+                    alice_value: result.alice_value,
+                    alice_basis: preparation_basis_name(alice_matrix, result.alice_basis),
+                    sent_state: ket(sent_state),
+                    eve,
+                    bob_basis,
+                    bob_value: result.bob_value,
+                    bob_sent_state: has_relay.then(|| ket(apply(bob_matrix, result.bob_value))),
+                    announcement: result.announcement,
+                    role: RoundRole::Discarded,
+                }
+            })
+            .collect::<Vec<ExplainedRound>>();
+
+        let discussion_result = qkd.sift_block(results, seed, 0);
+        for (round, result) in rounds.iter_mut().zip(&discussion_result.results) {
+            round.alice_value = result.alice_value;
+        }
+        let sifted_bits = |indexes: &[usize], role: RoundRole, rounds: &mut [ExplainedRound]| {
+            indexes
+                .iter()
+                .map(|&i| {
+                    rounds[i].role = role;
+                    let result = &discussion_result.results[i];
+                    SiftedBit {
+                        round: i,
+                        alice_value: result.alice_value,
+                        bob_value: result.bob_value,
+                        eve_value: result.eve_value,
+                    }
+                })
+                .collect::<Vec<SiftedBit>>()
+        };
+        let mut check_bits = sifted_bits(
+            &discussion_result.indexes_to_check,
+            RoundRole::Check,
+            &mut rounds,
+        );
+        let mut key_bits = sifted_bits(
+            &discussion_result.indexes_to_key,
+            RoundRole::Key,
+            &mut rounds,
+        );
+        check_bits.sort_by_key(|bit| bit.round);
+        key_bits.sort_by_key(|bit| bit.round);
+
+        Explanation {
+            protocol: protocol.to_string(),
+            interception_rate,
+            qber_threshold: qkd.qber_threshold(),
+            has_relay,
+            sifting_rules: qkd.describe_sifting(),
+            security_check: qkd.describe_security_check(&discussion_result),
+            rounds,
+            check_bits,
+            key_bits,
+            is_considered_secure: qkd.check_public_values(&discussion_result),
+        }
+    }
+
+    /// Returns the steps of the walkthrough: the quantum transmission, the
+    /// sifting, the check-bit comparison and the key extraction.
+    pub fn steps(&self) -> Vec<String> {
+        vec![
+            self.transmission_step(),
+            self.sifting_step(),
+            self.check_step(),
+            self.key_step(),
+        ]
+    }
+
+    fn transmission_step(&self) -> String {
+        if self.has_relay {
+            return self.relay_transmission_step();
+        }
+        let rows = self
+            .rounds
+            .iter()
+            .enumerate()
+            .map(|(i, round)| {
+                vec![
+                    i.to_string(),
+                    bit(round.alice_value).to_string(),
+                    round.alice_basis.clone(),
+                    round.sent_state.clone(),
+                    round.eve.as_ref().map_or("-".to_string(), |eve| {
+                        format!(
+                            "measures {} → {}, resends {}",
+                            eve.basis,
+                            bit(eve.value),
+                            eve.resent_state.as_deref().unwrap_or("-")
+                        )
+                    }),
+                    round.bob_basis.clone(),
+                    bit(round.bob_value).to_string(),
+                    round.role.as_str().to_string(),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        let intercepted = self
+            .rounds
+            .iter()
+            .filter(|round| round.eve.is_some())
+            .count();
+
+        format!(
+            "Step 1: quantum transmission\n\n\
+             Alice chooses a random bit and basis for every qubit and sends the\n\
+             resulting state. Eve intercepts each qubit with probability {}, measuring\n\
+             it in a random basis and resending the state she measured ({} of {} qubits).\n\
+             Bob measures every qubit in a random basis.\n\n{}",
+            self.interception_rate,
+            intercepted,
+            self.rounds.len(),
+            table(
+                &[
+                    "round",
+                    "alice_bit",
+                    "alice_basis",
+                    "sent",
+                    "eve",
+                    "bob_basis",
+                    "bob_bit",
+                    "sifting"
+                ],
+                &rows
+            )
+        )
+    }
+
+    /// Describes the transmission of measurement-device-independent protocols,
+    /// where Bob prepares qubits and Eve is the relay.
+    fn relay_transmission_step(&self) -> String {
+        let rows = self
+            .rounds
+            .iter()
+            .enumerate()
+            .map(|(i, round)| {
+                let announcement = round
+                    .announcement
+                    .map_or("-".to_string(), |state| state.to_string());
+                vec![
+                    i.to_string(),
+                    bit(round.alice_value).to_string(),
+                    round.alice_basis.clone(),
+                    round.sent_state.clone(),
+                    bit(round.bob_value).to_string(),
+                    round.bob_basis.clone(),
+                    round.bob_sent_state.clone().unwrap_or_default(),
+                    match &round.eve {
+                        Some(eve) => format!(
+                            "malicious: measures {} → {}, announces {}",
+                            eve.basis,
+                            bit(eve.value),
+                            announcement
+                        ),
+                        None => format!("announces {}", announcement),
+                    },
+                    round.role.as_str().to_string(),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        let malicious = self
+            .rounds
+            .iter()
+            .filter(|round| round.eve.is_some())
+            .count();
+
+        format!(
+            "Step 1: quantum transmission\n\n\
+             Alice and Bob each choose a random bit and basis for every qubit and send the\n\
+             resulting states to an untrusted relay, Charlie, who performs a Bell-state\n\
+             measurement and announces |Ψ+⟩ or |Ψ−⟩. Rounds projected onto |Φ+⟩ or |Φ−⟩\n\
+             fail and are not shown. Charlie is malicious with probability {}: he measures\n\
+             both qubits in the same random basis and announces a Bell state consistent\n\
+             with his outcomes ({} of {} rounds).\n\n{}",
+            self.interception_rate,
+            malicious,
+            self.rounds.len(),
+            table(
+                &[
+                    "round",
+                    "alice_bit",
+                    "alice_basis",
+                    "alice_sent",
+                    "bob_bit",
+                    "bob_basis",
+                    "bob_sent",
+                    "charlie",
+                    "sifting"
+                ],
+                &rows
+            )
+        )
+    }
+
+    fn sifting_step(&self) -> String {
+        let sifted = self.check_bits.len() + self.key_bits.len();
+        format!(
+            "Step 2: sifting\n\n\
+             Alice and Bob publicly discuss their choices (never their bits) following\n\
+             the rules of {}. {} of the {} rounds are kept and {} are discarded.\n\
+             {}\n\n\
+             Check bits: {}. Key bits: {}.",
+            self.protocol,
+            sifted,
+            self.rounds.len(),
+            self.rounds.len() - sifted,
+            self.sifting_rules,
+            self.check_bits.len(),
+            self.key_bits.len()
+        )
+    }
+
+    fn check_step(&self) -> String {
+        let rows = self
+            .check_bits
+            .iter()
+            .map(|check| {
+                vec![
+                    check.round.to_string(),
+                    bit(check.alice_value).to_string(),
+                    bit(check.bob_value).to_string(),
+                    if check.alice_value == check.bob_value {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                    .to_string(),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        let mismatched = self
+            .check_bits
+            .iter()
+            .filter(|check| check.alice_value != check.bob_value)
+            .count();
+        let verdict = if self.is_considered_secure {
+            "The check passes, so the protocol continues."
+        } else {
+            "The check fails, so Alice and Bob cannot rule out Eve and abort the protocol."
+        };
+
+        format!(
+            "Step 3: check-bit comparison\n\n\
+             Alice and Bob publicly reveal the check bits, which are discarded afterwards.\n\n{}\n\
+             {} of {} check bits are mismatched.\n{}\n{}",
+            table(&["round", "alice_bit", "bob_bit", "match"], &rows),
+            mismatched,
+            self.check_bits.len(),
+            self.security_check,
+            verdict
+        )
+    }
+
+    fn key_step(&self) -> String {
+        if !self.is_considered_secure {
+            return "Step 4: key extraction\n\nThe protocol was aborted, so no key is extracted."
+                .to_string();
+        }
+        let key = |value: fn(&SiftedBit) -> Option<bool>| {
+            self.key_bits
+                .iter()
+                .map(|key_bit| value(key_bit).map_or('-', bit))
+                .collect::<String>()
+        };
+        let mismatched = self
+            .key_bits
+            .iter()
+            .filter(|key_bit| key_bit.alice_value != key_bit.bob_value)
+            .count();
+        let known = self
+            .key_bits
+            .iter()
+            .filter(|key_bit| key_bit.is_known_by_eve())
+            .count();
+
+        format!(
+            "Step 4: key extraction\n\n\
+             The remaining sifted bits form the key, which is never revealed.\n\n\
+             Alice: {}\n\
+             Bob:   {}\n\
+             Eve:   {}\n\n\
+             {} of {} key bits are mismatched and Eve knows {} of the correct ones.",
+            key(|key_bit| Some(key_bit.alice_value)),
+            key(|key_bit| Some(key_bit.bob_value)),
+            key(|key_bit| key_bit.eve_value),
+            mismatched,
+            self.key_bits.len(),
+            known
+        )
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.steps().join("\n\n"))
+    }
+}
+
+/// Returns the state `matrix |value⟩`.
+fn apply(matrix: &ComplexMatrix, value: bool) -> (Complex64, Complex64) {
+    let mut qubit = Qubit::new();
+    if value {
+        qubit.apply_transformation(&X);
+    }
+    qubit.apply_transformation(matrix);
+    (qubit.get_zero_coef(), qubit.get_one_coef())
+}
+
+/// Returns the named state equal to `state` up to a global phase, if any.
+fn named_state(state: (Complex64, Complex64)) -> Option<(&'static str, &'static str)> {
+    let pivot = if state.0.norm() > TOLERANCE {
+        state.0
+    } else {
+        state.1
+    };
+    let phase = pivot.conj() / pivot.norm();
+    let (a, b) = (state.0 * phase, state.1 * phase);
+    NAMED_STATES
+        .iter()
+        .find(|(_, _, (x, y))| (a - x).norm() < TOLERANCE && (b - y).norm() < TOLERANCE)
+        .map(|&(name, basis, _)| (name, basis))
+}
+
+/// Formats a state as a ket, using the names of the Z, X and Y states when possible.
+pub fn ket(state: (Complex64, Complex64)) -> String {
+    match named_state(state) {
+        Some((name, _)) => name.to_string(),
+        None => format!("({:.3})|0⟩ + ({:.3})|1⟩", state.0, state.1),
+    }
+}
+
+/// Returns the name of the basis prepared by `matrix`, or its index if it is not Z, X or Y.
+fn preparation_basis_name(matrix: &ComplexMatrix, index: usize) -> String {
+    named_state(apply(matrix, false))
+        .map_or_else(|| format!("#{}", index), |(_, basis)| basis.to_string())
+}
+
+/// Returns the name of the basis measured by applying `matrix` and measuring in Z.
+fn measurement_basis_name(matrix: &ComplexMatrix, index: usize) -> String {
+    match matrix.invert() {
+        Some(inverse) => preparation_basis_name(&inverse, index),
+        None => format!("#{}", index),
+    }
+}
+
+fn bit(value: bool) -> char {
+    if value {
+        '1'
+    } else {
+        '0'
+    }
+}
+
+/// Formats rows as a left-aligned table.
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<usize>>();
+    let format_row = |columns: Vec<&str>| {
+        columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, &width)| format!("{:<width$}", column))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    std::iter::once(format_row(header.to_vec()))
+        .chain(
+            rows.iter()
+                .map(|row| format_row(row.iter().map(String::as_str).collect())),
+        )
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -0,0 +1,386 @@
+use crate::physical::PhysicalLayer;
+use crate::postprocessing::{key_bytes, PostProcessing};
+use crate::protocol::QKDResult;
+use crate::protocols::{ProtocolRegistry, QkdProtocol as Protocol};
+use crate::transcript::Transcript;
+use crate::utils::random_seed;
+
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// Version of the C API, increased on every incompatible change.
+pub const QKD_ABI_VERSION: u32 = 1;
+
+/// Outcome of a call to the C API.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QkdStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument is null.
+    NullPointer = 1,
+    /// A numeric argument is out of range or a string is not valid UTF-8.
+    InvalidArgument = 2,
+    /// The protocol name is not registered.
+    UnknownProtocol = 3,
+    /// The simulator panicked. The handles passed to the call are left unchanged.
+    Panic = 4,
+}
+
+/// Protocol configured through the C API. Opaque to C callers.
+pub struct QkdProtocol {
+    name: String,
+    protocol: Arc<dyn Protocol>,
+    number_of_qubits: usize,
+    interception_rate: f64,
+    physical_layer: PhysicalLayer,
+    qber_threshold: f64,
+    seed: Option<u64>,
+}
+
+/// Result of an execution run through the C API, with its final key. Opaque to C callers.
+pub struct QkdRun {
+    result: QKDResult,
+    key: Vec<u8>,
+}
+
+/// Metrics of an execution, mirroring `QKDResult`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QkdResult {
+    /// Execution time in microseconds.
+    pub elapsed_time_us: u64,
+    /// Whether the check bits were accepted.
+    pub is_considered_secure: bool,
+    /// Length of the sifted key, or 0 if the protocol was aborted.
+    pub key_length: usize,
+    /// QBER of the check bits, or NaN if it is not defined.
+    pub quantum_bit_error_rate: f64,
+    /// Fraction of the sifted key known by Eve.
+    pub eve_knowledge: f64,
+}
+
+/// Returns the version of the C API the library was built with.
+#[no_mangle]
+pub extern "C" fn qkd_abi_version() -> u32 {
+    QKD_ABI_VERSION
+}
+
+/// Creates a protocol handle with 1000 qubits, no eavesdropper and ideal hardware.
+///
+/// # Arguments
+///
+/// * `name` - Null-terminated name of a registered protocol.
+/// * `protocol` - Where the new handle is written, to be released with `qkd_protocol_free`.
+///
+/// # Safety
+///
+/// `name` must be a valid null-terminated string and `protocol` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_new(
+    name: *const c_char,
+    protocol: *mut *mut QkdProtocol,
+) -> QkdStatus {
+    if name.is_null() || protocol.is_null() {
+        return QkdStatus::NullPointer;
+    }
+    let Ok(name) = CStr::from_ptr(name).to_str() else {
+        return QkdStatus::InvalidArgument;
+    };
+    let Some(registered) = ProtocolRegistry::default().get(name) else {
+        return QkdStatus::UnknownProtocol;
+    };
+    *protocol = Box::into_raw(Box::new(QkdProtocol {
+        name: name.to_string(),
+        protocol: registered,
+        number_of_qubits: 1000,
+        interception_rate: 0.0,
+        physical_layer: PhysicalLayer::default(),
+        qber_threshold: 0.0,
+        seed: None,
+    }));
+    QkdStatus::Ok
+}
+
+/// Releases a protocol handle. Null handles are ignored.
+///
+/// # Safety
+///
+/// `protocol` must be null or a handle created by `qkd_protocol_new` and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_free(protocol: *mut QkdProtocol) {
+    if !protocol.is_null() {
+        drop(Box::from_raw(protocol));
+    }
+}
+
+/// Sets the number of qubits sent by Alice.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_number_of_qubits(
+    protocol: *mut QkdProtocol,
+    number_of_qubits: usize,
+) -> QkdStatus {
+    configure(protocol, |protocol| {
+        protocol.number_of_qubits = number_of_qubits;
+        QkdStatus::Ok
+    })
+}
+
+/// Sets the probability (0.0 to 1.0) that Eve intercepts a qubit.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_interception_rate(
+    protocol: *mut QkdProtocol,
+    interception_rate: f64,
+) -> QkdStatus {
+    configure_probability(protocol, interception_rate, |protocol| {
+        &mut protocol.interception_rate
+    })
+}
+
+/// Sets the probability (0.0 to 1.0) that the channel depolarizes a qubit.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_depolarization(
+    protocol: *mut QkdProtocol,
+    depolarization: f64,
+) -> QkdStatus {
+    configure_probability(protocol, depolarization, |protocol| {
+        &mut protocol.physical_layer.channel.depolarization
+    })
+}
+
+/// Sets the probability (0.0 to 1.0) that a photon is lost in the channel,
+/// in addition to the fiber attenuation.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_loss(protocol: *mut QkdProtocol, loss: f64) -> QkdStatus {
+    configure_probability(protocol, loss, |protocol| {
+        &mut protocol.physical_layer.channel.loss
+    })
+}
+
+/// Sets the length in kilometers of the fiber between Alice and Bob.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_distance_km(
+    protocol: *mut QkdProtocol,
+    distance_km: f64,
+) -> QkdStatus {
+    configure(protocol, |protocol| {
+        if !(distance_km >= 0.0 && distance_km.is_finite()) {
+            return QkdStatus::InvalidArgument;
+        }
+        protocol.physical_layer.channel.distance_km = distance_km;
+        QkdStatus::Ok
+    })
+}
+
+/// Sets the efficiency and the dark count probability (0.0 to 1.0) of Bob's detector.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_detector(
+    protocol: *mut QkdProtocol,
+    efficiency: f64,
+    dark_count_probability: f64,
+) -> QkdStatus {
+    configure(protocol, |protocol| {
+        if !is_probability(efficiency) || !is_probability(dark_count_probability) {
+            return QkdStatus::InvalidArgument;
+        }
+        protocol.physical_layer.detector.efficiency = efficiency;
+        protocol.physical_layer.detector.dark_count_probability = dark_count_probability;
+        QkdStatus::Ok
+    })
+}
+
+/// Sets the maximum fraction (0.0 to 1.0) of mismatched check bits tolerated before aborting.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_qber_threshold(
+    protocol: *mut QkdProtocol,
+    qber_threshold: f64,
+) -> QkdStatus {
+    configure_probability(protocol, qber_threshold, |protocol| {
+        &mut protocol.qber_threshold
+    })
+}
+
+/// Seeds every following run, which makes them reproducible. Runs are seeded
+/// from the operating system entropy source until a seed is set.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_set_seed(protocol: *mut QkdProtocol, seed: u64) -> QkdStatus {
+    configure(protocol, |protocol| {
+        protocol.seed = Some(seed);
+        QkdStatus::Ok
+    })
+}
+
+/// Executes the protocol and, if it is not aborted, reconciles and amplifies
+/// the sifted key into the final key.
+///
+/// # Arguments
+///
+/// * `protocol` - Configured protocol handle.
+/// * `run` - Where the new run handle is written, to be released with `qkd_run_free`.
+///
+/// # Safety
+///
+/// `protocol` must be null or a valid handle, and `run` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_protocol_run(
+    protocol: *const QkdProtocol,
+    run: *mut *mut QkdRun,
+) -> QkdStatus {
+    let (Some(protocol), false) = (protocol.as_ref(), run.is_null()) else {
+        return QkdStatus::NullPointer;
+    };
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        let qkd = protocol
+            .protocol
+            .build()
+            .with_physical_layer(protocol.physical_layer)
+            .with_qber_threshold(protocol.qber_threshold);
+        let seed = protocol.seed.unwrap_or_else(random_seed);
+        let (result, transcript) = Transcript::record(
+            &protocol.name,
+            &qkd,
+            protocol.number_of_qubits,
+            protocol.interception_rate,
+            seed,
+        );
+        let mut key = Vec::new();
+        if let (true, Ok(sifted_key)) = (result.is_considered_secure, transcript.sifted_key(&qkd)) {
+            let processed = PostProcessing::builder().build().run(&sifted_key, seed);
+            if processed.keys_match {
+                key = key_bytes(&processed.key);
+            }
+        }
+        QkdRun { result, key }
+    }));
+    match outcome {
+        Ok(outcome) => {
+            *run = Box::into_raw(Box::new(outcome));
+            QkdStatus::Ok
+        }
+        Err(_) => QkdStatus::Panic,
+    }
+}
+
+/// Releases a run handle and its key. Null handles are ignored.
+///
+/// # Safety
+///
+/// `run` must be null or a handle created by `qkd_protocol_run` and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_run_free(run: *mut QkdRun) {
+    if !run.is_null() {
+        drop(Box::from_raw(run));
+    }
+}
+
+/// Reads the metrics of a run.
+///
+/// # Safety
+///
+/// `run` must be null or a valid handle, and `result` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_run_result(run: *const QkdRun, result: *mut QkdResult) -> QkdStatus {
+    let (Some(run), false) = (run.as_ref(), result.is_null()) else {
+        return QkdStatus::NullPointer;
+    };
+    *result = QkdResult {
+        elapsed_time_us: run.result.elapsed_time.as_micros() as u64,
+        is_considered_secure: run.result.is_considered_secure,
+        key_length: run.result.key_length.unwrap_or(0),
+        quantum_bit_error_rate: run.result.quantum_bit_error_rate.unwrap_or(f64::NAN),
+        eve_knowledge: run.result.eve_knowledge,
+    };
+    QkdStatus::Ok
+}
+
+/// Borrows the final key of a run, packed most significant bit first.
+///
+/// The key is empty if the protocol was aborted, the reconciliation failed
+/// or no secret bits remain after the privacy amplification.
+///
+/// # Arguments
+///
+/// * `run` - Run handle.
+/// * `key` - Where the pointer to the key bytes is written. It remains valid
+///   until the run is released, and must not be freed by the caller.
+/// * `length` - Where the number of key bytes is written.
+///
+/// # Safety
+///
+/// `run` must be null or a valid handle, and `key` and `length` valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn qkd_run_key(
+    run: *const QkdRun,
+    key: *mut *const u8,
+    length: *mut usize,
+) -> QkdStatus {
+    let (Some(run), false, false) = (run.as_ref(), key.is_null(), length.is_null()) else {
+        return QkdStatus::NullPointer;
+    };
+    *key = run.key.as_ptr();
+    *length = run.key.len();
+    QkdStatus::Ok
+}
+
+/// Applies a change to a protocol handle, if it is not null.
+unsafe fn configure(
+    protocol: *mut QkdProtocol,
+    change: impl FnOnce(&mut QkdProtocol) -> QkdStatus,
+) -> QkdStatus {
+    match protocol.as_mut() {
+        Some(protocol) => change(protocol),
+        None => QkdStatus::NullPointer,
+    }
+}
+
+/// Sets a probability of a protocol handle, checking that it is between 0 and 1.
+unsafe fn configure_probability(
+    protocol: *mut QkdProtocol,
+    value: f64,
+    field: impl FnOnce(&mut QkdProtocol) -> &mut f64,
+) -> QkdStatus {
+    configure(protocol, |protocol| {
+        if !is_probability(value) {
+            return QkdStatus::InvalidArgument;
+        }
+        *field(protocol) = value;
+        QkdStatus::Ok
+    })
+}
+
+fn is_probability(value: f64) -> bool {
+    (0.0..=1.0).contains(&value)
+}
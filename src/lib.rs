@@ -1,27 +1,138 @@
+/// Module implementing the simulation backends.
+/// Contains the Backend selector and the precomputed probability tables and
+/// bit-packed rounds used by the fast statistical backend.
+pub mod backend;
+
+/// Module implementing the checkpoints of long-running sweeps.
+/// Saves the results of every completed configuration next to the output so
+/// that `sweep --resume` continues an interrupted sweep with the same ids and seeds.
+pub mod checkpoint;
+
+/// Module implementing the command-line interface.
+/// Exposes an entry point parameterized by a ProtocolRegistry so that downstream
+/// crates can ship a CLI including their own protocols.
+pub mod cli;
+
+/// Module defining declarative configurations of protocols and experiments.
+/// Contains ProtocolConfig and ExperimentConfig, which can be loaded from files
+/// when the `serde` feature is enabled.
+pub mod config;
+
+/// Module implementing the GG02 continuous-variable QKD protocol.
+/// Simulates Gaussian-modulated coherent states with homodyne or heterodyne detection,
+/// registered through the CvQkdProtocol trait, and bounds the key rate with the Holevo information.
+pub mod cvqkd;
+
+/// Module estimating the probability of detecting an eavesdropper.
+/// Provides the Monte Carlo `DetectionAnalysis` of the `analyze` subcommand
+/// and the ROC curves of false aborts against missed detections.
+pub mod detection;
+
+/// Module explaining protocol executions round by round.
+/// Provides the `Explanation` of the `explain` subcommand, which describes the
+/// states sent as kets, Eve's actions, the sifting and the key extraction.
+pub mod explain;
+
+/// Module implementing the stable C API exported by the `cdylib` feature.
+/// Provides opaque protocol and run handles with explicit free functions,
+/// described by the header `include/qkd.h` generated with cbindgen.
+#[cfg(feature = "cdylib")]
+pub mod ffi;
+
+/// Module implementing measurement-device-independent QKD (MDI-QKD).
+/// Contains the Relay participant, the MdiSifting strategy and the registered MDI protocol,
+/// in which an untrusted relay announces the outcome of a partial Bell-state measurement.
+pub mod mdi;
+
+/// Module tracking the live progress of parameter sweeps.
+/// Provides the per-configuration `SweepProgress` with abort counts, running
+/// QBER and key lengths, the estimated time left and histograms.
+pub mod monitor;
+
+/// Module implementing the output formats of experiment results.
+/// Provides the ResultWriter for CSV, JSON, JSON Lines and Parquet files,
+/// together with the metadata describing each run.
+pub mod output;
+
+/// Module modeling the imperfections of the physical layer.
+/// Contains the photon Source, the lossy and noisy Channel and the Detector,
+/// grouped in the PhysicalLayer applied to Bob's measurements.
+pub mod physical;
+
+/// Module rendering simulation results as SVG charts.
+/// Provides the `Chart` kinds of the `plot` subcommand and a minimal
+/// `Figure` renderer with error bars and one curve per protocol.
+pub mod plot;
+
 /// Module containing the implementation of QKD protocol participants (Alice, Bob, and Eve).
 /// Provides structs and builders for creating and configuring participants with their
 /// respective quantum bases and behaviors.
 pub mod participants;
 
+/// Module implementing the classical post-processing of sifted keys.
+/// Provides the Cascade-style error reconciliation and the Toeplitz hashing
+/// used for privacy amplification by the `postprocess` and `keygen` subcommands.
+pub mod postprocessing;
+
+/// Module implementing the Python bindings of the simulator.
+/// Exposes QKD, the physical layer, QKDResult and the protocol entry points
+/// as the `qkd` extension module, with results as dicts or NumPy arrays.
+#[cfg(feature = "python")]
+pub mod python;
+
 /// Module implementing the core Quantum Key Distribution protocols.
-/// Contains the main QKD struct, protocol execution logic, and result types
-/// including QKDResult and PublicDiscussionResult.
+/// Contains the main QKD struct, protocol execution logic, the sifting strategy
+/// trait, and result types including QKDResult and PublicDiscussionResult.
 pub mod protocol;
 
+/// Module containing the concrete protocols shipped with the crate (BB84, Six-State,
+/// B92, RFI-QKD and MDI-QKD), the QkdProtocol trait and the ProtocolRegistry used by the CLI.
+pub mod protocols;
+
+/// Module implementing the statistics aggregated across repetitions.
+/// Provides summaries with Student's t confidence intervals and Wilson score
+/// intervals for the abort probability of a configuration.
+pub mod statistics;
+
+/// Module implementing the expressions accepted by numeric parameters.
+/// Expands single values, `start:end:step` ranges, and `linspace` and `logspace`
+/// calls into the values of a parameter sweep.
+pub mod sweep;
+
+/// Module comparing simulation results with analytic predictions.
+/// Provides the `TheoreticalPrediction` of a protocol under intercept-resend
+/// attacks and the per-metric `ComparisonRecord`s of the `--theory` mode.
+pub mod theory;
+
+/// Module implementing per-round transcripts of protocol executions.
+/// Provides the Transcript struct to export every round with its sifting
+/// decision and to replay the post-processing from a saved file.
+pub mod transcript;
+
+/// Module implementing the terminal dashboard of the `sweep --tui` mode.
+/// Renders the live `SweepProgress` of a sweep with ratatui and lets the user
+/// cancel it while keeping the results collected so far.
+#[cfg(feature = "tui")]
+pub mod tui;
+
 /// Module defining fundamental quantum types and structures.
-/// Includes the Qubit struct and related quantum state representations
-/// used throughout the QKD simulations.
+/// Includes the Qubit struct, the N-qubit state-vector Register and related
+/// quantum state representations used throughout the QKD simulations.
 pub mod types;
 
 /// Module providing utility functions and common quantum operations.
-/// Contains mathematical utilities, basis matrices (I, H, H_Y), and
-/// helper functions like shuffle_and_split for protocol execution.
+/// Contains mathematical utilities, single-qubit gates (I, H, H_Y, Pauli, S, T and
+/// rotations), Bloch-sphere bases and helpers like shuffle_and_split.
 pub mod utils;
 
-use crate::participants::{Receiver, Sender};
-use crate::protocol::{PublicDiscussionResult, QExecutionResult, QKDResult, QKD};
-use crate::types::Qubit;
-use crate::utils::{shuffle_and_split, H, H_Y, I};
+/// Module implementing the WebAssembly bindings of the `wasm` feature.
+/// Exposes experiment runs and per-round transcripts to JavaScript through
+/// wasm-bindgen, returning them as plain objects.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use crate::protocol::QKDResult;
+use crate::protocols::{QkdProtocol, SixState, B92, BB84};
 
 /// Executes the BB84 QKD protocol with the specified number of qubits and interception rate.
 ///
@@ -32,11 +143,7 @@ use crate::utils::{shuffle_and_split, H, H_Y, I};
 /// # Returns
 /// A `QKDResult` containing the protocol execution results.
 pub fn run_bb84(number_of_qubits: usize, interception_rate: f64) -> QKDResult {
-    let alice = Sender::builder().posible_basis(vec![I, H]).build();
-    let bob = Receiver::builder().posible_basis(vec![I, H]).build();
-
-    let bb84 = QKD::builder().alice(alice).bob(bob).build();
-    bb84.run(number_of_qubits, interception_rate)
+    BB84.run(number_of_qubits, interception_rate)
 }
 
 /// Executes the Six-State QKD protocol with the specified number of qubits and interception rate.
@@ -48,16 +155,7 @@ pub fn run_bb84(number_of_qubits: usize, interception_rate: f64) -> QKDResult {
 /// # Returns
 /// A `QKDResult` containing the protocol execution results.
 pub fn run_six_state(number_of_qubits: usize, interception_rate: f64) -> QKDResult {
-    let alice = Sender::builder().posible_basis(vec![I, H, H_Y]).build();
-    let bob = Receiver::builder()
-        .posible_basis(vec![I, H, H_Y.invert().unwrap()])
-        .build();
-    let eve = Receiver::builder()
-        .posible_basis(vec![I, H, H_Y.invert().unwrap()])
-        .build();
-
-    let six_state = QKD::builder().alice(alice).bob(bob).eve(eve).build();
-    six_state.run(number_of_qubits, interception_rate)
+    SixState.run(number_of_qubits, interception_rate)
 }
 
 /// Executes the B92 QKD protocol with the specified number of qubits and interception rate.
@@ -69,56 +167,5 @@ pub fn run_six_state(number_of_qubits: usize, interception_rate: f64) -> QKDResu
 /// # Returns
 /// A `QKDResult` containing the protocol execution results.
 pub fn run_b92(number_of_qubits: usize, interception_rate: f64) -> QKDResult {
-    let prepare_b92 = Box::new(|| (Qubit::new(), false));
-
-    let alice = Sender::builder()
-        .posible_basis(vec![I, H])
-        .prepare(prepare_b92)
-        .build();
-    let bob = Receiver::builder().posible_basis(vec![I, H]).build();
-
-    let b92 = QKD::builder()
-        .alice(alice)
-        .bob(bob)
-        .public_basis_discussion(Box::new(public_basis_discussion_b92))
-        .build();
-    b92.run(number_of_qubits, interception_rate)
-}
-
-/// Performs the public basis discussion specific to the B92 protocol.
-///
-/// # Arguments
-/// * `results` - Vector of execution results from the B92 protocol.
-///
-/// # Returns
-/// A `PublicDiscussionResult` containing the results of the public discussion phase.
-fn public_basis_discussion_b92(results: &Vec<QExecutionResult>) -> PublicDiscussionResult {
-    let mut results = results.clone();
-    let bob_values: Vec<bool> = results.iter().map(|x| x.bob_value).collect();
-
-    let conclusive_indexes = bob_values
-        .iter()
-        .enumerate()
-        .filter_map(|(i, &value)| if value { Some(i) } else { None })
-        .collect::<Vec<usize>>();
-
-    results.iter_mut().enumerate().for_each(|(i, result)| {
-        if conclusive_indexes.contains(&i) {
-            result.bob_value = (1 - result.bob_basis) == 1;
-        }
-        result.alice_value = result.alice_basis == 1;
-    });
-
-    let (indexes_to_check, indexes_to_key) = shuffle_and_split(conclusive_indexes);
-    let (alice_public_values, bob_public_values) = indexes_to_check
-        .iter()
-        .map(|&i| (results[i].alice_value, results[i].bob_value))
-        .unzip();
-
-    PublicDiscussionResult {
-        alice_public_values,
-        bob_public_values,
-        indexes_to_key,
-        results,
-    }
+    B92.run(number_of_qubits, interception_rate)
 }
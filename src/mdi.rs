@@ -0,0 +1,272 @@
+use crate::participants::{Receiver, Sender};
+use crate::protocol::{
+    default_public_basis_discussion, PublicDiscussionResult, QExecutionResult, SiftingStrategy, QKD,
+};
+use crate::protocols::QkdProtocol;
+use crate::types::{ComplexMatrix, Qubit, Register};
+use crate::utils::{rand_index, H, I, X};
+use bon::Builder;
+use num_complex::Complex64;
+use rand::RngCore;
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Bell states that Charlie's measurement projects the two qubits onto.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BellState {
+    /// (|00⟩ + |11⟩)/√2
+    PhiPlus,
+    /// (|00⟩ − |11⟩)/√2
+    PhiMinus,
+    /// (|01⟩ + |10⟩)/√2
+    PsiPlus,
+    /// (|01⟩ − |10⟩)/√2
+    PsiMinus,
+}
+
+impl BellState {
+    /// Bell states that a linear-optics relay can identify, and therefore announce.
+    pub const ANNOUNCED: [BellState; 2] = [BellState::PsiPlus, BellState::PsiMinus];
+
+    /// Returns the amplitudes of the state in the computational basis.
+    pub fn amplitudes(&self) -> Vec<Complex64> {
+        let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(FRAC_1_SQRT_2, 0.0));
+        match self {
+            BellState::PhiPlus => vec![one, zero, zero, one],
+            BellState::PhiMinus => vec![one, zero, zero, -one],
+            BellState::PsiPlus => vec![zero, one, one, zero],
+            BellState::PsiMinus => vec![zero, one, -one, zero],
+        }
+    }
+
+    /// Returns the correlation between the bits of Alice and Bob when Charlie
+    /// announces this state.
+    ///
+    /// # Arguments
+    ///
+    /// * `alice_basis` - Preparation basis of Alice's qubit.
+    /// * `bob_basis` - Preparation basis of Bob's qubit.
+    ///
+    /// # Returns
+    ///
+    /// The probability that both bits are equal minus the probability that they
+    /// differ: 1.0 if they are equal, -1.0 if they are opposite and 0.0 if they
+    /// are uncorrelated.
+    pub fn correlation(&self, alice_basis: &ComplexMatrix, bob_basis: &ComplexMatrix) -> f64 {
+        let mut register =
+            Register::from_amplitudes(self.amplitudes()).expect("Bell states are normalized");
+        register.apply_transformation(0, &alice_basis.adjoint());
+        register.apply_transformation(1, &bob_basis.adjoint());
+        let amplitudes = register.amplitudes();
+        amplitudes[0].norm_sqr() + amplitudes[3].norm_sqr()
+            - amplitudes[1].norm_sqr()
+            - amplitudes[2].norm_sqr()
+    }
+
+    /// Returns the name used to store the state in a transcript file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BellState::PhiPlus => "phi+",
+            BellState::PhiMinus => "phi-",
+            BellState::PsiPlus => "psi+",
+            BellState::PsiMinus => "psi-",
+        }
+    }
+}
+
+impl FromStr for BellState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "phi+" => Ok(BellState::PhiPlus),
+            "phi-" => Ok(BellState::PhiMinus),
+            "psi+" => Ok(BellState::PsiPlus),
+            "psi-" => Ok(BellState::PsiMinus),
+            _ => Err(format!("`{}` is not a valid Bell state", s)),
+        }
+    }
+}
+
+impl fmt::Display for BellState {
+    /// Formats the state as a ket, e.g. `|Ψ+⟩`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BellState::PhiPlus => "Φ+",
+            BellState::PhiMinus => "Φ−",
+            BellState::PsiPlus => "Ψ+",
+            BellState::PsiMinus => "Ψ−",
+        };
+        write!(f, "|{}⟩", name)
+    }
+}
+
+/// Untrusted relay (Charlie) of measurement-device-independent protocols.
+///
+/// Instead of measuring Alice's qubits, Bob prepares his own with `bob`, and
+/// both qubits are sent to Charlie, who performs a partial Bell-state
+/// measurement and announces its outcome. A linear-optics relay can only
+/// identify the states |Ψ+⟩ and |Ψ−⟩, so rounds projected onto |Φ±⟩ fail.
+///
+/// A malicious Charlie takes the place of Eve: he measures both qubits in the
+/// same random basis of Eve and announces a Bell state consistent with his
+/// outcomes, or a failure if there is none.
+#[derive(Builder, Clone)]
+pub struct Relay {
+    /// Sender preparing Bob's qubits.
+    pub(crate) bob: Sender,
+}
+
+impl Relay {
+    /// Projects the qubits of Alice and Bob onto a Bell state.
+    ///
+    /// # Returns
+    ///
+    /// The announced Bell state, or `None` if the measurement failed.
+    pub(crate) fn measure(
+        &self,
+        alice_qubit: Qubit,
+        bob_qubit: Qubit,
+        rng: &mut dyn RngCore,
+    ) -> Option<BellState> {
+        let mut register = Register::from_qubits(&[alice_qubit, bob_qubit]);
+        register.apply_controlled(&[0], 1, &X);
+        register.apply_transformation(0, &H);
+        let sign = register.measure(0, rng);
+        let parity = register.measure(1, rng);
+        match (parity, sign) {
+            (true, false) => Some(BellState::PsiPlus),
+            (true, true) => Some(BellState::PsiMinus),
+            // |Φ+⟩ and |Φ−⟩ cannot be told apart, so the measurement fails.
+            (false, _) => None,
+        }
+    }
+
+    /// Measures both qubits in a random basis of `eve` and announces a Bell
+    /// state consistent with the outcomes.
+    ///
+    /// # Returns
+    ///
+    /// The value measured on Alice's qubit, the index of the basis used and
+    /// the announced Bell state, or `None` if no state is consistent.
+    pub(crate) fn intercept(
+        &self,
+        eve: &Receiver,
+        mut alice_qubit: Qubit,
+        mut bob_qubit: Qubit,
+        rng: &mut dyn RngCore,
+    ) -> (bool, usize, Option<BellState>) {
+        let basis = rand_index(eve.posible_basis.len(), rng);
+        let matrix = &eve.posible_basis[basis];
+        alice_qubit.apply_transformation(matrix);
+        let alice_value = eve.strategy.measure(&mut alice_qubit, rng);
+        bob_qubit.apply_transformation(matrix);
+        let bob_value = eve.strategy.measure(&mut bob_qubit, rng);
+
+        // The measured states are the ones prepared by the inverse matrix.
+        let prepared = matrix.invert().expect("measurement bases are invertible");
+        let candidates = BellState::ANNOUNCED
+            .into_iter()
+            .filter(|state| {
+                (state.correlation(&prepared, &prepared) > 0.0) == (alice_value == bob_value)
+            })
+            .collect::<Vec<BellState>>();
+        let announcement = match candidates.len() {
+            0 => None,
+            len => Some(candidates[rand_index(len, rng)]),
+        };
+        (alice_value, basis, announcement)
+    }
+}
+
+/// Sifting strategy of measurement-device-independent protocols.
+///
+/// In the announced rounds, the bits of Alice and Bob are correlated or
+/// anti-correlated depending on the Bell state and the bases, so Bob flips his
+/// bit when they are anti-correlated. The rounds with matching bases are then
+/// split into check and key bits as in `default_public_basis_discussion`.
+#[derive(Clone, Debug)]
+pub struct MdiSifting {
+    /// Preparation bases of Alice.
+    alice_bases: Vec<ComplexMatrix>,
+    /// Preparation bases of Bob.
+    bob_bases: Vec<ComplexMatrix>,
+}
+
+impl MdiSifting {
+    /// Creates the sifting strategy for the preparation bases of Alice and Bob.
+    pub fn new(alice_bases: Vec<ComplexMatrix>, bob_bases: Vec<ComplexMatrix>) -> Self {
+        MdiSifting {
+            alice_bases,
+            bob_bases,
+        }
+    }
+}
+
+impl SiftingStrategy for MdiSifting {
+    fn sift(
+        &self,
+        mut results: Vec<QExecutionResult>,
+        rng: &mut dyn RngCore,
+    ) -> PublicDiscussionResult {
+        for result in &mut results {
+            if let Some(announcement) = result.announcement {
+                let correlation = announcement.correlation(
+                    &self.alice_bases[result.alice_basis],
+                    &self.bob_bases[result.bob_basis],
+                );
+                result.bob_value ^= correlation < 0.0;
+            }
+        }
+        default_public_basis_discussion(results, rng)
+    }
+
+    fn describe_sifting(&self) -> String {
+        "Bob flips his bit when the announced Bell state anti-correlates the bits in\n\
+         his basis (|Ψ−⟩ in both bases, |Ψ+⟩ only in Z), so the bits below are his\n\
+         corrected ones. Half of the kept rounds are chosen at random as check bits,\n\
+         and the rest are kept for the key."
+            .to_string()
+    }
+}
+
+/// Measurement-device-independent protocol (MDI-QKD) with BB84 states.
+///
+/// Alice and Bob both prepare qubits in the Z and X bases and send them to an
+/// untrusted `Relay`. Since Alice and Bob do not measure anything, the
+/// protocol is immune to every attack on the detectors. The interception rate
+/// is the probability that Charlie is malicious in a round.
+///
+/// The physical layer places Charlie in the middle of the channel, so every
+/// qubit crosses half of it. Only the transmittance and the depolarization of
+/// the channel apply.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MDI;
+
+impl QkdProtocol for MDI {
+    fn name(&self) -> &str {
+        "MDI"
+    }
+
+    fn description(&self) -> &str {
+        "Measurement-device-independent protocol with BB84 states and an untrusted Bell-state relay"
+    }
+
+    fn build(&self) -> QKD {
+        let alice = Sender::builder().posible_basis(vec![I, H]).build();
+        let bob = Receiver::builder().posible_basis(vec![I, H]).build();
+        let relay = Relay::builder()
+            .bob(Sender::builder().posible_basis(vec![I, H]).build())
+            .build();
+
+        QKD::builder()
+            .alice(alice)
+            .bob(bob)
+            .relay(relay)
+            .sifting(Arc::new(MdiSifting::new(vec![I, H], vec![I, H])))
+            .build()
+    }
+}
@@ -0,0 +1,190 @@
+use crate::cvqkd::CvParameters;
+use crate::output::ResultRecord;
+use std::time::Duration;
+
+/// Configuration of a sweep whose runs are monitored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitoredConfiguration {
+    /// Name of the simulated protocol.
+    pub protocol: String,
+    /// Number of qubits sent.
+    pub number_of_qubits: usize,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Length in kilometers of the fiber, if a channel is simulated.
+    pub distance_km: Option<f64>,
+    /// Excess noise and modulation variance, for a continuous-variable protocol.
+    pub parameters: Option<CvParameters>,
+    /// Number of runs (repetitions) of the configuration.
+    pub runs: usize,
+}
+
+impl MonitoredConfiguration {
+    /// Returns the name of the protocol, followed by its parameters for a
+    /// continuous-variable protocol.
+    pub fn label(&self) -> String {
+        match self.parameters {
+            Some(parameters) => format!(
+                "{} (ξ={}, V_A={})",
+                self.protocol, parameters.excess_noise, parameters.modulation_variance
+            ),
+            None => self.protocol.clone(),
+        }
+    }
+}
+
+/// Results received so far for a configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigurationProgress {
+    /// Monitored configuration.
+    pub configuration: MonitoredConfiguration,
+    /// Number of runs completed.
+    pub completed_runs: usize,
+    /// Number of completed runs that were aborted.
+    pub aborted_runs: usize,
+    /// QBER of every accepted run.
+    pub quantum_bit_error_rates: Vec<f64>,
+    /// Key length of every accepted run.
+    pub key_lengths: Vec<usize>,
+}
+
+impl ConfigurationProgress {
+    /// Returns the mean QBER of the accepted runs, if any.
+    pub fn mean_quantum_bit_error_rate(&self) -> Option<f64> {
+        mean(self.quantum_bit_error_rates.iter().copied())
+    }
+
+    /// Returns the mean key length of the accepted runs, if any.
+    pub fn mean_key_length(&self) -> Option<f64> {
+        mean(self.key_lengths.iter().map(|&key_length| key_length as f64))
+    }
+
+    /// Returns `true` if every run of the configuration is completed.
+    pub fn is_finished(&self) -> bool {
+        self.completed_runs == self.configuration.runs
+    }
+}
+
+/// Live progress of a sweep, updated as runs finish in any order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepProgress {
+    /// Progress of every configuration, in the order of the sweep.
+    pub configurations: Vec<ConfigurationProgress>,
+}
+
+impl SweepProgress {
+    /// Creates the progress of a sweep without any completed run.
+    pub fn new(configurations: Vec<MonitoredConfiguration>) -> Self {
+        SweepProgress {
+            configurations: configurations
+                .into_iter()
+                .map(|configuration| ConfigurationProgress {
+                    configuration,
+                    completed_runs: 0,
+                    aborted_runs: 0,
+                    quantum_bit_error_rates: Vec::new(),
+                    key_lengths: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Adds a completed run to the progress of its configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `configuration` - Index of the configuration of the run.
+    /// * `record` - Result of the run.
+    pub fn record(&mut self, configuration: usize, record: &ResultRecord) {
+        let progress = &mut self.configurations[configuration];
+        progress.completed_runs += 1;
+        if !record.result.is_considered_secure {
+            progress.aborted_runs += 1;
+            return;
+        }
+        progress
+            .quantum_bit_error_rates
+            .extend(record.result.quantum_bit_error_rate);
+        progress.key_lengths.extend(record.result.key_length);
+    }
+
+    /// Returns the total number of runs of the sweep.
+    pub fn total_runs(&self) -> usize {
+        self.configurations
+            .iter()
+            .map(|progress| progress.configuration.runs)
+            .sum()
+    }
+
+    /// Returns the number of runs completed so far.
+    pub fn completed_runs(&self) -> usize {
+        self.configurations
+            .iter()
+            .map(|progress| progress.completed_runs)
+            .sum()
+    }
+
+    /// Returns the number of runs aborted so far.
+    pub fn aborted_runs(&self) -> usize {
+        self.configurations
+            .iter()
+            .map(|progress| progress.aborted_runs)
+            .sum()
+    }
+
+    /// Estimates the time left, assuming the remaining runs take as long as the completed ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - Time elapsed since the sweep started.
+    ///
+    /// # Returns
+    ///
+    /// The estimated time left, or `None` if no run is completed yet.
+    pub fn estimated_time_left(&self, elapsed: Duration) -> Option<Duration> {
+        let completed = self.completed_runs();
+        if completed == 0 {
+            return None;
+        }
+        let remaining = self.total_runs() - completed;
+        Some(elapsed.mul_f64(remaining as f64 / completed as f64))
+    }
+}
+
+/// Counts the values falling in equally wide bins between their minimum and maximum.
+///
+/// # Arguments
+///
+/// * `values` - Values to count.
+/// * `bins` - Number of bins.
+///
+/// # Returns
+///
+/// The lower edge and count of every bin, or a single bin if all values are
+/// equal. Empty if there are no values.
+pub fn histogram(values: &[f64], bins: usize) -> Vec<(f64, usize)> {
+    let Some(min) = values.iter().copied().reduce(f64::min) else {
+        return Vec::new();
+    };
+    let max = values.iter().copied().fold(min, f64::max);
+    if max == min || bins <= 1 {
+        return vec![(min, values.len())];
+    }
+
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0; bins];
+    for value in values {
+        let bin = (((value - min) / width) as usize).min(bins - 1);
+        counts[bin] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (min + i as f64 * width, count))
+        .collect()
+}
+
+fn mean(values: impl ExactSizeIterator<Item = f64>) -> Option<f64> {
+    let count = values.len();
+    (count > 0).then(|| values.sum::<f64>() / count as f64)
+}
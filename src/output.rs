@@ -0,0 +1,969 @@
+use crate::cvqkd::{CvParameters, HolevoEstimate};
+use crate::protocol::QKDResult;
+use crate::statistics::{ResultStatistics, Summary};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Format of the files where experiment results are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OutputFormat {
+    /// Comma-separated values, one row per experiment.
+    ///
+    /// Kept compatible with previous versions: no metadata header. Metrics
+    /// that are not defined for aborted runs are left empty.
+    #[default]
+    Csv,
+    /// A single JSON document with the metadata and an array of results.
+    Json,
+    /// JSON Lines: the metadata in the first line and one result per line.
+    Jsonl,
+    /// Apache Parquet with typed columns and the metadata stored as the
+    /// `qkd.metadata` key. Requires the `parquet` feature.
+    Parquet,
+}
+
+/// Type of the values of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Signed 64-bit integers.
+    Integer,
+    /// 64-bit floating-point numbers.
+    Float,
+    /// Booleans.
+    Boolean,
+    /// UTF-8 strings.
+    Text,
+}
+
+/// Column of the records written by a `ResultWriter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Column {
+    /// Key of the column in the JSON representation of the records.
+    pub name: &'static str,
+    /// Type of the values of the column.
+    pub column_type: ColumnType,
+    /// Whether the column can contain null values.
+    pub nullable: bool,
+}
+
+impl Column {
+    /// Creates a column, so that other record types can declare their `COLUMNS`.
+    pub const fn new(name: &'static str, column_type: ColumnType, nullable: bool) -> Self {
+        Column {
+            name,
+            column_type,
+            nullable,
+        }
+    }
+}
+
+/// Record that can be written by a `ResultWriter`.
+pub trait OutputRecord {
+    /// Typed columns of the JSON representation, in order.
+    const COLUMNS: &'static [Column];
+
+    /// Returns the header of the CSV format.
+    ///
+    /// By default, it contains the names of `COLUMNS`.
+    fn csv_header() -> Vec<&'static str> {
+        Self::COLUMNS.iter().map(|column| column.name).collect()
+    }
+
+    /// Returns the values of the record in the order of `CSV_HEADER`.
+    fn to_csv_row(&self) -> Vec<String>;
+
+    /// Returns the record as a JSON object with the keys of `COLUMNS`.
+    fn to_json(&self) -> Value;
+}
+
+/// Column names of the CSV output and the terminal table.
+///
+/// `distance_km` and the parameters of continuous-variable protocols come
+/// last, so that the previous columns keep their positions.
+pub const CSV_HEADER: [&str; 17] = [
+    "id",
+    "PROTOCOL",
+    "number_of_qubits",
+    "interception_rate",
+    "time_μs",
+    "is_considered_secure",
+    "key_length",
+    "eve_knowledge",
+    "QBER",
+    "distance_km",
+    "transmittance",
+    "excess_noise",
+    "mutual_information",
+    "holevo_bound",
+    "key_rate",
+    "channel_excess_noise",
+    "modulation_variance",
+];
+
+/// Result of a single experiment together with its parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultRecord {
+    /// Identifier of the experiment.
+    pub id: usize,
+    /// Name of the scenario of an experiment file, if any.
+    /// Not included in the CSV format.
+    pub scenario: Option<String>,
+    /// Name of the simulated protocol.
+    pub protocol: String,
+    /// Number of qubits sent.
+    pub number_of_qubits: usize,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Length of the fiber in kilometers, if the experiment modeled one.
+    pub distance_km: Option<f64>,
+    /// Excess noise and modulation variance set for a continuous-variable
+    /// protocol. `None` for discrete-variable protocols.
+    pub parameters: Option<CvParameters>,
+    /// Result of the protocol execution.
+    pub result: QKDResult,
+    /// Parameters estimated by a continuous-variable protocol.
+    /// `None` for discrete-variable protocols and runs without enough pulses.
+    pub estimate: Option<HolevoEstimate>,
+}
+
+impl OutputRecord for ResultRecord {
+    const COLUMNS: &'static [Column] = &[
+        Column::new("id", ColumnType::Integer, false),
+        Column::new("scenario", ColumnType::Text, true),
+        Column::new("protocol", ColumnType::Text, false),
+        Column::new("number_of_qubits", ColumnType::Integer, false),
+        Column::new("interception_rate", ColumnType::Float, false),
+        Column::new("distance_km", ColumnType::Float, true),
+        Column::new("channel_excess_noise", ColumnType::Float, true),
+        Column::new("modulation_variance", ColumnType::Float, true),
+        Column::new("time_us", ColumnType::Integer, false),
+        Column::new("is_considered_secure", ColumnType::Boolean, false),
+        Column::new("key_length", ColumnType::Integer, true),
+        Column::new("eve_knowledge", ColumnType::Float, true),
+        Column::new("qber", ColumnType::Float, true),
+        Column::new("transmittance", ColumnType::Float, true),
+        Column::new("excess_noise", ColumnType::Float, true),
+        Column::new("mutual_information", ColumnType::Float, true),
+        Column::new("holevo_bound", ColumnType::Float, true),
+        Column::new("key_rate", ColumnType::Float, true),
+    ];
+
+    fn csv_header() -> Vec<&'static str> {
+        CSV_HEADER.to_vec()
+    }
+
+    /// Metrics that are not defined for aborted runs are left empty, like the
+    /// distance of experiments without a fiber and the columns of runs
+    /// without `CvParameters` or a `HolevoEstimate`.
+    fn to_csv_row(&self) -> Vec<String> {
+        let estimate = |value: fn(&HolevoEstimate) -> f64| {
+            self.estimate
+                .as_ref()
+                .map_or(String::new(), |estimate| value(estimate).to_string())
+        };
+        let parameter = |value: fn(&CvParameters) -> f64| {
+            self.parameters
+                .as_ref()
+                .map_or(String::new(), |parameters| value(parameters).to_string())
+        };
+        vec![
+            self.id.to_string(),
+            self.protocol.clone(),
+            self.number_of_qubits.to_string(),
+            self.interception_rate.to_string(),
+            self.result.elapsed_time.as_micros().to_string(),
+            self.result.is_considered_secure.to_string(),
+            self.result
+                .key_length
+                .map_or(String::new(), |key_length| key_length.to_string()),
+            if self.result.is_considered_secure {
+                self.result.eve_knowledge.to_string()
+            } else {
+                String::new()
+            },
+            self.result
+                .quantum_bit_error_rate
+                .filter(|qber| qber.is_finite())
+                .map_or(String::new(), |qber| qber.to_string()),
+            self.distance_km.map_or(String::new(), |d| d.to_string()),
+            estimate(|estimate| estimate.transmittance),
+            estimate(|estimate| estimate.excess_noise),
+            estimate(|estimate| estimate.mutual_information),
+            estimate(|estimate| estimate.holevo_bound),
+            estimate(|estimate| estimate.key_rate),
+            parameter(|parameters| parameters.excess_noise),
+            parameter(|parameters| parameters.modulation_variance),
+        ]
+    }
+
+    /// Metrics that are not defined for aborted runs are `null`.
+    fn to_json(&self) -> Value {
+        let is_considered_secure = self.result.is_considered_secure;
+        json!({
+            "id": self.id,
+            "scenario": self.scenario,
+            "protocol": self.protocol,
+            "number_of_qubits": self.number_of_qubits,
+            "interception_rate": self.interception_rate,
+            "distance_km": self.distance_km,
+            "channel_excess_noise": self.parameters.map(|parameters| parameters.excess_noise),
+            "modulation_variance": self.parameters.map(|parameters| parameters.modulation_variance),
+            "time_us": self.result.elapsed_time.as_micros() as u64,
+            "is_considered_secure": is_considered_secure,
+            "key_length": self.result.key_length,
+            "eve_knowledge": is_considered_secure.then_some(self.result.eve_knowledge),
+            "qber": self.result.quantum_bit_error_rate.filter(|qber| qber.is_finite()),
+            "transmittance": self.estimate.map(|estimate| estimate.transmittance),
+            "excess_noise": self.estimate.map(|estimate| estimate.excess_noise),
+            "mutual_information": self.estimate.map(|estimate| estimate.mutual_information),
+            "holevo_bound": self.estimate.map(|estimate| estimate.holevo_bound),
+            "key_rate": self.estimate.map(|estimate| estimate.key_rate),
+        })
+    }
+}
+
+impl ResultRecord {
+    /// Builds a record from its JSON representation, as written by `to_json`.
+    ///
+    /// # Returns
+    ///
+    /// The record, or an error message naming the first missing or invalid key.
+    pub fn from_json(json: &Value) -> Result<Self, String> {
+        let field = |key: &str| {
+            json.get(key)
+                .ok_or_else(|| format!("Missing key `{}` in result {}", key, json))
+        };
+        let invalid = |key: &str| format!("Invalid value for `{}` in result {}", key, json);
+        let integer = |key: &str| {
+            field(key)?
+                .as_u64()
+                .map(|value| value as usize)
+                .ok_or_else(|| invalid(key))
+        };
+        let float = |key: &str| field(key)?.as_f64().ok_or_else(|| invalid(key));
+        let optional = |key: &str| json.get(key).filter(|value| !value.is_null());
+
+        let is_considered_secure = field("is_considered_secure")?
+            .as_bool()
+            .ok_or_else(|| invalid("is_considered_secure"))?;
+        let key_length = optional("key_length")
+            .map(|value| {
+                value
+                    .as_u64()
+                    .map(|v| v as usize)
+                    .ok_or_else(|| invalid("key_length"))
+            })
+            .transpose()?;
+        let quantum_bit_error_rate = match optional("qber") {
+            Some(value) => Some(value.as_f64().ok_or_else(|| invalid("qber"))?),
+            None => is_considered_secure.then_some(f64::NAN),
+        };
+        let estimate = optional("transmittance")
+            .map(|_| {
+                Ok::<_, String>(HolevoEstimate {
+                    transmittance: float("transmittance")?,
+                    excess_noise: float("excess_noise")?,
+                    mutual_information: float("mutual_information")?,
+                    holevo_bound: float("holevo_bound")?,
+                    key_rate: float("key_rate")?,
+                })
+            })
+            .transpose()?;
+        let parameters = optional("channel_excess_noise")
+            .map(|_| {
+                Ok::<_, String>(CvParameters {
+                    excess_noise: float("channel_excess_noise")?,
+                    modulation_variance: float("modulation_variance")?,
+                })
+            })
+            .transpose()?;
+
+        Ok(ResultRecord {
+            id: integer("id")?,
+            scenario: optional("scenario")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            protocol: field("protocol")?
+                .as_str()
+                .ok_or_else(|| invalid("protocol"))?
+                .to_string(),
+            number_of_qubits: integer("number_of_qubits")?,
+            interception_rate: float("interception_rate")?,
+            distance_km: optional("distance_km").and_then(Value::as_f64),
+            parameters,
+            result: QKDResult {
+                elapsed_time: Duration::from_micros(integer("time_us")? as u64),
+                is_considered_secure,
+                key_length,
+                quantum_bit_error_rate,
+                eve_knowledge: optional("eve_knowledge")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0),
+            },
+            estimate,
+        })
+    }
+
+    /// Builds a record from a row of the CSV format, given the index of every column.
+    ///
+    /// The distance and the parameters of continuous-variable protocols are optional, so files written
+    /// by previous versions can be read. The metrics of aborted runs are
+    /// ignored, since previous versions wrote `0` and `-1` instead of empty fields.
+    fn from_csv_row(
+        row: &csv::StringRecord,
+        columns: &HashMap<&str, usize>,
+    ) -> Result<Self, String> {
+        let field = |name: &str| {
+            columns
+                .get(name)
+                .and_then(|&i| row.get(i))
+                .ok_or_else(|| format!("Missing column `{}`", name))
+        };
+        fn parse<T: FromStr>(value: &str, name: &str) -> Result<T, String> {
+            value
+                .parse()
+                .map_err(|_| format!("Invalid value `{}` for column `{}`", value, name))
+        }
+
+        let optional = |name: &str| {
+            field(name)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(|value| parse::<f64>(value, name))
+                .transpose()
+        };
+        let required = |name: &str| parse::<f64>(field(name)?, name);
+
+        let is_considered_secure = parse(field(CSV_HEADER[5])?, CSV_HEADER[5])?;
+        let estimate = optional(CSV_HEADER[10])?
+            .map(|transmittance| {
+                Ok::<_, String>(HolevoEstimate {
+                    transmittance,
+                    excess_noise: required(CSV_HEADER[11])?,
+                    mutual_information: required(CSV_HEADER[12])?,
+                    holevo_bound: required(CSV_HEADER[13])?,
+                    key_rate: required(CSV_HEADER[14])?,
+                })
+            })
+            .transpose()?;
+        let parameters = optional(CSV_HEADER[15])?
+            .map(|excess_noise| {
+                Ok::<_, String>(CvParameters {
+                    excess_noise,
+                    modulation_variance: required(CSV_HEADER[16])?,
+                })
+            })
+            .transpose()?;
+        Ok(ResultRecord {
+            id: parse(field(CSV_HEADER[0])?, CSV_HEADER[0])?,
+            scenario: None,
+            protocol: field(CSV_HEADER[1])?.to_string(),
+            number_of_qubits: parse(field(CSV_HEADER[2])?, CSV_HEADER[2])?,
+            interception_rate: parse(field(CSV_HEADER[3])?, CSV_HEADER[3])?,
+            distance_km: optional(CSV_HEADER[9])?,
+            parameters,
+            result: QKDResult {
+                elapsed_time: Duration::from_micros(parse(field(CSV_HEADER[4])?, CSV_HEADER[4])?),
+                is_considered_secure,
+                key_length: is_considered_secure
+                    .then(|| parse(field(CSV_HEADER[6])?, CSV_HEADER[6]))
+                    .transpose()?,
+                quantum_bit_error_rate: is_considered_secure
+                    .then(|| Ok::<_, String>(optional(CSV_HEADER[8])?.unwrap_or(f64::NAN)))
+                    .transpose()?,
+                eve_knowledge: is_considered_secure
+                    .then(|| optional(CSV_HEADER[7]))
+                    .transpose()?
+                    .flatten()
+                    .unwrap_or(0.0),
+            },
+            estimate,
+        })
+    }
+}
+
+/// Reads the per-run results written in the CSV, JSON or JSON Lines format.
+///
+/// The format is detected from the content of the file. Parquet files and
+/// aggregated results are not supported.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file to read.
+///
+/// # Returns
+///
+/// The records of the file, or an `InvalidData` error if it is malformed.
+pub fn read_records<P: AsRef<Path>>(path: P) -> io::Result<Vec<ResultRecord>> {
+    let content = std::fs::read_to_string(path)?;
+    let invalid_data = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    if !content.trim_start().starts_with('{') {
+        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let header = reader.headers()?.clone();
+        let columns = header
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name, i))
+            .collect::<HashMap<&str, usize>>();
+        return reader
+            .records()
+            .map(|row| ResultRecord::from_csv_row(&row?, &columns).map_err(invalid_data))
+            .collect();
+    }
+
+    let rows = match serde_json::from_str::<Value>(&content) {
+        Ok(json) => json
+            .get("results")
+            .and_then(Value::as_array)
+            .cloned()
+            .ok_or_else(|| invalid_data("Missing `results` array".to_string()))?,
+        Err(_) => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<Value>)
+            .filter(|json| !matches!(json, Ok(json) if json.get("metadata").is_some()))
+            .collect::<Result<Vec<Value>, _>>()
+            .map_err(|error| invalid_data(error.to_string()))?,
+    };
+    rows.iter()
+        .map(|row| ResultRecord::from_json(row).map_err(invalid_data))
+        .collect()
+}
+
+/// Groups the records that are repetitions of the same configuration.
+///
+/// A configuration is given by the scenario, protocol, number of qubits,
+/// interception rate, distance and parameters of continuous-variable protocols.
+///
+/// # Arguments
+///
+/// * `records` - Results of every experiment.
+///
+/// # Returns
+///
+/// The records of every configuration, in order of first appearance.
+pub fn group_by_configuration<'a>(
+    records: impl IntoIterator<Item = &'a ResultRecord>,
+) -> Vec<Vec<&'a ResultRecord>> {
+    let mut groups: Vec<Vec<&ResultRecord>> = Vec::new();
+    let mut indexes = HashMap::new();
+    for record in records {
+        let key = (
+            &record.scenario,
+            &record.protocol,
+            record.number_of_qubits,
+            record.interception_rate.to_bits(),
+            record.distance_km.map(f64::to_bits),
+            record.parameters.map(|parameters| {
+                (
+                    parameters.excess_noise.to_bits(),
+                    parameters.modulation_variance.to_bits(),
+                )
+            }),
+        );
+        let index = *indexes.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(record);
+    }
+    groups
+}
+
+/// Metadata describing how a set of results was generated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunMetadata {
+    /// Version of this crate.
+    pub crate_version: String,
+    /// Base seed of the simulation.
+    pub seed: u64,
+    /// Full configuration of the run.
+    pub configuration: Value,
+}
+
+impl RunMetadata {
+    /// Creates the metadata of a run with the current crate version.
+    pub fn new(seed: u64, configuration: Value) -> Self {
+        RunMetadata {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+            configuration,
+        }
+    }
+
+    /// Returns the metadata as a JSON object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "crate_version": self.crate_version,
+            "seed": self.seed,
+            "configuration": self.configuration,
+        })
+    }
+}
+
+/// Statistics of the repetitions of a configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateRecord {
+    /// Name of the scenario of an experiment file, if any.
+    pub scenario: Option<String>,
+    /// Name of the simulated protocol.
+    pub protocol: String,
+    /// Number of qubits sent.
+    pub number_of_qubits: usize,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Length in kilometers of the fiber, if a channel was simulated.
+    pub distance_km: Option<f64>,
+    /// Excess noise and modulation variance set for a continuous-variable protocol.
+    pub parameters: Option<CvParameters>,
+    /// Statistics of the results of every repetition.
+    pub statistics: ResultStatistics,
+}
+
+impl AggregateRecord {
+    /// Groups the records by configuration and aggregates each group.
+    ///
+    /// A configuration is given by the scenario, protocol, number of qubits,
+    /// interception rate, distance and parameters of continuous-variable
+    /// protocols. Groups are returned in order of first appearance.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - Results of every experiment.
+    /// * `confidence` - Confidence level (0.0 to 1.0) of the intervals.
+    pub fn group(records: &[ResultRecord], confidence: f64) -> Vec<AggregateRecord> {
+        group_by_configuration(records)
+            .into_iter()
+            .map(|group| {
+                let record = group[0];
+                let results = group
+                    .iter()
+                    .map(|record| record.result.clone())
+                    .collect::<Vec<QKDResult>>();
+                AggregateRecord {
+                    scenario: record.scenario.clone(),
+                    protocol: record.protocol.clone(),
+                    number_of_qubits: record.number_of_qubits,
+                    interception_rate: record.interception_rate,
+                    distance_km: record.distance_km,
+                    parameters: record.parameters,
+                    statistics: ResultStatistics::new(&results, confidence),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the main statistics formatted for the terminal, in the order of
+    /// `AGGREGATE_TABLE_HEADER`.
+    pub fn to_table_row(&self) -> [String; 12] {
+        let statistics = &self.statistics;
+        let (low, high) = statistics.abort.confidence_interval;
+        let parameter = |value: fn(&CvParameters) -> f64| {
+            self.parameters
+                .as_ref()
+                .map_or("-".to_string(), |parameters| value(parameters).to_string())
+        };
+        [
+            self.protocol.clone(),
+            self.number_of_qubits.to_string(),
+            self.interception_rate.to_string(),
+            self.distance_km.map_or("-".to_string(), |d| d.to_string()),
+            parameter(|parameters| parameters.excess_noise),
+            parameter(|parameters| parameters.modulation_variance),
+            statistics.abort.trials.to_string(),
+            format!("{:.4} [{:.4}, {:.4}]", statistics.abort.estimate, low, high),
+            format_summary(statistics.quantum_bit_error_rate.as_ref(), 4),
+            format_summary(statistics.key_length.as_ref(), 1),
+            format_summary(statistics.eve_knowledge.as_ref(), 4),
+            format_summary(Some(&statistics.time_us), 0),
+        ]
+    }
+}
+
+/// Column names of the terminal table of aggregated statistics.
+pub const AGGREGATE_TABLE_HEADER: [&str; 12] = [
+    "PROTOCOL",
+    "number_of_qubits",
+    "interception_rate",
+    "distance_km",
+    "channel_excess_noise",
+    "modulation_variance",
+    "runs",
+    "abort_probability",
+    "QBER",
+    "key_length",
+    "eve_knowledge",
+    "time_μs",
+];
+
+/// Formats a summary as `mean [low, high]`, or `-` if it is not defined.
+fn format_summary(summary: Option<&Summary>, decimals: usize) -> String {
+    match summary {
+        Some(summary) => match summary.confidence_interval {
+            Some((low, high)) => format!(
+                "{:.*} [{:.*}, {:.*}]",
+                decimals, summary.mean, decimals, low, decimals, high
+            ),
+            None => format!("{:.*}", decimals, summary.mean),
+        },
+        None => "-".to_string(),
+    }
+}
+
+/// Metrics of an `AggregateRecord`, as JSON key prefixes.
+const AGGREGATE_METRICS: [&str; 4] = ["qber", "key_length", "eve_knowledge", "time_us"];
+
+impl AggregateRecord {
+    /// Returns the summary of every metric, in the order of `AGGREGATE_METRICS`.
+    fn summaries(&self) -> [Option<&Summary>; 4] {
+        let statistics = &self.statistics;
+        [
+            statistics.quantum_bit_error_rate.as_ref(),
+            statistics.key_length.as_ref(),
+            statistics.eve_knowledge.as_ref(),
+            Some(&statistics.time_us),
+        ]
+    }
+}
+
+/// Defines the columns of an `AggregateRecord`, with five columns per metric.
+macro_rules! aggregate_columns {
+    ($($metric:literal),*) => {
+        [
+            Column::new("scenario", ColumnType::Text, true),
+            Column::new("protocol", ColumnType::Text, false),
+            Column::new("number_of_qubits", ColumnType::Integer, false),
+            Column::new("interception_rate", ColumnType::Float, false),
+            Column::new("distance_km", ColumnType::Float, true),
+            Column::new("channel_excess_noise", ColumnType::Float, true),
+            Column::new("modulation_variance", ColumnType::Float, true),
+            Column::new("runs", ColumnType::Integer, false),
+            Column::new("aborted_runs", ColumnType::Integer, false),
+            Column::new("abort_probability", ColumnType::Float, false),
+            Column::new("abort_ci_low", ColumnType::Float, false),
+            Column::new("abort_ci_high", ColumnType::Float, false),
+            $(
+                Column::new(concat!($metric, "_count"), ColumnType::Integer, false),
+                Column::new(concat!($metric, "_mean"), ColumnType::Float, true),
+                Column::new(concat!($metric, "_std"), ColumnType::Float, true),
+                Column::new(concat!($metric, "_ci_low"), ColumnType::Float, true),
+                Column::new(concat!($metric, "_ci_high"), ColumnType::Float, true),
+            )*
+        ]
+    };
+}
+
+impl OutputRecord for AggregateRecord {
+    const COLUMNS: &'static [Column] =
+        &aggregate_columns!("qber", "key_length", "eve_knowledge", "time_us");
+
+    /// Values that are not defined are written as empty fields.
+    fn to_csv_row(&self) -> Vec<String> {
+        let json = self.to_json();
+        Self::COLUMNS
+            .iter()
+            .map(|column| match &json[column.name] {
+                Value::Null => String::new(),
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            })
+            .collect()
+    }
+
+    /// Metrics of runs that were all aborted, and deviations and intervals of
+    /// single runs, are `null`.
+    fn to_json(&self) -> Value {
+        let abort = &self.statistics.abort;
+        let mut json = json!({
+            "scenario": self.scenario,
+            "protocol": self.protocol,
+            "number_of_qubits": self.number_of_qubits,
+            "interception_rate": self.interception_rate,
+            "distance_km": self.distance_km,
+            "channel_excess_noise": self.parameters.map(|parameters| parameters.excess_noise),
+            "modulation_variance": self.parameters.map(|parameters| parameters.modulation_variance),
+            "runs": abort.trials,
+            "aborted_runs": abort.successes,
+            "abort_probability": abort.estimate,
+            "abort_ci_low": abort.confidence_interval.0,
+            "abort_ci_high": abort.confidence_interval.1,
+        });
+        for (metric, summary) in AGGREGATE_METRICS.iter().zip(self.summaries()) {
+            let interval = summary.and_then(|summary| summary.confidence_interval);
+            json[format!("{}_count", metric)] = json!(summary.map_or(0, |summary| summary.count));
+            json[format!("{}_mean", metric)] = json!(summary.map(|summary| summary.mean));
+            json[format!("{}_std", metric)] =
+                json!(summary.and_then(|summary| summary.standard_deviation));
+            json[format!("{}_ci_low", metric)] = json!(interval.map(|(low, _)| low));
+            json[format!("{}_ci_high", metric)] = json!(interval.map(|(_, high)| high));
+        }
+        json
+    }
+}
+
+/// Number of records per row group of the Parquet format, so that only one
+/// group is kept in memory while writing.
+pub const PARQUET_ROWS_PER_GROUP: usize = 8192;
+
+/// Writer of experiment results in any `OutputFormat`.
+///
+/// Records are written as they arrive, except for Parquet, which buffers
+/// them until a row group of `PARQUET_ROWS_PER_GROUP` records is complete.
+pub struct ResultWriter<R: OutputRecord = ResultRecord> {
+    inner: WriterKind,
+    record_type: PhantomData<R>,
+}
+
+enum WriterKind {
+    Csv(Box<csv::Writer<File>>),
+    Json {
+        file: BufWriter<File>,
+        is_first: bool,
+    },
+    Jsonl(BufWriter<File>),
+    #[cfg(feature = "parquet")]
+    Parquet(Box<parquet_output::ParquetWriter>),
+}
+
+impl<R: OutputRecord> ResultWriter<R> {
+    /// Creates the output file and writes its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to create.
+    /// * `format` - Format of the file.
+    /// * `metadata` - Metadata of the run, ignored by the CSV format.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        format: OutputFormat,
+        metadata: &RunMetadata,
+    ) -> io::Result<Self> {
+        let inner = match format {
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_path(path)?;
+                writer.write_record(R::csv_header())?;
+                WriterKind::Csv(Box::new(writer))
+            }
+            OutputFormat::Json => {
+                let mut file = BufWriter::new(File::create(path)?);
+                write!(file, "{{\"metadata\":{},\"results\":[", metadata.to_json())?;
+                WriterKind::Json {
+                    file,
+                    is_first: true,
+                }
+            }
+            OutputFormat::Jsonl => {
+                let mut file = BufWriter::new(File::create(path)?);
+                writeln!(file, "{}", json!({ "metadata": metadata.to_json() }))?;
+                WriterKind::Jsonl(file)
+            }
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => WriterKind::Parquet(Box::new(
+                parquet_output::ParquetWriter::create(File::create(path)?, metadata, R::COLUMNS)
+                    .map_err(io::Error::other)?,
+            )),
+            #[cfg(not(feature = "parquet"))]
+            OutputFormat::Parquet => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Parquet output requires the `parquet` feature",
+                ))
+            }
+        };
+        Ok(ResultWriter {
+            inner,
+            record_type: PhantomData,
+        })
+    }
+
+    /// Writes a record.
+    pub fn write(&mut self, record: &R) -> io::Result<()> {
+        match &mut self.inner {
+            WriterKind::Csv(writer) => writer.write_record(record.to_csv_row())?,
+            WriterKind::Json { file, is_first } => {
+                if !*is_first {
+                    write!(file, ",")?;
+                }
+                *is_first = false;
+                write!(file, "{}", record.to_json())?;
+            }
+            WriterKind::Jsonl(file) => writeln!(file, "{}", record.to_json())?,
+            #[cfg(feature = "parquet")]
+            WriterKind::Parquet(writer) => {
+                writer.write(record.to_json()).map_err(io::Error::other)?
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the footer of the file and flushes it.
+    pub fn finish(self) -> io::Result<()> {
+        match self.inner {
+            WriterKind::Csv(mut writer) => writer.flush(),
+            WriterKind::Json { mut file, .. } => {
+                writeln!(file, "]}}")?;
+                file.flush()
+            }
+            WriterKind::Jsonl(mut file) => file.flush(),
+            #[cfg(feature = "parquet")]
+            WriterKind::Parquet(writer) => writer.finish().map_err(io::Error::other),
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_output {
+    use super::{Column, ColumnType, RunMetadata, PARQUET_ROWS_PER_GROUP};
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DataType, DoubleType, Int64Type};
+    use parquet::errors::Result;
+    use parquet::file::metadata::KeyValue;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+    use parquet::schema::parser::parse_message_type;
+    use serde_json::Value;
+    use std::fs::File;
+    use std::sync::Arc;
+
+    /// Key of the Parquet key-value metadata where the run metadata is stored.
+    const METADATA_KEY: &str = "qkd.metadata";
+
+    /// Builds the Parquet schema of the columns. Nullable columns are optional.
+    fn schema(columns: &[Column]) -> String {
+        let fields = columns
+            .iter()
+            .map(|column| {
+                let repetition = if column.nullable {
+                    "OPTIONAL"
+                } else {
+                    "REQUIRED"
+                };
+                let physical_type = match column.column_type {
+                    ColumnType::Integer => "INT64",
+                    ColumnType::Float => "DOUBLE",
+                    ColumnType::Boolean => "BOOLEAN",
+                    ColumnType::Text => "BYTE_ARRAY",
+                };
+                let annotation = match column.column_type {
+                    ColumnType::Text => " (UTF8)",
+                    _ => "",
+                };
+                format!(
+                    "{} {} {}{};",
+                    repetition, physical_type, column.name, annotation
+                )
+            })
+            .collect::<Vec<String>>();
+        format!("message qkd_results {{ {} }}", fields.join(" "))
+    }
+
+    /// Writer of Parquet files, one row group of `PARQUET_ROWS_PER_GROUP` records at a time.
+    pub(super) struct ParquetWriter {
+        writer: SerializedFileWriter<File>,
+        columns: &'static [Column],
+        rows: Vec<Value>,
+    }
+
+    impl ParquetWriter {
+        /// Creates the writer with the schema of the columns and the run metadata.
+        pub(super) fn create(
+            file: File,
+            metadata: &RunMetadata,
+            columns: &'static [Column],
+        ) -> Result<Self> {
+            let schema = Arc::new(parse_message_type(&schema(columns))?);
+            let properties = WriterProperties::builder()
+                .set_key_value_metadata(Some(vec![KeyValue::new(
+                    METADATA_KEY.to_string(),
+                    metadata.to_json().to_string(),
+                )]))
+                .build();
+            Ok(ParquetWriter {
+                writer: SerializedFileWriter::new(file, schema, Arc::new(properties))?,
+                columns,
+                rows: Vec::with_capacity(PARQUET_ROWS_PER_GROUP),
+            })
+        }
+
+        /// Buffers a row, and writes the row group once it is complete.
+        pub(super) fn write(&mut self, row: Value) -> Result<()> {
+            self.rows.push(row);
+            if self.rows.len() == PARQUET_ROWS_PER_GROUP {
+                self.write_row_group()?;
+            }
+            Ok(())
+        }
+
+        /// Writes the remaining rows and the footer of the file.
+        pub(super) fn finish(mut self) -> Result<()> {
+            if !self.rows.is_empty() {
+                self.write_row_group()?;
+            }
+            self.writer.close()?;
+            Ok(())
+        }
+
+        /// Writes the buffered rows as a row group and clears the buffer.
+        fn write_row_group(&mut self) -> Result<()> {
+            let mut row_group = self.writer.next_row_group()?;
+            for column in self.columns {
+                let values = self.rows.iter().map(|row| &row[column.name]);
+                match column.column_type {
+                    ColumnType::Integer => write_column::<Int64Type>(
+                        &mut row_group,
+                        values.map(Value::as_i64).collect(),
+                    )?,
+                    ColumnType::Float => write_column::<DoubleType>(
+                        &mut row_group,
+                        values.map(Value::as_f64).collect(),
+                    )?,
+                    ColumnType::Boolean => write_column::<BoolType>(
+                        &mut row_group,
+                        values.map(Value::as_bool).collect(),
+                    )?,
+                    ColumnType::Text => write_column::<ByteArrayType>(
+                        &mut row_group,
+                        values
+                            .map(|value| value.as_str().map(ByteArray::from))
+                            .collect(),
+                    )?,
+                }
+            }
+            row_group.close()?;
+            self.rows.clear();
+            Ok(())
+        }
+    }
+
+    /// Writes the next column of the row group, using `None` for null values.
+    fn write_column<T: DataType>(
+        row_group: &mut SerializedRowGroupWriter<'_, File>,
+        values: Vec<Option<T::T>>,
+    ) -> Result<()> {
+        let mut column = row_group
+            .next_column()?
+            .expect("Every column is defined in the schema");
+        let writer = column.typed::<T>();
+        let definition_levels = values
+            .iter()
+            .map(|v| v.is_some() as i16)
+            .collect::<Vec<_>>();
+        let is_optional = writer.get_descriptor().max_def_level() > 0;
+        let values = values.into_iter().flatten().collect::<Vec<_>>();
+        writer.write_batch(
+            &values,
+            is_optional.then_some(definition_levels.as_slice()),
+            None,
+        )?;
+        column.close()
+    }
+}
@@ -1,6 +1,103 @@
+use crate::backend::{StatisticalMeasurement, StatisticalPreparation};
 use crate::types::{ComplexMatrix, Qubit};
-use crate::utils::{rand_bool, rand_choose, rand_float, X};
+use crate::utils::{rand_bool, rand_float, rand_index, X};
 use bon::Builder;
+use rand::RngCore;
+use std::sync::Arc;
+
+/// Strategy used by a sender to prepare qubits and encode them in a basis.
+///
+/// Implementors must be thread-safe so that protocols can be shared across threads.
+/// All randomness must be drawn from the given `rng` to keep seeded runs reproducible.
+pub trait PreparationStrategy: Send + Sync {
+    /// Prepares a qubit in a random state.
+    /// Returns the prepared qubit and its classical bit value.
+    ///
+    /// By default, it prepares |0⟩ or |1⟩ with equal probability.
+    fn prepare(&self, rng: &mut dyn RngCore) -> (Qubit, bool) {
+        default_prepare(rng)
+    }
+
+    /// Changes the qubit's basis before sending.
+    /// Returns the index of the selected basis in `posible_basis`.
+    ///
+    /// By default, it selects a random basis from `posible_basis` and applies it to the qubit.
+    fn change_basis(
+        &self,
+        qubit: &mut Qubit,
+        posible_basis: &[ComplexMatrix],
+        rng: &mut dyn RngCore,
+    ) -> usize {
+        default_change_basis(qubit, posible_basis, rng)
+    }
+
+    /// Statistical model of this strategy, used by the statistical backend.
+    ///
+    /// Returns `None` by default, so custom strategies always run on the state-vector backend.
+    fn statistical_model(&self) -> Option<StatisticalPreparation> {
+        None
+    }
+}
+
+/// Strategy used by a receiver (Bob or Eve) to measure incoming qubits.
+///
+/// Implementors must be thread-safe so that protocols can be shared across threads.
+/// All randomness must be drawn from the given `rng` to keep seeded runs reproducible.
+pub trait MeasurementStrategy: Send + Sync {
+    /// Changes the qubit's basis before measurement.
+    /// Returns the index of the selected basis in `posible_basis`.
+    ///
+    /// By default, it selects a random basis from `posible_basis` and applies it to the qubit.
+    fn change_basis(
+        &self,
+        qubit: &mut Qubit,
+        posible_basis: &[ComplexMatrix],
+        rng: &mut dyn RngCore,
+    ) -> usize {
+        default_change_basis(qubit, posible_basis, rng)
+    }
+
+    /// Measures a qubit and obtains a classical bit.
+    /// The measurement collapses the qubit's state according to its current probabilities.
+    fn measure(&self, qubit: &mut Qubit, rng: &mut dyn RngCore) -> bool {
+        default_measure(qubit, rng)
+    }
+
+    /// Attempts to restore a qubit's state after measurement.
+    /// Used by Eve to minimize detection during eavesdropping.
+    ///
+    /// By default, it applies the inverse of the basis matrix used for measurement.
+    fn try_to_restore_qubit(&self, qubit: &mut Qubit, basis_matrix: &ComplexMatrix) {
+        default_try_to_restore_qubit(qubit, basis_matrix)
+    }
+
+    /// Statistical model of this strategy, used by the statistical backend.
+    ///
+    /// Returns `None` by default, so custom strategies always run on the state-vector backend.
+    fn statistical_model(&self) -> Option<StatisticalMeasurement> {
+        None
+    }
+}
+
+/// Default preparation strategy: random bit in a random basis.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomPreparation;
+
+impl PreparationStrategy for RandomPreparation {
+    fn statistical_model(&self) -> Option<StatisticalPreparation> {
+        Some(StatisticalPreparation::RandomBit)
+    }
+}
+
+/// Default measurement strategy: projective measurement in a random basis.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomMeasurement;
+
+impl MeasurementStrategy for RandomMeasurement {
+    fn statistical_model(&self) -> Option<StatisticalMeasurement> {
+        Some(StatisticalMeasurement::RandomBasis)
+    }
+}
 
 /// Quantum sender entity in a QKD protocol.
 ///
@@ -8,19 +105,25 @@ use bon::Builder;
 /// - Choosing from a set of possible quantum bases.
 /// - Preparing qubits in a random state.
 /// - Changing the qubit's basis before sending.
-#[doc(hidden)]
-#[derive(Builder)]
+#[derive(Builder, Clone)]
 pub struct Sender {
     /// Available quantum bases that Alice can use to prepare and transform qubits.
     pub(crate) posible_basis: Vec<ComplexMatrix>,
-    /// Function to randomly change the qubit's basis before sending.
-    /// By default, it selects a random basis from `posible_basis` and applies it to the qubit.
-    #[builder(default = Box::new(default_change_basis))]
-    pub(crate) change_basis: Box<dyn Fn(&mut Qubit, &Vec<ComplexMatrix>) -> usize>,
-    /// Function to prepare a qubit in a random state (|0⟩ or |1⟩ with equal probability).
-    /// Returns the prepared qubit and its classical bit value.
-    #[builder(default = Box::new(default_prepare))]
-    pub(crate) prepare: Box<dyn Fn() -> (Qubit, bool)>,
+    /// Strategy used to prepare qubits and change their basis before sending.
+    #[builder(default = Arc::new(RandomPreparation))]
+    pub(crate) strategy: Arc<dyn PreparationStrategy>,
+}
+
+impl Sender {
+    /// Prepares a qubit and encodes it in one of the available bases.
+    /// Returns the qubit, its classical bit value and the index of the basis used.
+    pub(crate) fn send(&self, rng: &mut dyn RngCore) -> (Qubit, bool, usize) {
+        let (mut qubit, value) = self.strategy.prepare(rng);
+        let basis = self
+            .strategy
+            .change_basis(&mut qubit, &self.posible_basis, rng);
+        (qubit, value, basis)
+    }
 }
 
 /// Quantum receiver entity in a QKD protocol.
@@ -30,24 +133,32 @@ pub struct Sender {
 /// - Changing the qubit's basis before measurement.
 /// - Measuring the qubit to obtain a classical bit.
 /// - Attempting to restore the qubit's state (for Eve).
-#[doc(hidden)]
-#[derive(Builder)]
+#[derive(Builder, Clone)]
 pub struct Receiver {
     /// Available quantum bases that the receiver can use to measure qubits.
     pub(crate) posible_basis: Vec<ComplexMatrix>,
-    /// Function to randomly change the qubit's basis before measurement.
-    /// By default, it selects a random basis from `posible_basis` and applies it to the qubit.
-    #[builder(default = Box::new(default_change_basis))]
-    pub(crate) change_basis: Box<dyn Fn(&mut Qubit, &Vec<ComplexMatrix>) -> usize>,
-    /// Function to measure a qubit and obtain a classical bit.
-    /// The measurement collapses the qubit's state according to its current probabilities.
-    #[builder(default = Box::new(default_measure))]
-    pub(crate) measure: Box<dyn Fn(&mut Qubit) -> bool>,
-    /// Function to attempt restoring a qubit's state after measurement.
-    /// Used by Eve to minimize detection during eavesdropping.
-    /// By default, it applies the inverse of the basis matrix used for measurement.
-    #[builder(default = Box::new(default_try_to_restore_qubit))]
-    pub(crate) try_to_restore_qubit: Box<dyn Fn(&mut Qubit, &ComplexMatrix)>,
+    /// Strategy used to change the basis, measure and restore qubits.
+    #[builder(default = Arc::new(RandomMeasurement))]
+    pub(crate) strategy: Arc<dyn MeasurementStrategy>,
+}
+
+impl Receiver {
+    /// Measures a qubit in one of the available bases.
+    /// Returns the measured bit value and the index of the basis used.
+    pub(crate) fn receive(&self, qubit: &mut Qubit, rng: &mut dyn RngCore) -> (bool, usize) {
+        let basis = self.strategy.change_basis(qubit, &self.posible_basis, rng);
+        let value = self.strategy.measure(qubit, rng);
+        (value, basis)
+    }
+
+    /// Measures a qubit and tries to restore it so that it can be forwarded.
+    /// Returns the measured bit value and the index of the basis used.
+    pub(crate) fn intercept(&self, qubit: &mut Qubit, rng: &mut dyn RngCore) -> (bool, usize) {
+        let (value, basis) = self.receive(qubit, rng);
+        self.strategy
+            .try_to_restore_qubit(qubit, &self.posible_basis[basis]);
+        (value, basis)
+    }
 }
 
 /// Default basis change function for quantum entities.
@@ -59,13 +170,18 @@ pub struct Receiver {
 ///
 /// * `qubit` - The qubit to transform.
 /// * `posible_basis` - Available quantum bases to choose from.
+/// * `rng` - Random number generator used to select the basis.
 ///
 /// # Returns
 ///
-/// The index of the selected basis in the `posible_basis` vector.
-fn default_change_basis(qubit: &mut Qubit, posible_basis: &Vec<ComplexMatrix>) -> usize {
-    let (basis_id, matrix) = rand_choose(posible_basis.iter().enumerate().collect());
-    qubit.apply_transformation(&matrix);
+/// The index of the selected basis in the `posible_basis` slice.
+pub fn default_change_basis(
+    qubit: &mut Qubit,
+    posible_basis: &[ComplexMatrix],
+    rng: &mut dyn RngCore,
+) -> usize {
+    let basis_id = rand_index(posible_basis.len(), rng);
+    qubit.apply_transformation(&posible_basis[basis_id]);
     basis_id
 }
 
@@ -74,12 +190,16 @@ fn default_change_basis(qubit: &mut Qubit, posible_basis: &Vec<ComplexMatrix>) -
 /// Prepares a qubit in the |0⟩ state and applies a bit-flip with 50% probability,
 /// resulting in either |0⟩ or |1⟩ with equal probability.
 ///
+/// # Arguments
+///
+/// * `rng` - Random number generator used to draw the bit value.
+///
 /// # Returns
 ///
 /// A tuple containing the prepared qubit and its classical bit value (false for |0⟩, true for |1⟩).
-fn default_prepare() -> (Qubit, bool) {
+pub fn default_prepare(rng: &mut dyn RngCore) -> (Qubit, bool) {
     let mut qubit = Qubit::new(); // |0⟩
-    let value = rand_bool();
+    let value = rand_bool(rng);
     // Perform a bit-flip with 1/2 probability
     if value {
         qubit.apply_transformation(&X); // |1⟩
@@ -97,14 +217,15 @@ fn default_prepare() -> (Qubit, bool) {
 /// # Arguments
 ///
 /// * `qubit` - The qubit to measure.
+/// * `rng` - Random number generator used to sample the outcome.
 ///
 /// # Returns
 ///
 /// The classical bit value obtained from the measurement (false for |0⟩, true for |1⟩).
-fn default_measure<'a>(qubit: &'a mut Qubit) -> bool {
+pub fn default_measure(qubit: &mut Qubit, rng: &mut dyn RngCore) -> bool {
     let one_probability = qubit.get_one_coef().norm().powf(2.0);
     qubit.reset(); // |0⟩
-    let measurement_result = rand_float() < one_probability;
+    let measurement_result = rand_float(rng) < one_probability;
     if measurement_result {
         qubit.apply_transformation(&X); // |1⟩
     }
@@ -120,6 +241,6 @@ fn default_measure<'a>(qubit: &'a mut Qubit) -> bool {
 ///
 /// * `qubit` - The qubit to restore.
 /// * `basis_matrix` - The basis matrix that was used for measurement.
-fn default_try_to_restore_qubit(qubit: &mut Qubit, basis_matrix: &ComplexMatrix) {
+pub fn default_try_to_restore_qubit(qubit: &mut Qubit, basis_matrix: &ComplexMatrix) {
     qubit.apply_transformation(&basis_matrix.invert().unwrap());
 }
@@ -0,0 +1,192 @@
+use crate::types::Qubit;
+use crate::utils::{rand_bool, rand_float, X};
+use rand::RngCore;
+
+/// Photon source used by Alice.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Source {
+    /// Mean photon number of the weak coherent pulses sent by Alice.
+    /// If `None`, the source emits exactly one photon per pulse.
+    ///
+    /// Only the probability of empty pulses is modeled: multi-photon pulses
+    /// are detected as single photons and are not exploited by Eve.
+    pub mean_photon_number: Option<f64>,
+}
+
+impl Source {
+    /// Returns the probability that a pulse contains at least one photon.
+    pub fn non_empty_probability(&self) -> f64 {
+        match self.mean_photon_number {
+            Some(mean_photon_number) => 1.0 - (-mean_photon_number).exp(),
+            None => 1.0,
+        }
+    }
+}
+
+/// Quantum channel between Alice and Bob.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Channel {
+    /// Probability (0.0 to 1.0) that a photon is lost in the channel,
+    /// in addition to the fiber attenuation.
+    pub loss: f64,
+    /// Probability (0.0 to 1.0) that the channel replaces the qubit with the
+    /// maximally mixed state, so that Bob measures a uniformly random bit.
+    pub depolarization: f64,
+    /// Length of the fiber in kilometers.
+    pub distance_km: f64,
+    /// Attenuation of the fiber in dB per kilometer.
+    pub attenuation_db_per_km: f64,
+    /// Angle in radians by which the channel rotates the polarization around
+    /// the Z axis of the Bloch sphere, i.e. the drift between the X/Y reference
+    /// frames of Alice and Bob. States of the Z basis are not affected.
+    pub rotation_rad: f64,
+}
+
+/// Attenuation of standard telecom fiber at 1550 nm, in dB per kilometer.
+pub const FIBER_ATTENUATION_DB_PER_KM: f64 = 0.2;
+
+impl Channel {
+    /// Creates a lossless noiseless fiber of the given length with the default attenuation.
+    pub fn fiber(distance_km: f64) -> Self {
+        Channel {
+            distance_km,
+            ..Channel::default()
+        }
+    }
+
+    /// Returns the probability that a photon reaches Bob.
+    pub fn transmittance(&self) -> f64 {
+        (1.0 - self.loss) * 10f64.powf(-self.attenuation_db_per_km * self.distance_km / 10.0)
+    }
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Channel {
+            loss: 0.0,
+            depolarization: 0.0,
+            distance_km: 0.0,
+            attenuation_db_per_km: FIBER_ATTENUATION_DB_PER_KM,
+            rotation_rad: 0.0,
+        }
+    }
+}
+
+/// Single-photon detector used by Bob.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Detector {
+    /// Probability (0.0 to 1.0) that an arriving photon is detected.
+    pub efficiency: f64,
+    /// Probability (0.0 to 1.0) of a click with a random value when no photon is detected.
+    pub dark_count_probability: f64,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Detector {
+            efficiency: 1.0,
+            dark_count_probability: 0.0,
+        }
+    }
+}
+
+/// Imperfections of the physical layer of a protocol.
+///
+/// The default value is an ideal single-photon source, a lossless noiseless
+/// channel and a perfect detector, which reproduces the behavior of protocols
+/// without any physical layer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct PhysicalLayer {
+    /// Photon source used by Alice.
+    pub source: Source,
+    /// Quantum channel between Alice and Bob.
+    pub channel: Channel,
+    /// Single-photon detector used by Bob.
+    pub detector: Detector,
+}
+
+impl PhysicalLayer {
+    /// Returns `true` if every component of the physical layer is ideal.
+    pub fn is_ideal(&self) -> bool {
+        *self == PhysicalLayer::default()
+    }
+
+    /// Returns the probability that a photon sent by Alice is detected by Bob.
+    pub fn signal_detection_probability(&self) -> f64 {
+        self.source.non_empty_probability()
+            * self.channel.transmittance()
+            * self.detector.efficiency
+    }
+
+    /// Applies the physical layer to the value measured by Bob.
+    ///
+    /// Ideal layers return the value without drawing any random number, so seeded
+    /// runs are not affected by the physical layer unless it is configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `bob_value` - Value that Bob would measure with ideal hardware.
+    /// * `rng` - Random number generator used to sample losses and noise.
+    ///
+    /// # Returns
+    ///
+    /// The value registered by Bob's detector, or `None` if there was no click.
+    pub(crate) fn detect(&self, bob_value: bool, rng: &mut dyn RngCore) -> Option<bool> {
+        if self.is_ideal() {
+            return Some(bob_value);
+        }
+
+        if rand_float(rng) < self.signal_detection_probability() {
+            if rand_float(rng) < self.channel.depolarization {
+                Some(rand_bool(rng))
+            } else {
+                Some(bob_value)
+            }
+        } else if rand_float(rng) < self.detector.dark_count_probability {
+            Some(rand_bool(rng))
+        } else {
+            None
+        }
+    }
+
+    /// Sends a qubit through half of the channel, to a relay in its middle.
+    ///
+    /// Only the transmittance and the depolarization of the channel apply, and
+    /// ideal layers return without drawing any random number.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit` - Qubit to send, depolarized in place.
+    /// * `rng` - Random number generator used to sample losses and noise.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the qubit is lost or not detected by the relay.
+    pub(crate) fn transmit_to_relay(&self, qubit: &mut Qubit, rng: &mut dyn RngCore) -> bool {
+        if self.is_ideal() {
+            return true;
+        }
+        let detection_probability = self.source.non_empty_probability()
+            * self.channel.transmittance().sqrt()
+            * self.detector.efficiency;
+        if rand_float(rng) >= detection_probability {
+            return false;
+        }
+        if rand_float(rng) < self.channel.depolarization {
+            // The maximally mixed state is an even mixture of |0⟩ and |1⟩.
+            *qubit = Qubit::new();
+            if rand_bool(rng) {
+                qubit.apply_transformation(&X);
+            }
+        }
+        true
+    }
+}
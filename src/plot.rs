@@ -0,0 +1,523 @@
+use crate::output::{group_by_configuration, ResultRecord};
+use crate::statistics::{Proportion, Summary};
+
+use clap::ValueEnum;
+use std::fmt::Write;
+
+/// Width of the generated charts, in pixels.
+const WIDTH: f64 = 800.0;
+/// Height of the generated charts, in pixels.
+const HEIGHT: f64 = 500.0;
+/// Margins around the plot area: top, right, bottom and left.
+const MARGINS: (f64, f64, f64, f64) = (50.0, 200.0, 60.0, 80.0);
+/// Colors of the series, in order (Tableau 10 palette).
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// Charts that can be rendered from simulation results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Chart {
+    /// Mean QBER of the accepted runs against the interception rate.
+    QberVsInterceptionRate,
+    /// Probability that Eve is detected (the protocol is aborted) against the number of qubits.
+    DetectionVsQubits,
+    /// Key length per sent qubit against the length of the fiber.
+    KeyRateVsDistance,
+    /// Mean fraction of the key known by Eve against the mean QBER.
+    EveKnowledgeVsQber,
+}
+
+/// Parameters that identify a configuration of the simulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Parameter {
+    Scenario,
+    Qubits,
+    InterceptionRate,
+    Distance,
+    ExcessNoise,
+    ModulationVariance,
+}
+
+impl Parameter {
+    /// Returns the value of the parameter in a record, formatted for a legend.
+    fn label(&self, record: &ResultRecord) -> Option<String> {
+        match self {
+            Parameter::Scenario => record.scenario.clone(),
+            Parameter::Qubits => Some(format!("n={}", record.number_of_qubits)),
+            Parameter::InterceptionRate => Some(format!("rate={}", record.interception_rate)),
+            Parameter::Distance => record.distance_km.map(|d| format!("{} km", d)),
+            Parameter::ExcessNoise => record
+                .parameters
+                .map(|parameters| format!("ξ={}", parameters.excess_noise)),
+            Parameter::ModulationVariance => record
+                .parameters
+                .map(|parameters| format!("V_A={}", parameters.modulation_variance)),
+        }
+    }
+}
+
+impl Chart {
+    /// Every chart, in the order they are rendered by default.
+    pub const ALL: [Chart; 4] = [
+        Chart::QberVsInterceptionRate,
+        Chart::DetectionVsQubits,
+        Chart::KeyRateVsDistance,
+        Chart::EveKnowledgeVsQber,
+    ];
+
+    /// Returns the name of the chart, as accepted on the command line.
+    pub fn name(&self) -> String {
+        self.to_possible_value()
+            .expect("Charts are not skipped")
+            .get_name()
+            .to_string()
+    }
+
+    /// Parameter that varies along the curves of the chart.
+    fn swept_parameter(&self) -> Parameter {
+        match self {
+            Chart::QberVsInterceptionRate | Chart::EveKnowledgeVsQber => {
+                Parameter::InterceptionRate
+            }
+            Chart::DetectionVsQubits => Parameter::Qubits,
+            Chart::KeyRateVsDistance => Parameter::Distance,
+        }
+    }
+
+    /// Builds the figure of the chart.
+    ///
+    /// Runs with the same configuration are treated as repetitions: each point
+    /// is their mean and the error bars are the confidence interval of the mean
+    /// (or the Wilson interval for probabilities).
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - Results of the simulator.
+    /// * `confidence` - Confidence level (0.0 to 1.0) of the error bars.
+    ///
+    /// # Returns
+    ///
+    /// The figure, or `None` if the results do not contain the data required by the chart.
+    pub fn figure(&self, records: &[ResultRecord], confidence: f64) -> Option<Figure> {
+        let records = records
+            .iter()
+            .filter(|record| *self != Chart::KeyRateVsDistance || record.distance_km.is_some())
+            .collect::<Vec<&ResultRecord>>();
+        let swept = self.swept_parameter();
+        let legend_parameters = [
+            Parameter::Scenario,
+            Parameter::Qubits,
+            Parameter::InterceptionRate,
+            Parameter::Distance,
+            Parameter::ExcessNoise,
+            Parameter::ModulationVariance,
+        ]
+        .into_iter()
+        .filter(|&parameter| parameter != swept && varies(&records, parameter))
+        .collect::<Vec<Parameter>>();
+
+        let mut series: Vec<Series> = Vec::new();
+        for configuration in group_by_configuration(records.iter().copied()) {
+            let Some(point) = self.point(&configuration, confidence) else {
+                continue;
+            };
+            let label = std::iter::once(configuration[0].protocol.clone())
+                .chain(
+                    legend_parameters
+                        .iter()
+                        .filter_map(|parameter| parameter.label(configuration[0])),
+                )
+                .collect::<Vec<String>>()
+                .join(", ");
+            match series.iter_mut().find(|series| series.label == label) {
+                Some(series) => series.points.push(point),
+                None => series.push(Series {
+                    label,
+                    points: vec![point],
+                }),
+            }
+        }
+        if series.is_empty() {
+            return None;
+        }
+        for series in &mut series {
+            series.points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        }
+
+        let (title, x_label, y_label) = match self {
+            Chart::QberVsInterceptionRate => {
+                ("QBER vs interception rate", "Interception rate", "QBER")
+            }
+            Chart::DetectionVsQubits => (
+                "Detection probability vs number of qubits",
+                "Number of qubits",
+                "Probability of detecting Eve",
+            ),
+            Chart::KeyRateVsDistance => (
+                "Key rate vs distance",
+                "Distance (km)",
+                "Key bits per sent qubit",
+            ),
+            Chart::EveKnowledgeVsQber => ("Eve's knowledge vs QBER", "QBER", "Eve's knowledge"),
+        };
+        let x_values = series
+            .iter()
+            .flat_map(|series| series.points.iter().map(|point| point.x));
+        let (x_min, x_max) = bounds(x_values);
+        Some(Figure {
+            title: title.to_string(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            log_x: *self == Chart::DetectionVsQubits && x_min > 0.0 && x_max / x_min >= 100.0,
+            series,
+        })
+    }
+
+    /// Computes the point of a configuration from the results of its repetitions.
+    fn point(&self, runs: &[&ResultRecord], confidence: f64) -> Option<Point> {
+        let accepted = runs
+            .iter()
+            .filter(|record| record.result.is_considered_secure)
+            .collect::<Vec<_>>();
+        let summary = |sample: Vec<f64>| {
+            let sample = sample
+                .into_iter()
+                .filter(|value| value.is_finite())
+                .collect::<Vec<f64>>();
+            Summary::new(&sample, confidence)
+        };
+        let qber = || {
+            summary(
+                accepted
+                    .iter()
+                    .filter_map(|record| record.result.quantum_bit_error_rate)
+                    .collect(),
+            )
+        };
+        let record = runs[0];
+
+        match self {
+            Chart::QberVsInterceptionRate => {
+                let qber = qber()?;
+                Some(Point::new(record.interception_rate, None, &qber))
+            }
+            Chart::DetectionVsQubits => {
+                let detection =
+                    Proportion::wilson(runs.len() - accepted.len(), runs.len(), confidence);
+                Some(Point {
+                    x: record.number_of_qubits as f64,
+                    y: detection.estimate,
+                    x_interval: None,
+                    y_interval: Some(detection.confidence_interval),
+                })
+            }
+            Chart::KeyRateVsDistance => {
+                let key_rate = summary(
+                    runs.iter()
+                        .map(|record| {
+                            record.result.key_length.unwrap_or(0) as f64
+                                / record.number_of_qubits.max(1) as f64
+                        })
+                        .collect(),
+                )?;
+                Some(Point::new(record.distance_km?, None, &key_rate))
+            }
+            Chart::EveKnowledgeVsQber => {
+                let qber = qber()?;
+                let knowledge = summary(
+                    accepted
+                        .iter()
+                        .map(|record| record.result.eve_knowledge)
+                        .collect(),
+                )?;
+                Some(Point::new(qber.mean, qber.confidence_interval, &knowledge))
+            }
+        }
+    }
+}
+
+/// Point of a series, with optional error bars.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    /// Horizontal coordinate.
+    pub x: f64,
+    /// Vertical coordinate.
+    pub y: f64,
+    /// Horizontal error bar.
+    pub x_interval: Option<(f64, f64)>,
+    /// Vertical error bar.
+    pub y_interval: Option<(f64, f64)>,
+}
+
+impl Point {
+    /// Creates a point whose vertical coordinate is the mean of a sample.
+    fn new(x: f64, x_interval: Option<(f64, f64)>, y: &Summary) -> Self {
+        Point {
+            x,
+            y: y.mean,
+            x_interval,
+            y_interval: y.confidence_interval,
+        }
+    }
+}
+
+/// Curve of a figure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    /// Label of the curve in the legend.
+    pub label: String,
+    /// Points of the curve, sorted by horizontal coordinate.
+    pub points: Vec<Point>,
+}
+
+/// Line chart with one curve per series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Figure {
+    /// Title displayed above the chart.
+    pub title: String,
+    /// Label of the horizontal axis.
+    pub x_label: String,
+    /// Label of the vertical axis.
+    pub y_label: String,
+    /// Whether the horizontal axis uses a logarithmic scale.
+    pub log_x: bool,
+    /// Curves of the chart.
+    pub series: Vec<Series>,
+}
+
+impl Figure {
+    /// Renders the figure as a standalone SVG document.
+    pub fn to_svg(&self) -> String {
+        let (top, right, bottom, left) = MARGINS;
+        let (plot_width, plot_height) = (WIDTH - left - right, HEIGHT - top - bottom);
+        let points = || self.series.iter().flat_map(|series| series.points.iter());
+
+        let x_axis = if self.log_x {
+            Axis::logarithmic(bounds(points().map(|point| point.x)))
+        } else {
+            Axis::linear(bounds(points().flat_map(|point| {
+                let (low, high) = point.x_interval.unwrap_or((point.x, point.x));
+                [point.x, low, high]
+            })))
+        };
+        let y_axis = Axis::linear(bounds(points().flat_map(|point| {
+            let (low, high) = point.y_interval.unwrap_or((point.y, point.y));
+            [point.y, low, high]
+        })));
+        let x = |value: f64| left + x_axis.position(value) * plot_width;
+        let y = |value: f64| top + (1.0 - y_axis.position(value)) * plot_height;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = WIDTH,
+            h = HEIGHT
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle" font-size="16">{}</text>"#,
+            left + plot_width / 2.0,
+            top / 2.0 + 5.0,
+            escape(&self.title)
+        );
+
+        for &(value, ref label) in &x_axis.ticks {
+            let position = x(value);
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{p:.2}" y1="{top}" x2="{p:.2}" y2="{bottom:.2}" stroke="#e0e0e0"/><text x="{p:.2}" y="{label_y:.2}" text-anchor="middle">{label}</text>"##,
+                p = position,
+                top = top,
+                bottom = top + plot_height,
+                label_y = top + plot_height + 18.0,
+                label = escape(label)
+            );
+        }
+        for &(value, ref label) in &y_axis.ticks {
+            let position = y(value);
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{left}" y1="{p:.2}" x2="{right:.2}" y2="{p:.2}" stroke="#e0e0e0"/><text x="{label_x:.2}" y="{text_y:.2}" text-anchor="end">{label}</text>"##,
+                p = position,
+                left = left,
+                right = left + plot_width,
+                label_x = left - 6.0,
+                text_y = position + 4.0,
+                label = escape(label)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+            left, top, plot_width, plot_height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+            left + plot_width / 2.0,
+            HEIGHT - 15.0,
+            escape(&self.x_label)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="20" y="{y}" text-anchor="middle" transform="rotate(-90 20 {y})">{}</text>"#,
+            escape(&self.y_label),
+            y = top + plot_height / 2.0
+        );
+
+        for (i, series) in self.series.iter().enumerate() {
+            let color = PALETTE[i % PALETTE.len()];
+            let path = series
+                .points
+                .iter()
+                .map(|point| format!("{:.2},{:.2}", x(point.x), y(point.y)))
+                .collect::<Vec<String>>()
+                .join(" ");
+            let _ = writeln!(svg, r#"<g stroke="{0}" fill="{0}">"#, color);
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke-width="2"/>"#,
+                path
+            );
+            for point in &series.points {
+                let (px, py) = (x(point.x), y(point.y));
+                if let Some((low, high)) = point.y_interval {
+                    let (low, high) = (y(low), y(high));
+                    let _ = writeln!(
+                        svg,
+                        r#"<path d="M{px:.2},{low:.2}V{high:.2}M{a:.2},{low:.2}H{b:.2}M{a:.2},{high:.2}H{b:.2}" fill="none"/>"#,
+                        a = px - 4.0,
+                        b = px + 4.0
+                    );
+                }
+                if let Some((low, high)) = point.x_interval {
+                    let (low, high) = (x(low), x(high));
+                    let _ = writeln!(
+                        svg,
+                        r#"<path d="M{low:.2},{py:.2}H{high:.2}M{low:.2},{a:.2}V{b:.2}M{high:.2},{a:.2}V{b:.2}" fill="none"/>"#,
+                        a = py - 4.0,
+                        b = py + 4.0
+                    );
+                }
+                let _ = writeln!(svg, r#"<circle cx="{:.2}" cy="{:.2}" r="3"/>"#, px, py);
+            }
+            let legend_y = top + 10.0 + 20.0 * i as f64;
+            let legend_x = left + plot_width + 15.0;
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{}" y1="{ly}" x2="{}" y2="{ly}" stroke-width="2"/><text x="{}" y="{}" stroke="none" fill="black">{}</text>"#,
+                legend_x,
+                legend_x + 20.0,
+                legend_x + 26.0,
+                legend_y + 4.0,
+                escape(&series.label),
+                ly = legend_y
+            );
+            let _ = writeln!(svg, "</g>");
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Axis of a figure, mapping values to the unit interval.
+struct Axis {
+    min: f64,
+    max: f64,
+    logarithmic: bool,
+    ticks: Vec<(f64, String)>,
+}
+
+impl Axis {
+    /// Creates a linear axis covering the range with ticks at multiples of 1, 2 or 5.
+    fn linear((min, max): (f64, f64)) -> Self {
+        let (min, max) = if max - min > f64::EPSILON * max.abs().max(1.0) {
+            (min, max)
+        } else {
+            (
+                min - 0.5 * min.abs().max(1.0),
+                max + 0.5 * max.abs().max(1.0),
+            )
+        };
+        let raw_step = (max - min) / 6.0;
+        let magnitude = 10f64.powf(raw_step.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|factor| factor * magnitude)
+            .find(|&step| step >= raw_step)
+            .unwrap_or(10.0 * magnitude);
+        let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+        let (first, last) = ((min / step).floor() as i64, (max / step).ceil() as i64);
+        let ticks = (first..=last)
+            .map(|i| {
+                let value = i as f64 * step;
+                (value, format!("{:.*}", decimals, value))
+            })
+            .collect();
+        Axis {
+            min: first as f64 * step,
+            max: last as f64 * step,
+            logarithmic: false,
+            ticks,
+        }
+    }
+
+    /// Creates a logarithmic axis covering the range with a tick at every power of ten.
+    fn logarithmic((min, max): (f64, f64)) -> Self {
+        let (first, last) = (min.log10().floor() as i32, max.log10().ceil() as i32);
+        let ticks = (first..=last)
+            .map(|exponent| {
+                let label = if (0..=6).contains(&exponent) {
+                    format!("{}", 10u64.pow(exponent as u32))
+                } else {
+                    format!("1e{}", exponent)
+                };
+                (10f64.powi(exponent), label)
+            })
+            .collect();
+        Axis {
+            min: 10f64.powi(first),
+            max: 10f64.powi(last.max(first + 1)),
+            logarithmic: true,
+            ticks,
+        }
+    }
+
+    /// Returns the position of a value along the axis, from 0.0 to 1.0.
+    fn position(&self, value: f64) -> f64 {
+        if self.logarithmic {
+            (value.log10() - self.min.log10()) / (self.max.log10() - self.min.log10())
+        } else {
+            (value - self.min) / (self.max - self.min)
+        }
+    }
+}
+
+/// Returns the minimum and maximum of the finite values.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+/// Returns `true` if the records take more than one value of the parameter.
+fn varies(records: &[&ResultRecord], parameter: Parameter) -> bool {
+    records
+        .iter()
+        .any(|record| parameter.label(record) != parameter.label(records[0]))
+}
+
+/// Escapes the characters of a text that are special in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
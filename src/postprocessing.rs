@@ -0,0 +1,334 @@
+use crate::protocol::PublicDiscussionResult;
+use crate::utils::{derive_seed, seeded_rng};
+use bon::Builder;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+/// Random stream reserved for the permutations of the reconciliation.
+const RECONCILIATION_STREAM: u64 = u64::MAX - 1;
+
+/// Random stream reserved for the hash function of the privacy amplification.
+const AMPLIFICATION_STREAM: u64 = u64::MAX - 2;
+
+/// Keys of Alice and Bob after the public discussion, with the QBER estimated
+/// from the check bits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SiftedKey {
+    /// Key bits of Alice.
+    pub alice: Vec<bool>,
+    /// Key bits of Bob, which may differ from Alice's because of noise or Eve.
+    pub bob: Vec<bool>,
+    /// Number of check bits publicly compared.
+    pub check_bits: usize,
+    /// Fraction of mismatched check bits.
+    pub estimated_qber: f64,
+}
+
+impl SiftedKey {
+    /// Extracts the sifted key of a public discussion.
+    pub fn new(discussion_result: &PublicDiscussionResult) -> Self {
+        let (alice, bob) = discussion_result
+            .indexes_to_key
+            .iter()
+            .map(|&i| {
+                let result = &discussion_result.results[i];
+                (result.alice_value, result.bob_value)
+            })
+            .unzip();
+        let check_bits = discussion_result.alice_public_values.len();
+        let mismatched = discussion_result
+            .alice_public_values
+            .iter()
+            .zip(&discussion_result.bob_public_values)
+            .filter(|(a, b)| a != b)
+            .count();
+
+        SiftedKey {
+            alice,
+            bob,
+            check_bits,
+            estimated_qber: if check_bits == 0 {
+                0.0
+            } else {
+                mismatched as f64 / check_bits as f64
+            },
+        }
+    }
+}
+
+/// Classical post-processing of a sifted key: error reconciliation followed
+/// by privacy amplification.
+#[derive(Builder, Clone, Copy, Debug)]
+pub struct PostProcessing {
+    /// Number of passes of the reconciliation.
+    #[builder(default = 4)]
+    passes: usize,
+    /// Probability (0.0 to 1.0) that privacy amplification fails to remove
+    /// Eve's information, which reduces the final key by `2 log2(1 / epsilon)` bits.
+    #[builder(default = 1e-10)]
+    epsilon: f64,
+}
+
+/// Outcome of the classical post-processing of a sifted key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostProcessingResult {
+    /// Number of bits of the sifted key.
+    pub sifted_length: usize,
+    /// QBER estimated from the check bits.
+    pub estimated_qber: f64,
+    /// Number of mismatched bits between the sifted keys of Alice and Bob.
+    pub mismatched_bits: usize,
+    /// Number of parity bits disclosed during the reconciliation.
+    pub leaked_bits: usize,
+    /// Number of mismatched bits left after the reconciliation.
+    pub residual_errors: usize,
+    /// Final key of Alice.
+    pub key: Vec<bool>,
+    /// Whether the final keys of Alice and Bob are identical.
+    pub keys_match: bool,
+}
+
+impl PostProcessing {
+    /// Reconciles and amplifies a sifted key.
+    ///
+    /// # Arguments
+    ///
+    /// * `sifted_key` - Keys of Alice and Bob after the public discussion.
+    /// * `seed` - Seed of the public randomness (permutations and hash function).
+    ///
+    /// # Returns
+    ///
+    /// The `PostProcessingResult`, whose key is empty if no secret bits remain.
+    pub fn run(&self, sifted_key: &SiftedKey, seed: u64) -> PostProcessingResult {
+        let mut rng = seeded_rng(derive_seed(seed, RECONCILIATION_STREAM), 0);
+        let reconciliation = reconcile(
+            &sifted_key.alice,
+            &sifted_key.bob,
+            sifted_key.estimated_qber,
+            self.passes,
+            &mut rng,
+        );
+
+        let output_length = secure_key_length(
+            sifted_key.alice.len(),
+            sifted_key.estimated_qber,
+            reconciliation.leaked_bits,
+            self.epsilon,
+        );
+        let amplification_seed = derive_seed(seed, AMPLIFICATION_STREAM);
+        let key = amplify(&sifted_key.alice, output_length, amplification_seed);
+        let bob_key = amplify(&reconciliation.key, output_length, amplification_seed);
+
+        let count_mismatches = |bob: &[bool]| {
+            sifted_key
+                .alice
+                .iter()
+                .zip(bob)
+                .filter(|(a, b)| a != b)
+                .count()
+        };
+        PostProcessingResult {
+            sifted_length: sifted_key.alice.len(),
+            estimated_qber: sifted_key.estimated_qber,
+            mismatched_bits: count_mismatches(&sifted_key.bob),
+            leaked_bits: reconciliation.leaked_bits,
+            residual_errors: count_mismatches(&reconciliation.key),
+            keys_match: key == bob_key,
+            key,
+        }
+    }
+}
+
+/// Bob's key after error reconciliation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reconciliation {
+    /// Key of Bob with the located errors corrected.
+    pub key: Vec<bool>,
+    /// Number of parity bits disclosed.
+    pub leaked_bits: usize,
+}
+
+/// Corrects Bob's key with the Cascade protocol.
+///
+/// In every pass, the key is shuffled and split into blocks, whose parities are
+/// compared. Blocks with different parities contain an odd number of errors,
+/// and one of them is located by binary search and corrected. Correcting a bit
+/// changes the parity of its blocks in the other passes, which are searched
+/// again to find the errors they were hiding. The first block size is
+/// `0.73 / qber` and doubles in every pass.
+///
+/// Every parity compared is counted as leaked, including the parities of
+/// sub-blocks that Cascade could reuse, so the leakage is slightly overestimated.
+///
+/// # Arguments
+///
+/// * `alice` - Key of Alice.
+/// * `bob` - Key of Bob, of the same length.
+/// * `qber` - Estimated fraction of mismatched bits.
+/// * `passes` - Number of passes.
+/// * `rng` - Random number generator used for the public permutations.
+pub fn reconcile(
+    alice: &[bool],
+    bob: &[bool],
+    qber: f64,
+    passes: usize,
+    rng: &mut dyn RngCore,
+) -> Reconciliation {
+    let length = alice.len();
+    let mut key = bob.to_vec();
+    let mut leaked_bits = 0;
+    if length == 0 {
+        return Reconciliation { key, leaked_bits };
+    }
+
+    let mut block_size = if qber > 0.0 {
+        ((0.73 / qber).ceil() as usize).clamp(2, length)
+    } else {
+        length
+    };
+    let parity = |key: &[bool], indexes: &[usize]| indexes.iter().filter(|&&i| key[i]).count() % 2;
+    // Order of the bits and block size of every pass, and position of every bit in each order.
+    let mut orders: Vec<(Vec<usize>, usize)> = Vec::with_capacity(passes);
+    let mut positions: Vec<Vec<usize>> = Vec::with_capacity(passes);
+    for pass in 0..passes {
+        let mut order = (0..length).collect::<Vec<usize>>();
+        if pass > 0 {
+            order.shuffle(rng);
+        }
+        let mut position = vec![0; length];
+        for (i, &bit) in order.iter().enumerate() {
+            position[bit] = i;
+        }
+        orders.push((order, block_size));
+        positions.push(position);
+
+        for block in 0..length.div_ceil(block_size) {
+            leaked_bits += 1;
+            // Blocks of earlier passes that may contain an odd number of errors.
+            let mut pending = vec![(pass, block)];
+            while let Some((pass, block)) = pending.pop() {
+                let (order, size) = &orders[pass];
+                let mut candidates = &order[block * size..((block + 1) * size).min(length)];
+                if parity(alice, candidates) == parity(&key, candidates) {
+                    continue;
+                }
+                while candidates.len() > 1 {
+                    let (first, second) = candidates.split_at(candidates.len() / 2);
+                    leaked_bits += 1;
+                    candidates = if parity(alice, first) != parity(&key, first) {
+                        first
+                    } else {
+                        second
+                    };
+                }
+                let bit = candidates[0];
+                key[bit] = !key[bit];
+                for (other, (_, size)) in orders.iter().enumerate() {
+                    if other != pass {
+                        pending.push((other, positions[other][bit] / size));
+                    }
+                }
+            }
+        }
+        block_size = (2 * block_size).min(length);
+    }
+
+    Reconciliation { key, leaked_bits }
+}
+
+/// Returns the number of secret bits that can be extracted from a reconciled key.
+///
+/// Uses the asymptotic bound of BB84, where Eve's information is given by the
+/// binary entropy of the QBER, minus the bits disclosed during the reconciliation
+/// and the security margin of the privacy amplification.
+///
+/// # Arguments
+///
+/// * `key_length` - Number of bits of the sifted key.
+/// * `qber` - Estimated fraction of mismatched bits.
+/// * `leaked_bits` - Number of bits disclosed during the reconciliation.
+/// * `epsilon` - Probability that privacy amplification fails.
+pub fn secure_key_length(key_length: usize, qber: f64, leaked_bits: usize, epsilon: f64) -> usize {
+    let length = key_length as f64 * (1.0 - binary_entropy(qber))
+        - leaked_bits as f64
+        - 2.0 * (1.0 / epsilon).log2();
+    length.max(0.0).floor() as usize
+}
+
+/// Compresses a key with a random Toeplitz matrix, a universal hash function.
+///
+/// # Arguments
+///
+/// * `key` - Key to compress.
+/// * `output_length` - Number of bits of the result.
+/// * `seed` - Seed of the matrix, which must be shared by Alice and Bob.
+///
+/// # Returns
+///
+/// The product of the matrix and the key.
+pub fn amplify(key: &[bool], output_length: usize, seed: u64) -> Vec<bool> {
+    let mut rng = seeded_rng(seed, 0);
+    // Row `i` of the matrix is given by bits `i..i + key.len()` of the diagonal values.
+    let diagonal_words = (key.len() + output_length).div_ceil(64) + 1;
+    let diagonals = (0..diagonal_words)
+        .map(|_| rng.random::<u64>())
+        .collect::<Vec<u64>>();
+    let key_words = pack(key);
+
+    (0..output_length)
+        .map(|row| {
+            let ones = key_words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| (bits_at(&diagonals, row + 64 * i) & word).count_ones())
+                .sum::<u32>();
+            ones % 2 == 1
+        })
+        .collect()
+}
+
+/// Packs a key into whole bytes, most significant bit first, dropping the trailing bits.
+///
+/// # Arguments
+///
+/// * `key` - Bits of the key.
+///
+/// # Returns
+///
+/// The `key.len() / 8` bytes of the key.
+pub fn key_bytes(key: &[bool]) -> Vec<u8> {
+    key.chunks_exact(8)
+        .map(|bits| bits.iter().fold(0, |byte, &bit| byte << 1 | u8::from(bit)))
+        .collect()
+}
+
+/// Packs bits into words, least significant bit first.
+fn pack(bits: &[bool]) -> Vec<u64> {
+    bits.chunks(64)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, &bit)| bit)
+                .fold(0, |word, (i, _)| word | 1 << i)
+        })
+        .collect()
+}
+
+/// Returns the 64 bits of `words` starting at bit `offset`.
+fn bits_at(words: &[u64], offset: usize) -> u64 {
+    let (index, shift) = (offset / 64, offset % 64);
+    let low = words[index] >> shift;
+    match words.get(index + 1) {
+        Some(high) if shift > 0 => low | high << (64 - shift),
+        _ => low,
+    }
+}
+
+/// Binary entropy function, in bits.
+pub(crate) fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+}
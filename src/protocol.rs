@@ -1,13 +1,22 @@
+use crate::backend::{Backend, PackedRounds, ProbabilityTables, ROUNDS_PER_WORD};
+use crate::mdi::{BellState, Relay};
 use crate::participants::{Receiver, Sender};
-use crate::utils::{rand_float, shuffle_and_split, H, I};
+use crate::physical::PhysicalLayer;
+use crate::types::ComplexMatrix;
+use crate::utils::{derive_seed, rand_float, random_seed, rz, seeded_rng, shuffle_and_split, H, I};
 use bon::Builder;
-use std::time::{Duration, Instant};
+use rand::RngCore;
+use rayon::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use web_time::Instant;
 
 /// Represents the result of a single quantum execution round in a QKD protocol.
 ///
 /// This struct captures the values and bases chosen by Alice and Bob,
 /// as well as the potential eavesdropping attempts by Eve during the round.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QExecutionResult {
     /// Bit value chosen by Alice.
     pub alice_value: bool,
@@ -21,10 +30,18 @@ pub struct QExecutionResult {
     pub eve_value: Option<bool>,
     /// Measurement basis used by Eve, if any.
     pub eve_basis: Option<usize>,
+    /// Bell state announced by the relay of measurement-device-independent
+    /// protocols, or `None` in protocols without a relay.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub announcement: Option<BellState>,
 }
 
 impl QExecutionResult {
-    /// Creates a new `QExecutionResult` with the specified values and bases.
+    /// Creates a new `QExecutionResult` with the specified values and bases,
+    /// without any announcement.
     ///
     /// # Arguments
     ///
@@ -49,6 +66,7 @@ impl QExecutionResult {
             bob_basis,
             eve_value,
             eve_basis,
+            announcement: None,
         }
     }
 }
@@ -57,9 +75,20 @@ impl QExecutionResult {
 ///
 /// This struct encapsulates the outcome of the entire QKD process, including
 /// timing, security status, key metrics, and estimated eavesdropping knowledge.
-#[derive(Debug)]
+///
+/// With the `serde` feature, `elapsed_time` is serialized as an integer
+/// number of nanoseconds under the `elapsed_time_ns` key.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QKDResult {
     /// Total duration of the quantum communication process, from initialization to completion.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "elapsed_time_ns",
+            with = "crate::utils::serde_duration_nanos"
+        )
+    )]
     pub elapsed_time: Duration,
 
     /// Indicates whether the communication is considered secure.
@@ -83,41 +112,256 @@ pub struct QKDResult {
 /// This struct contains the values publicly shared by Alice and Bob,
 /// the indexes of the bits used to generate the final key,
 /// and the detailed results of each quantum execution round.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PublicDiscussionResult {
     /// Publicly announced bit values by Alice.
     pub alice_public_values: Vec<bool>,
     /// Publicly announced bit values by Bob.
     pub bob_public_values: Vec<bool>,
+    /// Indexes of the bits publicly compared to detect eavesdropping.
+    pub indexes_to_check: Vec<usize>,
     /// Indexes of the bits selected for the final key generation.
     pub indexes_to_key: Vec<usize>,
     /// Detailed results of each quantum execution round.
     pub results: Vec<QExecutionResult>,
 }
 
+/// Number of rounds simulated sequentially with the same random stream.
+///
+/// Fixed so that seeded runs are reproducible regardless of the number of threads.
+pub const ROUNDS_PER_CHUNK: usize = 4096;
+
+/// Random stream reserved for the public discussion phase.
+pub(crate) const SIFTING_STREAM: u64 = u64::MAX;
+
+/// Strategy used during the public discussion phase to sift the raw results.
+///
+/// Decides which rounds are publicly compared to detect eavesdropping and which
+/// rounds are kept to build the final key.
+/// Implementors must be thread-safe so that protocols can be shared across threads.
+/// All randomness must be drawn from the given `rng` to keep seeded runs reproducible.
+pub trait SiftingStrategy: Send + Sync {
+    /// Performs the public basis discussion over the results of a block of rounds.
+    ///
+    /// The results are taken by value so that strategies can update them in place
+    /// and return them without copying.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - Quantum execution results of every round in the block.
+    /// * `rng` - Random number generator used to choose the check bits.
+    ///
+    /// # Returns
+    ///
+    /// A `PublicDiscussionResult` containing the public values, key indexes, and results.
+    fn sift(&self, results: Vec<QExecutionResult>, rng: &mut dyn RngCore)
+        -> PublicDiscussionResult;
+
+    /// Decides whether the public discussion of a block passes the security check.
+    ///
+    /// By default, the fraction of mismatched public values must not exceed the
+    /// QBER threshold (see `default_security_check`).
+    ///
+    /// # Arguments
+    ///
+    /// * `discussion_result` - Public discussion of the block returned by `sift`.
+    /// * `qber_threshold` - Maximum fraction (0.0 to 1.0) of mismatched check bits
+    ///   configured in the protocol.
+    ///
+    /// # Returns
+    ///
+    /// `true` if no eavesdropping was detected, so the key can be kept.
+    fn is_secure(&self, discussion_result: &PublicDiscussionResult, qber_threshold: f64) -> bool {
+        default_security_check(discussion_result, qber_threshold)
+    }
+
+    /// Returns `false` if `is_secure` ignores the QBER threshold of the protocol.
+    fn uses_qber_threshold(&self) -> bool {
+        true
+    }
+
+    /// Describes how the kept rounds are split into check and key bits, for
+    /// the walkthrough of `Explanation`.
+    fn describe_sifting(&self) -> String {
+        "Half of the kept rounds are chosen at random as check bits, and the rest\n\
+         are kept for the key. Without Eve or noise, Alice and Bob always agree on them."
+            .to_string()
+    }
+
+    /// Describes the security check of a block, for the walkthrough of `Explanation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `discussion_result` - Public discussion of the block returned by `sift`.
+    /// * `qber_threshold` - QBER threshold configured in the protocol.
+    fn describe_security_check(
+        &self,
+        discussion_result: &PublicDiscussionResult,
+        qber_threshold: f64,
+    ) -> String {
+        let check_bits = discussion_result.alice_public_values.len();
+        format!(
+            "The protocol is aborted if more than {} of the {} check bits are\n\
+             mismatched (QBER threshold {}).",
+            (qber_threshold * check_bits as f64).floor(),
+            check_bits,
+            qber_threshold
+        )
+    }
+}
+
+/// Default sifting strategy: keeps the rounds where Alice and Bob used the same basis.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BasisMatchSifting;
+
+impl SiftingStrategy for BasisMatchSifting {
+    fn sift(
+        &self,
+        results: Vec<QExecutionResult>,
+        rng: &mut dyn RngCore,
+    ) -> PublicDiscussionResult {
+        default_public_basis_discussion(results, rng)
+    }
+}
+
 /// Represents a Quantum Key Distribution (QKD) protocol instance.
 ///
 /// This struct encapsulates the participants (Alice, Bob, and Eve),
 /// the public basis discussion logic, and the methods to execute the protocol.
-#[derive(Builder)]
+#[derive(Builder, Clone)]
 pub struct QKD {
     /// Quantum sender (Alice) in the QKD protocol.
     alice: Sender,
     /// Quantum receiver (Bob) in the QKD protocol.
+    /// Unused if the protocol has a relay, since Bob prepares qubits instead.
     bob: Receiver,
     /// Potential eavesdropper (Eve) in the QKD protocol.
     /// By default, Eve can measure in the I and H bases.
     #[builder(default = Receiver::builder().posible_basis(vec![I, H]).build())]
     eve: Receiver,
-    /// Function to perform the public basis discussion phase.
+    /// Strategy to perform the public basis discussion phase.
     /// Determines which bits are used for key generation and which for security checking.
-    #[builder(default = Box::new(default_public_basis_discussion))]
-    public_basis_discussion: Box<dyn Fn(&Vec<QExecutionResult>) -> PublicDiscussionResult>,
+    #[builder(default = Arc::new(BasisMatchSifting))]
+    sifting: Arc<dyn SiftingStrategy>,
+    /// Backend used to simulate the quantum phase.
+    /// By default, the state vector of every qubit is evolved.
+    #[builder(default)]
+    backend: Backend,
+    /// Source, channel and detector between Alice and Bob.
+    /// By default, every component is ideal.
+    #[builder(default)]
+    physical_layer: PhysicalLayer,
+    /// Untrusted relay of measurement-device-independent protocols, to which
+    /// Alice and Bob both send qubits. Eve is the relay in the intercepted rounds.
+    /// By default, there is no relay and Bob measures Alice's qubits.
+    relay: Option<Relay>,
+    /// Maximum fraction (0.0 to 1.0) of mismatched check bits tolerated before aborting.
+    /// By default, any mismatch aborts the protocol.
+    #[builder(default)]
+    qber_threshold: f64,
 }
 
 impl QKD {
+    /// Returns the same protocol using the given simulation backend.
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Returns the same protocol using the given eavesdropper.
+    pub fn with_eve(mut self, eve: Receiver) -> Self {
+        self.eve = eve;
+        self
+    }
+
+    /// Returns the same protocol using the given physical layer.
+    pub fn with_physical_layer(mut self, physical_layer: PhysicalLayer) -> Self {
+        self.physical_layer = physical_layer;
+        self
+    }
+
+    /// Returns the same protocol tolerating the given fraction of mismatched check bits.
+    ///
+    /// Sifting strategies with their own security check, such as `RfiSifting`,
+    /// ignore the threshold (see `uses_qber_threshold`).
+    pub fn with_qber_threshold(mut self, qber_threshold: f64) -> Self {
+        self.qber_threshold = qber_threshold;
+        self
+    }
+
+    /// Returns the number of bases available to Alice and Bob.
+    pub fn number_of_bases(&self) -> (usize, usize) {
+        let (alice_bases, bob_bases, _) = self.bases();
+        (alice_bases.len(), bob_bases.len())
+    }
+
+    /// Returns `true` if Alice and Bob send their qubits to a relay.
+    pub fn has_relay(&self) -> bool {
+        self.relay.is_some()
+    }
+
+    /// Returns the physical layer of the protocol.
+    pub fn physical_layer(&self) -> PhysicalLayer {
+        self.physical_layer
+    }
+
+    /// Returns the maximum fraction of mismatched check bits tolerated before aborting.
+    pub fn qber_threshold(&self) -> f64 {
+        self.qber_threshold
+    }
+
+    /// Returns `false` if the security check of the sifting strategy ignores the
+    /// QBER threshold.
+    pub fn uses_qber_threshold(&self) -> bool {
+        self.sifting.uses_qber_threshold()
+    }
+
+    /// Describes the sifting strategy, as in `SiftingStrategy::describe_sifting`.
+    pub(crate) fn describe_sifting(&self) -> String {
+        self.sifting.describe_sifting()
+    }
+
+    /// Describes the security check of a block, as in
+    /// `SiftingStrategy::describe_security_check`.
+    pub(crate) fn describe_security_check(
+        &self,
+        discussion_result: &PublicDiscussionResult,
+    ) -> String {
+        self.sifting
+            .describe_security_check(discussion_result, self.qber_threshold)
+    }
+
+    /// Returns the bases available to Alice, Bob and Eve.
+    ///
+    /// With a relay, Bob's bases are the ones he prepares qubits in.
+    pub(crate) fn bases(&self) -> (&[ComplexMatrix], &[ComplexMatrix], &[ComplexMatrix]) {
+        let bob_bases = match &self.relay {
+            Some(relay) => &relay.bob.posible_basis,
+            None => &self.bob.posible_basis,
+        };
+        (
+            &self.alice.posible_basis,
+            bob_bases,
+            &self.eve.posible_basis,
+        )
+    }
+
+    /// Returns `true` if every participant can be simulated by the statistical backend.
+    ///
+    /// Channels that rotate the polarization and protocols with a relay are
+    /// only simulated on the state-vector backend.
+    pub fn supports_statistical_backend(&self) -> bool {
+        self.physical_layer.channel.rotation_rad == 0.0
+            && self.relay.is_none()
+            && ProbabilityTables::new(&self.alice, &self.bob, &self.eve).is_some()
+    }
+
     /// Executes the QKD protocol for a given number of qubits and interception rate.
     ///
+    /// The simulation is seeded from the operating system entropy source.
+    /// Use `run_with_seed` for reproducible results.
+    ///
     /// # Arguments
     ///
     /// * `number_of_qubits` - Number of qubits to use in the protocol.
@@ -128,68 +372,259 @@ impl QKD {
     /// A `QKDResult` containing the protocol outcome, including timing,
     /// security status, key metrics, and estimated eavesdropping knowledge.
     pub fn run(&self, number_of_qubits: usize, interception_rate: f64) -> QKDResult {
-        let initial_time = Instant::now();
-        let results = (0..number_of_qubits)
-            .into_iter()
-            .map(|_| self.quantum_communication(interception_rate))
-            .collect::<Vec<QExecutionResult>>();
+        self.run_with_seed(number_of_qubits, interception_rate, random_seed())
+    }
 
-        let discussion_result = (self.public_basis_discussion)(&results);
-        let results = discussion_result.results;
+    /// Executes the QKD protocol for a given number of qubits and interception rate,
+    /// drawing all randomness from the given seed.
+    ///
+    /// Rounds are simulated in parallel in blocks of `ROUNDS_PER_CHUNK` qubits, each
+    /// with its own random stream derived from `seed`. Therefore, the outcome only
+    /// depends on the seed and not on the number of threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// A `QKDResult` containing the protocol outcome, including timing,
+    /// security status, key metrics, and estimated eavesdropping knowledge.
+    pub fn run_with_seed(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> QKDResult {
+        self.run_streaming_with_seed(number_of_qubits, interception_rate, seed, number_of_qubits)
+    }
 
-        let is_considered_secure = self.check_public_values(
-            discussion_result.alice_public_values,
-            discussion_result.bob_public_values,
-        );
+    /// Executes the QKD protocol processing the qubits in fixed-size blocks.
+    ///
+    /// Each block is simulated, sifted and checked independently, and only the key
+    /// statistics are accumulated, so memory usage is bounded by the block size.
+    /// The protocol is aborted as soon as a block fails the security check.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `block_size` - Number of qubits per block.
+    ///
+    /// # Returns
+    ///
+    /// A `QKDResult` containing the protocol outcome, including timing,
+    /// security status, key metrics, and estimated eavesdropping knowledge.
+    pub fn run_streaming(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        block_size: usize,
+    ) -> QKDResult {
+        self.run_streaming_with_seed(
+            number_of_qubits,
+            interception_rate,
+            random_seed(),
+            block_size,
+        )
+    }
 
-        let mut eve_knowledge = 0.0;
-        let (mut quantum_bit_error_rate, mut key_length) = (None, None);
-        if is_considered_secure {
-            let ((alice_secret_values, bob_secret_values), eve_secret_values): (
-                (Vec<bool>, Vec<bool>),
-                Vec<Option<bool>>,
-            ) = discussion_result
-                .indexes_to_key
-                .into_iter()
-                .map(|i| {
-                    (
-                        (results[i].alice_value, results[i].bob_value),
-                        results[i].eve_value,
-                    )
-                })
-                .unzip();
-
-            key_length = Some(alice_secret_values.len());
-
-            let (mismatched_bits, absolute_eve_knowledge) = alice_secret_values
-                .into_iter()
-                .zip(bob_secret_values)
-                .zip(eve_secret_values)
-                .fold((0.0, 0.0), |mut acc, ((a, b), e)| {
-                    if a != b {
-                        acc.0 += 1.0;
-                    } else {
-                        acc.1 += if e.map_or(false, |e| a == e) {
-                            1.0
-                        } else {
-                            0.0
-                        }
-                    }
-                    acc
-                });
-
-            quantum_bit_error_rate = Some(mismatched_bits / key_length.unwrap() as f64);
-            eve_knowledge = absolute_eve_knowledge / key_length.unwrap() as f64;
-        }
-        let elapsed_time = initial_time.elapsed();
+    /// Executes the QKD protocol in fixed-size blocks, drawing all randomness from
+    /// the given seed.
+    ///
+    /// The block size is rounded up to a multiple of `ROUNDS_PER_CHUNK`, so the
+    /// simulated rounds are the same as in `run_with_seed` for the same seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    /// * `block_size` - Number of qubits per block.
+    ///
+    /// # Returns
+    ///
+    /// A `QKDResult` containing the protocol outcome, including timing,
+    /// security status, key metrics, and estimated eavesdropping knowledge.
+    pub fn run_streaming_with_seed(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+        block_size: usize,
+    ) -> QKDResult {
+        let initial_time = Instant::now();
+        let rounds_per_block = block_size.max(1).div_ceil(ROUNDS_PER_CHUNK) * ROUNDS_PER_CHUNK;
 
-        QKDResult {
-            elapsed_time,
-            is_considered_secure,
-            key_length,
-            quantum_bit_error_rate,
-            eve_knowledge,
+        let mut statistics = KeyStatistics::default();
+        let mut is_considered_secure = true;
+        for (block, start) in (0..number_of_qubits).step_by(rounds_per_block).enumerate() {
+            let end = number_of_qubits.min(start + rounds_per_block);
+            let results = self.simulate_rounds(start, end, interception_rate, seed);
+
+            let discussion_result = self.sift_block(results, seed, block);
+            is_considered_secure = self.check_public_values(&discussion_result);
+            if !is_considered_secure {
+                break;
+            }
+            statistics.add_key(&discussion_result);
         }
+
+        statistics.into_result(is_considered_secure, initial_time.elapsed())
+    }
+
+    /// Performs the public discussion phase over a block of rounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - Quantum execution results of every round in the block.
+    /// * `seed` - Base seed of the simulation.
+    /// * `block` - Index of the block, which selects its random stream.
+    ///
+    /// # Returns
+    ///
+    /// The `PublicDiscussionResult` of the block.
+    pub(crate) fn sift_block(
+        &self,
+        results: Vec<QExecutionResult>,
+        seed: u64,
+        block: usize,
+    ) -> PublicDiscussionResult {
+        let mut sifting_rng = seeded_rng(derive_seed(seed, SIFTING_STREAM), block as u64);
+        self.sifting.sift(results, &mut sifting_rng)
+    }
+
+    /// Simulates the quantum phase of the protocol without any post-processing.
+    ///
+    /// Rounds where Bob's detector does not click, or the measurement of the
+    /// relay fails, are publicly discarded, so they are not included in the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// The `QExecutionResult` of every detected round, in order.
+    pub fn simulate(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> Vec<QExecutionResult> {
+        self.simulate_rounds(0, number_of_qubits, interception_rate, seed)
+    }
+
+    /// Simulates the quantum phase and the public discussion, and returns which
+    /// sifted bits are mismatched between Alice and Bob.
+    ///
+    /// The check bits come first, followed by the key bits, each in random order,
+    /// so any prefix of the result is a random sample of the sifted bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// `true` for every sifted bit where Alice and Bob disagree.
+    pub fn sifted_errors(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> Vec<bool> {
+        let results = self.simulate_rounds(0, number_of_qubits, interception_rate, seed);
+        let discussion_result = self.sift_block(results, seed, 0);
+        discussion_result
+            .indexes_to_check
+            .iter()
+            .chain(&discussion_result.indexes_to_key)
+            .map(|&i| {
+                let result = &discussion_result.results[i];
+                result.alice_value != result.bob_value
+            })
+            .collect()
+    }
+
+    /// Simulates the rounds in the range [start, end) in parallel.
+    ///
+    /// `start` must be a multiple of `ROUNDS_PER_CHUNK`, so that every chunk uses
+    /// the random stream given by its global position.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Index of the first round.
+    /// * `end` - Index after the last round.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// The `QExecutionResult` of every detected round, in order.
+    fn simulate_rounds(
+        &self,
+        start: usize,
+        end: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> Vec<QExecutionResult> {
+        let tables = match self.backend {
+            Backend::Statistical if self.supports_statistical_backend() => {
+                ProbabilityTables::new(&self.alice, &self.bob, &self.eve)
+            }
+            _ => None,
+        };
+
+        let first_chunk = start / ROUNDS_PER_CHUNK;
+        let last_chunk = end.div_ceil(ROUNDS_PER_CHUNK);
+        (first_chunk..last_chunk)
+            .into_par_iter()
+            .flat_map_iter(|chunk| {
+                let mut rng = seeded_rng(seed, chunk as u64);
+                let chunk_start = chunk * ROUNDS_PER_CHUNK;
+                let chunk_end = end.min(chunk_start + ROUNDS_PER_CHUNK);
+                let rounds = chunk_start..chunk_end;
+                match &tables {
+                    Some(tables) => rounds
+                        .step_by(ROUNDS_PER_WORD)
+                        .map(|block_start| {
+                            let len = ROUNDS_PER_WORD.min(chunk_end - block_start);
+                            let mut block = tables.sample(len, interception_rate, &mut rng);
+                            block.detect(|bob_value| {
+                                self.physical_layer.detect(bob_value, &mut rng)
+                            });
+                            block
+                        })
+                        .collect::<Vec<PackedRounds>>()
+                        .iter()
+                        .flat_map(PackedRounds::rounds)
+                        .collect::<Vec<QExecutionResult>>(),
+                    None => rounds
+                        .filter_map(|_| {
+                            if let Some(relay) = &self.relay {
+                                return self.relay_communication(
+                                    relay,
+                                    interception_rate,
+                                    &mut rng,
+                                );
+                            }
+                            let mut result =
+                                self.quantum_communication(interception_rate, &mut rng);
+                            result.bob_value =
+                                self.physical_layer.detect(result.bob_value, &mut rng)?;
+                            Some(result)
+                        })
+                        .collect::<Vec<QExecutionResult>>(),
+                }
+            })
+            .collect()
     }
 
     /// Simulates a single quantum communication round between Alice and Bob,
@@ -198,30 +633,35 @@ impl QKD {
     /// # Arguments
     ///
     /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts the qubit.
+    /// * `rng` - Random number generator used by every participant in the round.
     ///
     /// # Returns
     ///
     /// A `QExecutionResult` containing the values and bases chosen by Alice, Bob, and Eve.
-    fn quantum_communication(&self, interception_rate: f64) -> QExecutionResult {
+    fn quantum_communication(
+        &self,
+        interception_rate: f64,
+        rng: &mut dyn RngCore,
+    ) -> QExecutionResult {
         // Alice
-        let (mut qubit, alice_value) = (self.alice.prepare)();
-        let alice_basis = (self.alice.change_basis)(&mut qubit, &self.alice.posible_basis);
+        let (mut qubit, alice_value, alice_basis) = self.alice.send(rng);
 
         // Eve
-        let mut eve_basis = None;
-        let mut eve_value = None;
-        if rand_float() < interception_rate {
-            eve_basis = Some((self.eve.change_basis)(&mut qubit, &self.eve.posible_basis));
-            eve_value = Some((self.eve.measure)(&mut qubit));
-            (self.eve.try_to_restore_qubit)(
-                &mut qubit,
-                &self.eve.posible_basis[eve_basis.unwrap()],
-            );
+        let (eve_value, eve_basis) = if rand_float(rng) < interception_rate {
+            let (value, basis) = self.eve.intercept(&mut qubit, rng);
+            (Some(value), Some(basis))
+        } else {
+            (None, None)
+        };
+
+        // Channel
+        let rotation_rad = self.physical_layer.channel.rotation_rad;
+        if rotation_rad != 0.0 {
+            qubit.apply_transformation(&rz(rotation_rad));
         }
 
         // Bob
-        let bob_basis = (self.bob.change_basis)(&mut qubit, &self.bob.posible_basis);
-        let bob_value = (self.bob.measure)(&mut qubit);
+        let (bob_value, bob_basis) = self.bob.receive(&mut qubit, rng);
 
         QExecutionResult::new(
             alice_value,
@@ -233,28 +673,152 @@ impl QKD {
         )
     }
 
-    /// Checks if the public values announced by Alice and Bob match.
+    /// Simulates a single round where Alice and Bob send their qubits to a relay,
+    /// which is malicious with probability `interception_rate`.
     ///
     /// # Arguments
     ///
-    /// * `alice_public_values` - Public values announced by Alice.
-    /// * `bob_public_values` - Public values announced by Bob.
+    /// * `relay` - Relay of the protocol, holding Bob's sender.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that the relay is malicious.
+    /// * `rng` - Random number generator used by every participant in the round.
     ///
     /// # Returns
     ///
-    /// `true` if all public values match, indicating no eavesdropping was detected.
-    fn check_public_values(
+    /// A `QExecutionResult` with the values prepared by Alice and Bob and the
+    /// announcement of the relay, or `None` if a qubit was lost or the relay
+    /// did not announce any Bell state.
+    fn relay_communication(
         &self,
-        alice_public_values: Vec<bool>,
-        bob_public_values: Vec<bool>,
-    ) -> bool {
-        alice_public_values
-            .into_iter()
-            .zip(bob_public_values)
-            .all(|(a, b)| a == b)
+        relay: &Relay,
+        interception_rate: f64,
+        rng: &mut dyn RngCore,
+    ) -> Option<QExecutionResult> {
+        // Alice and Bob
+        let (mut alice_qubit, alice_value, alice_basis) = self.alice.send(rng);
+        let (mut bob_qubit, bob_value, bob_basis) = relay.bob.send(rng);
+
+        // Channel
+        let alice_arrives = self.physical_layer.transmit_to_relay(&mut alice_qubit, rng);
+        let bob_arrives = self.physical_layer.transmit_to_relay(&mut bob_qubit, rng);
+        if !(alice_arrives && bob_arrives) {
+            return None;
+        }
+
+        // Relay
+        let (announcement, eve_value, eve_basis) = if rand_float(rng) < interception_rate {
+            let (value, basis, announcement) =
+                relay.intercept(&self.eve, alice_qubit, bob_qubit, rng);
+            (announcement, Some(value), Some(basis))
+        } else {
+            (relay.measure(alice_qubit, bob_qubit, rng), None, None)
+        };
+
+        let mut result = QExecutionResult::new(
+            alice_value,
+            alice_basis,
+            bob_value,
+            bob_basis,
+            eve_value,
+            eve_basis,
+        );
+        result.announcement = Some(announcement?);
+        Some(result)
+    }
+
+    /// Checks the public discussion of a block with the security check of the
+    /// sifting strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `discussion_result` - Public discussion of the block.
+    ///
+    /// # Returns
+    ///
+    /// `true` if no eavesdropping was detected.
+    pub(crate) fn check_public_values(&self, discussion_result: &PublicDiscussionResult) -> bool {
+        self.sifting
+            .is_secure(discussion_result, self.qber_threshold)
+    }
+}
+
+/// Statistics of the sifted key accumulated across blocks.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct KeyStatistics {
+    /// Number of bits in the key.
+    key_length: usize,
+    /// Number of key bits where Alice and Bob disagree.
+    mismatched_bits: usize,
+    /// Number of correct key bits also known by Eve.
+    eve_known_bits: usize,
+}
+
+impl KeyStatistics {
+    /// Adds the key bits selected in `discussion_result` to the key.
+    pub(crate) fn add_key(&mut self, discussion_result: &PublicDiscussionResult) {
+        for &i in &discussion_result.indexes_to_key {
+            let result = &discussion_result.results[i];
+            self.key_length += 1;
+            if result.alice_value != result.bob_value {
+                self.mismatched_bits += 1;
+            } else if result.eve_value == Some(result.alice_value) {
+                self.eve_known_bits += 1;
+            }
+        }
+    }
+
+    /// Builds the final `QKDResult` from the accumulated statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_considered_secure` - Whether every security check passed.
+    /// * `elapsed_time` - Total duration of the protocol execution.
+    pub(crate) fn into_result(
+        self,
+        is_considered_secure: bool,
+        elapsed_time: Duration,
+    ) -> QKDResult {
+        let mut eve_knowledge = 0.0;
+        let (mut quantum_bit_error_rate, mut key_length) = (None, None);
+        if is_considered_secure {
+            key_length = Some(self.key_length);
+            quantum_bit_error_rate = Some(self.mismatched_bits as f64 / self.key_length as f64);
+            eve_knowledge = self.eve_known_bits as f64 / self.key_length as f64;
+        }
+
+        QKDResult {
+            elapsed_time,
+            is_considered_secure,
+            key_length,
+            quantum_bit_error_rate,
+            eve_knowledge,
+        }
     }
 }
 
+/// Default security check of the public discussion.
+///
+/// # Arguments
+///
+/// * `discussion_result` - Public discussion with the values announced by Alice and Bob.
+/// * `qber_threshold` - Maximum fraction (0.0 to 1.0) of mismatched public values.
+///
+/// # Returns
+///
+/// `true` if the fraction of mismatched public values does not exceed the
+/// QBER threshold, indicating no eavesdropping was detected.
+pub fn default_security_check(
+    discussion_result: &PublicDiscussionResult,
+    qber_threshold: f64,
+) -> bool {
+    let mismatched_values = discussion_result
+        .alice_public_values
+        .iter()
+        .zip(&discussion_result.bob_public_values)
+        .filter(|(a, b)| a != b)
+        .count();
+    mismatched_values as f64 <= qber_threshold * discussion_result.alice_public_values.len() as f64
+}
+
 /// Default public basis discussion function.
 ///
 /// Selects a random subset of matching basis results for public comparison,
@@ -263,11 +827,15 @@ impl QKD {
 /// # Arguments
 ///
 /// * `results` - Vector of quantum execution results.
+/// * `rng` - Random number generator used to choose the check bits.
 ///
 /// # Returns
 ///
 /// A `PublicDiscussionResult` containing the public values, key indexes, and results.
-fn default_public_basis_discussion(results: &Vec<QExecutionResult>) -> PublicDiscussionResult {
+pub fn default_public_basis_discussion(
+    results: Vec<QExecutionResult>,
+    rng: &mut dyn RngCore,
+) -> PublicDiscussionResult {
     let (alice_basis, bob_basis): (Vec<usize>, Vec<usize>) =
         results.iter().map(|x| (x.alice_basis, x.bob_basis)).unzip();
 
@@ -279,7 +847,7 @@ fn default_public_basis_discussion(results: &Vec<QExecutionResult>) -> PublicDis
         .map(|(i, _)| i)
         .collect::<Vec<usize>>();
 
-    let (indexes_to_check, indexes_to_key) = shuffle_and_split(eq_basis_indexes);
+    let (indexes_to_check, indexes_to_key) = shuffle_and_split(eq_basis_indexes, rng);
 
     let (alice_public_values, bob_public_values) = indexes_to_check
         .iter()
@@ -289,7 +857,8 @@ fn default_public_basis_discussion(results: &Vec<QExecutionResult>) -> PublicDis
     PublicDiscussionResult {
         alice_public_values,
         bob_public_values,
+        indexes_to_check,
         indexes_to_key,
-        results: results.to_vec(),
+        results,
     }
 }
@@ -0,0 +1,565 @@
+use crate::backend::StatisticalPreparation;
+use crate::cvqkd::{CvQkdProtocol, GG02Heterodyne, GG02Homodyne};
+use crate::mdi::MDI;
+use crate::participants::{PreparationStrategy, Receiver, Sender};
+use crate::postprocessing::binary_entropy;
+use crate::protocol::{PublicDiscussionResult, QExecutionResult, QKDResult, SiftingStrategy, QKD};
+use crate::theory::TheoreticalPrediction;
+use crate::types::Qubit;
+use crate::utils::{shuffle_and_split, H, H_Y, I};
+use rand::RngCore;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A named QKD protocol that can be built into a runnable `QKD` instance.
+///
+/// Downstream crates can implement this trait and add their protocols to a
+/// `ProtocolRegistry` to make them available to the CLI.
+pub trait QkdProtocol: Send + Sync {
+    /// Unique name used to select the protocol (e.g. `BB84`).
+    fn name(&self) -> &str;
+
+    /// Short human-readable description of the protocol.
+    fn description(&self) -> &str;
+
+    /// Builds the participants and sifting logic of the protocol.
+    fn build(&self) -> QKD;
+
+    /// Executes the protocol with the specified number of qubits and interception rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to be used in the protocol.
+    /// * `interception_rate` - Probability that Eve intercepts a qubit (0.0 to 1.0).
+    ///
+    /// # Returns
+    ///
+    /// A `QKDResult` containing the protocol execution results.
+    fn run(&self, number_of_qubits: usize, interception_rate: f64) -> QKDResult {
+        self.build().run(number_of_qubits, interception_rate)
+    }
+
+    /// Executes the protocol drawing all randomness from the given seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits to be used in the protocol.
+    /// * `interception_rate` - Probability that Eve intercepts a qubit (0.0 to 1.0).
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// A `QKDResult` containing the protocol execution results.
+    fn run_with_seed(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> QKDResult {
+        self.build()
+            .run_with_seed(number_of_qubits, interception_rate, seed)
+    }
+
+    /// Analytic expectations of the protocol under an intercept-resend attack
+    /// with ideal hardware, used to validate the simulation.
+    ///
+    /// Returns `None` by default, for protocols without a closed-form model.
+    ///
+    /// # Arguments
+    ///
+    /// * `interception_rate` - Probability that Eve intercepts a qubit (0.0 to 1.0).
+    fn theoretical_prediction(&self, _interception_rate: f64) -> Option<TheoreticalPrediction> {
+        None
+    }
+}
+
+/// Collection of protocols available by name.
+///
+/// Protocols are kept sorted by name. Discrete-variable protocols (`QkdProtocol`)
+/// and continuous-variable protocols (`CvQkdProtocol`) share the same names, so
+/// registering a protocol replaces any protocol with the same name of either
+/// kind. The default registry contains the protocols implemented by this crate:
+/// BB84, Six-State, B92, RFI-QKD, MDI-QKD and GG02 with homodyne and heterodyne
+/// detection.
+#[derive(Clone)]
+pub struct ProtocolRegistry {
+    protocols: BTreeMap<String, Arc<dyn QkdProtocol>>,
+    continuous_protocols: BTreeMap<String, Arc<dyn CvQkdProtocol>>,
+}
+
+impl ProtocolRegistry {
+    /// Creates a registry without any protocol.
+    pub fn empty() -> Self {
+        ProtocolRegistry {
+            protocols: BTreeMap::new(),
+            continuous_protocols: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a protocol to the registry, replacing any protocol with the same name.
+    pub fn register<P: QkdProtocol + 'static>(&mut self, protocol: P) -> &mut Self {
+        self.continuous_protocols.remove(protocol.name());
+        self.protocols
+            .insert(protocol.name().to_string(), Arc::new(protocol));
+        self
+    }
+
+    /// Adds a continuous-variable protocol to the registry, replacing any
+    /// protocol with the same name.
+    pub fn register_continuous<P: CvQkdProtocol + 'static>(&mut self, protocol: P) -> &mut Self {
+        self.protocols.remove(protocol.name());
+        self.continuous_protocols
+            .insert(protocol.name().to_string(), Arc::new(protocol));
+        self
+    }
+
+    /// Returns the discrete-variable protocol registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn QkdProtocol>> {
+        self.protocols.get(name).cloned()
+    }
+
+    /// Returns the continuous-variable protocol registered under `name`, if any.
+    pub fn get_continuous(&self, name: &str) -> Option<Arc<dyn CvQkdProtocol>> {
+        self.continuous_protocols.get(name).cloned()
+    }
+
+    /// Returns the names of all registered discrete-variable protocols.
+    pub fn names(&self) -> Vec<&str> {
+        self.protocols.keys().map(String::as_str).collect()
+    }
+
+    /// Returns the names of all registered continuous-variable protocols.
+    pub fn continuous_names(&self) -> Vec<&str> {
+        self.continuous_protocols
+            .keys()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Iterates over all registered discrete-variable protocols sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn QkdProtocol>> {
+        self.protocols.values()
+    }
+
+    /// Iterates over all registered continuous-variable protocols sorted by name.
+    pub fn iter_continuous(&self) -> impl Iterator<Item = &Arc<dyn CvQkdProtocol>> {
+        self.continuous_protocols.values()
+    }
+}
+
+impl Default for ProtocolRegistry {
+    fn default() -> Self {
+        let mut registry = ProtocolRegistry::empty();
+        registry
+            .register(BB84)
+            .register(SixState)
+            .register(B92)
+            .register(RFI)
+            .register(MDI)
+            .register_continuous(GG02Homodyne)
+            .register_continuous(GG02Heterodyne);
+        registry
+    }
+}
+
+/// BB84 protocol: two conjugate bases (Z and X).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BB84;
+
+impl QkdProtocol for BB84 {
+    fn name(&self) -> &str {
+        "BB84"
+    }
+
+    fn description(&self) -> &str {
+        "Bennett-Brassard 1984 protocol using the Z and X bases"
+    }
+
+    fn build(&self) -> QKD {
+        let alice = Sender::builder().posible_basis(vec![I, H]).build();
+        let bob = Receiver::builder().posible_basis(vec![I, H]).build();
+
+        QKD::builder().alice(alice).bob(bob).build()
+    }
+
+    /// Bases match half of the time. Eve uses the wrong basis half of the time,
+    /// which gives Bob a random bit, so she causes an error in a quarter of the
+    /// intercepted rounds and knows the bit in 1/2 + 1/8 of them.
+    fn theoretical_prediction(&self, interception_rate: f64) -> Option<TheoreticalPrediction> {
+        Some(TheoreticalPrediction {
+            sift_rate: 1.0 / 2.0,
+            quantum_bit_error_rate: interception_rate / 4.0,
+            eve_knowledge: interception_rate * 5.0 / 8.0,
+        })
+    }
+}
+
+/// Six-State protocol: three mutually unbiased bases (Z, X and Y).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SixState;
+
+impl QkdProtocol for SixState {
+    fn name(&self) -> &str {
+        "SixState"
+    }
+
+    fn description(&self) -> &str {
+        "Six-State protocol using the Z, X and Y bases"
+    }
+
+    fn build(&self) -> QKD {
+        let alice = Sender::builder().posible_basis(vec![I, H, H_Y]).build();
+        let bob = Receiver::builder()
+            .posible_basis(vec![I, H, H_Y.invert().unwrap()])
+            .build();
+        let eve = Receiver::builder()
+            .posible_basis(vec![I, H, H_Y.invert().unwrap()])
+            .build();
+
+        QKD::builder().alice(alice).bob(bob).eve(eve).build()
+    }
+
+    /// Bases match a third of the time. Eve uses the wrong basis two thirds of
+    /// the time, which gives Bob a random bit, so she causes an error in a third
+    /// of the intercepted rounds and knows the bit in 1/3 + 1/6 of them.
+    fn theoretical_prediction(&self, interception_rate: f64) -> Option<TheoreticalPrediction> {
+        Some(TheoreticalPrediction {
+            sift_rate: 1.0 / 3.0,
+            quantum_bit_error_rate: interception_rate / 3.0,
+            eve_knowledge: interception_rate / 2.0,
+        })
+    }
+}
+
+/// B92 protocol: two non-orthogonal states, one per basis.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct B92;
+
+impl QkdProtocol for B92 {
+    fn name(&self) -> &str {
+        "B92"
+    }
+
+    fn description(&self) -> &str {
+        "Bennett 1992 protocol using two non-orthogonal states"
+    }
+
+    fn build(&self) -> QKD {
+        let alice = Sender::builder()
+            .posible_basis(vec![I, H])
+            .strategy(Arc::new(B92Preparation))
+            .build();
+        let bob = Receiver::builder().posible_basis(vec![I, H]).build();
+
+        QKD::builder()
+            .alice(alice)
+            .bob(bob)
+            .sifting(Arc::new(B92Sifting))
+            .build()
+    }
+
+    /// Without Eve, a quarter of the rounds are conclusive. A state resent by
+    /// Eve is conclusive with probability 3/8, and wrong with probability 1/8,
+    /// so conclusive rounds occur with probability (2 + r) / 8 and errors with r / 8.
+    /// Eve's raw measurement matches the key bit in r / 8 of the rounds as well.
+    fn theoretical_prediction(&self, interception_rate: f64) -> Option<TheoreticalPrediction> {
+        let error_rate = interception_rate / (2.0 + interception_rate);
+        Some(TheoreticalPrediction {
+            sift_rate: (2.0 + interception_rate) / 8.0,
+            quantum_bit_error_rate: error_rate,
+            eve_knowledge: error_rate,
+        })
+    }
+}
+
+/// Preparation strategy of the B92 protocol.
+///
+/// Alice always prepares |0⟩, so the encoded bit is given by the chosen basis.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct B92Preparation;
+
+impl PreparationStrategy for B92Preparation {
+    fn prepare(&self, _rng: &mut dyn RngCore) -> (Qubit, bool) {
+        (Qubit::new(), false)
+    }
+
+    fn statistical_model(&self) -> Option<StatisticalPreparation> {
+        Some(StatisticalPreparation::FixedZero)
+    }
+}
+
+/// Sifting strategy of the B92 protocol.
+///
+/// Only conclusive measurements (Bob measured 1) are kept, and the bit
+/// values are derived from the bases used by Alice and Bob.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct B92Sifting;
+
+impl SiftingStrategy for B92Sifting {
+    fn sift(
+        &self,
+        results: Vec<QExecutionResult>,
+        rng: &mut dyn RngCore,
+    ) -> PublicDiscussionResult {
+        public_basis_discussion_b92(results, rng)
+    }
+
+    fn describe_sifting(&self) -> String {
+        "Alice's bit is given by her basis (0 for Z, 1 for X), as shown above. Bob only\n\
+         keeps the conclusive rounds, where he measured 1: the state cannot then be the\n\
+         one of his basis, so his bit is 1 if he used Z and 0 if he used X. Half of the\n\
+         kept rounds are chosen at random as check bits, and the rest are kept for the\n\
+         key. Without Eve or noise, Alice and Bob always agree on them."
+            .to_string()
+    }
+}
+
+/// Performs the public basis discussion specific to the B92 protocol.
+///
+/// # Arguments
+/// * `results` - Vector of execution results from the B92 protocol.
+/// * `rng` - Random number generator used to choose the check bits.
+///
+/// # Returns
+/// A `PublicDiscussionResult` containing the results of the public discussion phase.
+fn public_basis_discussion_b92(
+    mut results: Vec<QExecutionResult>,
+    rng: &mut dyn RngCore,
+) -> PublicDiscussionResult {
+    let mut conclusive_indexes = Vec::new();
+    results.iter_mut().enumerate().for_each(|(i, result)| {
+        if result.bob_value {
+            conclusive_indexes.push(i);
+            result.bob_value = result.bob_basis == 0;
+        }
+        result.alice_value = result.alice_basis == 1;
+    });
+
+    let (indexes_to_check, indexes_to_key) = shuffle_and_split(conclusive_indexes, rng);
+    let (alice_public_values, bob_public_values) = indexes_to_check
+        .iter()
+        .map(|&i| (results[i].alice_value, results[i].bob_value))
+        .unzip();
+
+    PublicDiscussionResult {
+        alice_public_values,
+        bob_public_values,
+        indexes_to_check,
+        indexes_to_key,
+        results,
+    }
+}
+
+/// Reference-frame-independent protocol (RFI-QKD): the Z basis is shared by
+/// Alice and Bob, but their X and Y bases may drift by an unknown angle β
+/// around it, e.g. with a `Channel` whose `rotation_rad` is β.
+///
+/// The key is built from the rounds where both used Z, and the rounds where
+/// both used X or Y are revealed to estimate the C parameter, which does not
+/// depend on β. The protocol is aborted if the key rate bounded from the QBER
+/// of the Z check bits and C is not positive (see `RfiEstimate`), so its QBER
+/// threshold is ignored and `QKD::uses_qber_threshold` is `false`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RFI;
+
+impl QkdProtocol for RFI {
+    fn name(&self) -> &str {
+        "RFI"
+    }
+
+    fn description(&self) -> &str {
+        "Reference-frame-independent protocol with a shared Z basis and drifting X and Y bases"
+    }
+
+    fn build(&self) -> QKD {
+        let alice = Sender::builder().posible_basis(vec![I, H, H_Y]).build();
+        let bob = Receiver::builder()
+            .posible_basis(vec![I, H, H_Y.adjoint()])
+            .build();
+        let eve = Receiver::builder()
+            .posible_basis(vec![I, H, H_Y.adjoint()])
+            .build();
+
+        QKD::builder()
+            .alice(alice)
+            .bob(bob)
+            .eve(eve)
+            .sifting(Arc::new(RfiSifting))
+            .build()
+    }
+}
+
+/// Index of the Z basis in the bases of the RFI-QKD protocol.
+const RFI_Z_BASIS: usize = 0;
+
+/// Sifting strategy of the RFI-QKD protocol.
+///
+/// Rounds where Alice and Bob used Z are split into check and key bits as in
+/// `default_public_basis_discussion`. Every round where both used X or Y is
+/// also publicly compared, after the Z check bits, to estimate the C parameter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RfiSifting;
+
+impl SiftingStrategy for RfiSifting {
+    fn sift(
+        &self,
+        results: Vec<QExecutionResult>,
+        rng: &mut dyn RngCore,
+    ) -> PublicDiscussionResult {
+        let (z_indexes, xy_indexes): (Vec<usize>, Vec<usize>) = (0..results.len())
+            .filter(|&i| {
+                let result = &results[i];
+                (result.alice_basis == RFI_Z_BASIS) == (result.bob_basis == RFI_Z_BASIS)
+            })
+            .partition(|&i| results[i].alice_basis == RFI_Z_BASIS);
+
+        let (mut indexes_to_check, indexes_to_key) = shuffle_and_split(z_indexes, rng);
+        indexes_to_check.extend(xy_indexes);
+        let (alice_public_values, bob_public_values) = indexes_to_check
+            .iter()
+            .map(|&i| (results[i].alice_value, results[i].bob_value))
+            .unzip();
+
+        PublicDiscussionResult {
+            alice_public_values,
+            bob_public_values,
+            indexes_to_check,
+            indexes_to_key,
+            results,
+        }
+    }
+
+    fn is_secure(&self, discussion_result: &PublicDiscussionResult, _qber_threshold: f64) -> bool {
+        RfiEstimate::from_discussion(discussion_result)
+            .is_some_and(|estimate| estimate.key_rate > 0.0)
+    }
+
+    fn uses_qber_threshold(&self) -> bool {
+        false
+    }
+
+    fn describe_sifting(&self) -> String {
+        "Half of the kept rounds where both used Z are chosen at random as check bits,\n\
+         and the rest are kept for the key. Every round where both used X or Y is also\n\
+         revealed as a check bit: those only agree up to the drift of the reference\n\
+         frames, and are used to estimate the C parameter."
+            .to_string()
+    }
+
+    fn describe_security_check(
+        &self,
+        discussion_result: &PublicDiscussionResult,
+        _qber_threshold: f64,
+    ) -> String {
+        match RfiEstimate::from_discussion(discussion_result) {
+            Some(estimate) => format!(
+                "The Z check bits have a QBER of {:.4} and the X and Y rounds give\n\
+                 C = {:.4}, which bound the information of Eve to {:.4} and the key rate\n\
+                 to {:.4} per key bit. The protocol is aborted if the key rate is not positive.",
+                estimate.quantum_bit_error_rate,
+                estimate.c_parameter,
+                estimate.eve_information,
+                estimate.key_rate
+            ),
+            None => "The Z basis or one of the pairs of X and Y bases was never compared,\n\
+                     so the C parameter cannot be estimated and the protocol is aborted."
+                .to_string(),
+        }
+    }
+}
+
+/// Security parameters of the RFI-QKD protocol estimated from its public discussion.
+///
+/// Follows the asymptotic analysis of Laing et al. (Phys. Rev. A 82, 012304),
+/// where Eve's information on the key is bounded from the QBER `e` of the Z
+/// basis and the C parameter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RfiEstimate {
+    /// Fraction of mismatched check bits measured in the Z basis.
+    pub quantum_bit_error_rate: f64,
+    /// C = ⟨XX⟩² + ⟨XY⟩² + ⟨YX⟩² + ⟨YY⟩², where ⟨AB⟩ is the correlation of the
+    /// values of Alice in basis A and Bob in basis B. It is 2 for an ideal
+    /// channel whatever the drift of the frames, and 0 without correlations.
+    pub c_parameter: f64,
+    /// Upper bound on the information of Eve per key bit.
+    pub eve_information: f64,
+    /// Secret key rate per sifted key bit, 1 - h(e) - `eve_information`.
+    pub key_rate: f64,
+}
+
+impl RfiEstimate {
+    /// Bounds the information of Eve and the key rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `quantum_bit_error_rate` - QBER of the Z basis.
+    /// * `c_parameter` - Estimated C parameter, between 0 and 2.
+    ///
+    /// # Returns
+    ///
+    /// The `RfiEstimate` of the given parameters.
+    pub fn new(quantum_bit_error_rate: f64, c_parameter: f64) -> Self {
+        let e = quantum_bit_error_rate;
+        let correlation = (c_parameter.max(0.0) / 2.0).sqrt();
+        let u = if e < 1.0 {
+            (correlation / (1.0 - e)).min(1.0)
+        } else {
+            1.0
+        };
+        let v = if e > 0.0 {
+            ((c_parameter / 2.0 - ((1.0 - e) * u).powi(2)).max(0.0)).sqrt() / e
+        } else {
+            0.0
+        };
+        let eve_information = (1.0 - e) * binary_entropy((1.0 + u) / 2.0)
+            + e * binary_entropy((1.0 + v.min(1.0)) / 2.0);
+
+        RfiEstimate {
+            quantum_bit_error_rate,
+            c_parameter,
+            eve_information,
+            key_rate: 1.0 - binary_entropy(e) - eve_information,
+        }
+    }
+
+    /// Estimates the parameters from the public discussion of the RFI-QKD protocol.
+    ///
+    /// # Arguments
+    ///
+    /// * `discussion_result` - Public discussion returned by `RfiSifting`.
+    ///
+    /// # Returns
+    ///
+    /// The `RfiEstimate`, or `None` if there are no Z check bits or one of the
+    /// four pairs of X and Y bases was never compared.
+    pub fn from_discussion(discussion_result: &PublicDiscussionResult) -> Option<Self> {
+        // Rounds and mismatches of the Z basis, then of every pair of X (1) and Y (2) bases.
+        let mut z_counts = (0usize, 0usize);
+        let mut xy_counts = [[(0usize, 0usize); 2]; 2];
+        for &i in &discussion_result.indexes_to_check {
+            let result = &discussion_result.results[i];
+            let counts = match (result.alice_basis, result.bob_basis) {
+                (RFI_Z_BASIS, RFI_Z_BASIS) => &mut z_counts,
+                (alice @ 1..=2, bob @ 1..=2) => &mut xy_counts[alice - 1][bob - 1],
+                _ => continue,
+            };
+            counts.0 += 1;
+            counts.1 += usize::from(result.alice_value != result.bob_value);
+        }
+
+        if z_counts.0 == 0 {
+            return None;
+        }
+        let mut c_parameter = 0.0;
+        for &(rounds, mismatches) in xy_counts.iter().flatten() {
+            if rounds == 0 {
+                return None;
+            }
+            c_parameter += (1.0 - 2.0 * mismatches as f64 / rounds as f64).powi(2);
+        }
+        Some(RfiEstimate::new(
+            z_counts.1 as f64 / z_counts.0 as f64,
+            c_parameter,
+        ))
+    }
+}
@@ -0,0 +1,528 @@
+use crate::backend::Backend;
+use crate::participants::Receiver;
+use crate::physical::{Channel, Detector, PhysicalLayer, Source};
+use crate::protocol::{QKDResult, QKD};
+use crate::protocols::ProtocolRegistry;
+use crate::types::ComplexMatrix;
+use crate::utils::{derive_seed, random_seed, H, H_Y, I};
+
+use numpy::PyArray1;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+
+/// Photon source used by Alice.
+#[pyclass(name = "Source", module = "qkd", frozen)]
+#[derive(Clone, Copy)]
+pub struct PySource(pub Source);
+
+#[pymethods]
+impl PySource {
+    /// Creates a source of weak coherent pulses, or of single photons if
+    /// `mean_photon_number` is omitted.
+    #[new]
+    #[pyo3(signature = (mean_photon_number=None))]
+    fn new(mean_photon_number: Option<f64>) -> PyResult<Self> {
+        if let Some(mean_photon_number) = mean_photon_number {
+            check_non_negative("mean_photon_number", mean_photon_number)?;
+        }
+        Ok(PySource(Source { mean_photon_number }))
+    }
+
+    #[getter]
+    fn mean_photon_number(&self) -> Option<f64> {
+        self.0.mean_photon_number
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Quantum channel between Alice and Bob.
+#[pyclass(name = "Channel", module = "qkd", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyChannel(pub Channel);
+
+#[pymethods]
+impl PyChannel {
+    /// Creates a channel with the given loss, depolarization, fiber length and
+    /// rotation of the polarization around the Z axis.
+    #[new]
+    #[pyo3(signature = (
+        distance_km=0.0,
+        loss=0.0,
+        depolarization=0.0,
+        attenuation_db_per_km=crate::physical::FIBER_ATTENUATION_DB_PER_KM,
+        rotation_rad=0.0,
+    ))]
+    fn new(
+        distance_km: f64,
+        loss: f64,
+        depolarization: f64,
+        attenuation_db_per_km: f64,
+        rotation_rad: f64,
+    ) -> PyResult<Self> {
+        check_non_negative("distance_km", distance_km)?;
+        check_probability("loss", loss)?;
+        check_probability("depolarization", depolarization)?;
+        check_non_negative("attenuation_db_per_km", attenuation_db_per_km)?;
+        if !rotation_rad.is_finite() {
+            return Err(PyValueError::new_err(format!(
+                "`rotation_rad` must be a finite number, found {}",
+                rotation_rad
+            )));
+        }
+        Ok(PyChannel(Channel {
+            loss,
+            depolarization,
+            distance_km,
+            attenuation_db_per_km,
+            rotation_rad,
+        }))
+    }
+
+    #[getter]
+    fn distance_km(&self) -> f64 {
+        self.0.distance_km
+    }
+
+    #[getter]
+    fn loss(&self) -> f64 {
+        self.0.loss
+    }
+
+    #[getter]
+    fn depolarization(&self) -> f64 {
+        self.0.depolarization
+    }
+
+    #[getter]
+    fn attenuation_db_per_km(&self) -> f64 {
+        self.0.attenuation_db_per_km
+    }
+
+    #[getter]
+    fn rotation_rad(&self) -> f64 {
+        self.0.rotation_rad
+    }
+
+    /// Returns the probability that a photon reaches Bob.
+    fn transmittance(&self) -> f64 {
+        self.0.transmittance()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Single-photon detector used by Bob.
+#[pyclass(name = "Detector", module = "qkd", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyDetector(pub Detector);
+
+#[pymethods]
+impl PyDetector {
+    /// Creates a detector with the given efficiency and dark count probability.
+    #[new]
+    #[pyo3(signature = (efficiency=1.0, dark_count_probability=0.0))]
+    fn new(efficiency: f64, dark_count_probability: f64) -> PyResult<Self> {
+        check_probability("efficiency", efficiency)?;
+        check_probability("dark_count_probability", dark_count_probability)?;
+        Ok(PyDetector(Detector {
+            efficiency,
+            dark_count_probability,
+        }))
+    }
+
+    #[getter]
+    fn efficiency(&self) -> f64 {
+        self.0.efficiency
+    }
+
+    #[getter]
+    fn dark_count_probability(&self) -> f64 {
+        self.0.dark_count_probability
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Result of a protocol execution.
+#[pyclass(name = "QKDResult", module = "qkd", frozen)]
+#[derive(Clone)]
+pub struct PyQkdResult(pub QKDResult);
+
+#[pymethods]
+impl PyQkdResult {
+    /// Execution time in seconds.
+    #[getter]
+    fn elapsed_time(&self) -> f64 {
+        self.0.elapsed_time.as_secs_f64()
+    }
+
+    #[getter]
+    fn is_considered_secure(&self) -> bool {
+        self.0.is_considered_secure
+    }
+
+    /// Length of the key, or `None` if the protocol was aborted.
+    #[getter]
+    fn key_length(&self) -> Option<usize> {
+        self.0.key_length
+    }
+
+    /// QBER of the check bits, or `None` if it is not defined.
+    #[getter]
+    fn quantum_bit_error_rate(&self) -> Option<f64> {
+        self.0
+            .quantum_bit_error_rate
+            .filter(|quantum_bit_error_rate| quantum_bit_error_rate.is_finite())
+    }
+
+    #[getter]
+    fn eve_knowledge(&self) -> f64 {
+        self.0.eve_knowledge
+    }
+
+    /// Returns the result as a dictionary with the keys of the JSON output.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("time_us", self.0.elapsed_time.as_micros() as u64)?;
+        dict.set_item("is_considered_secure", self.0.is_considered_secure)?;
+        dict.set_item("key_length", self.0.key_length)?;
+        dict.set_item("eve_knowledge", self.0.eve_knowledge)?;
+        dict.set_item("qber", self.quantum_bit_error_rate())?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "QKDResult(is_considered_secure={}, key_length={:?}, quantum_bit_error_rate={:?}, eve_knowledge={})",
+            self.0.is_considered_secure,
+            self.0.key_length,
+            self.quantum_bit_error_rate(),
+            self.0.eve_knowledge
+        )
+    }
+}
+
+/// Instance of a registered protocol with its simulation backend, physical
+/// layer, eavesdropper and abort threshold.
+#[pyclass(name = "QKD", module = "qkd", frozen)]
+pub struct PyQkd {
+    qkd: QKD,
+}
+
+#[pymethods]
+impl PyQkd {
+    /// Builds a registered protocol.
+    ///
+    /// `backend` is `"state-vector"` or `"statistical"`, and `eve_basis` lists
+    /// the bases measured by Eve among `"Z"`, `"X"` and `"Y"` (by default, the
+    /// bases of the protocol). `qber_threshold` is rejected for the protocols
+    /// whose security check ignores it, like `"RFI"`.
+    #[new]
+    #[pyo3(signature = (
+        protocol,
+        *,
+        backend="state-vector",
+        qber_threshold=None,
+        source=None,
+        channel=None,
+        detector=None,
+        eve_basis=None,
+    ))]
+    fn new(
+        protocol: &str,
+        backend: &str,
+        qber_threshold: Option<f64>,
+        source: Option<PySource>,
+        channel: Option<PyChannel>,
+        detector: Option<PyDetector>,
+        eve_basis: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let registry = ProtocolRegistry::default();
+        let protocol = registry.get(protocol).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "`{}` is not a registered protocol. Registered protocols are: {:?}",
+                protocol,
+                registry.names()
+            ))
+        })?;
+        let backend = match backend {
+            "state-vector" => Backend::StateVector,
+            "statistical" => Backend::Statistical,
+            backend => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown backend `{}`. Backends are: [\"state-vector\", \"statistical\"]",
+                    backend
+                )))
+            }
+        };
+
+        let mut qkd = protocol
+            .build()
+            .with_backend(backend)
+            .with_physical_layer(PhysicalLayer {
+                source: source.map_or_else(Source::default, |source| source.0),
+                channel: channel.map_or_else(Channel::default, |channel| channel.0),
+                detector: detector.map_or_else(Detector::default, |detector| detector.0),
+            });
+        if let Some(qber_threshold) = qber_threshold {
+            if !qkd.uses_qber_threshold() {
+                return Err(PyValueError::new_err(format!(
+                    "`{}` does not abort based on a QBER threshold, so `qber_threshold` cannot be used",
+                    protocol.name()
+                )));
+            }
+            check_probability("qber_threshold", qber_threshold)?;
+            qkd = qkd.with_qber_threshold(qber_threshold);
+        }
+        if let Some(names) = eve_basis {
+            let posible_basis = names
+                .iter()
+                .map(|name| measurement_basis(name))
+                .collect::<PyResult<Vec<ComplexMatrix>>>()?;
+            qkd = qkd.with_eve(Receiver::builder().posible_basis(posible_basis).build());
+        }
+        Ok(PyQkd { qkd })
+    }
+
+    /// Executes the protocol once. The seed is random if omitted.
+    #[pyo3(signature = (number_of_qubits, interception_rate=0.0, seed=None, block_size=None))]
+    fn run(
+        &self,
+        py: Python<'_>,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: Option<u64>,
+        block_size: Option<usize>,
+    ) -> PyResult<PyQkdResult> {
+        check_probability("interception_rate", interception_rate)?;
+        let seed = seed.unwrap_or_else(random_seed);
+        let result =
+            py.detach(|| self.run_with_seed(number_of_qubits, interception_rate, seed, block_size));
+        Ok(PyQkdResult(result))
+    }
+
+    /// Executes `repetitions` independent runs in parallel, seeded as the
+    /// repetitions of the CLI, and returns every metric as a NumPy array.
+    ///
+    /// Metrics that are not defined for aborted runs are `NaN`.
+    #[pyo3(signature = (
+        number_of_qubits,
+        interception_rate=0.0,
+        repetitions=1,
+        seed=None,
+        block_size=None,
+    ))]
+    fn run_many<'py>(
+        &self,
+        py: Python<'py>,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        repetitions: usize,
+        seed: Option<u64>,
+        block_size: Option<usize>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        check_probability("interception_rate", interception_rate)?;
+        // Raises `ModuleNotFoundError` instead of panicking when creating the arrays.
+        py.import("numpy")?;
+        let seed = seed.unwrap_or_else(random_seed);
+        let results = py.detach(|| {
+            (0..repetitions)
+                .into_par_iter()
+                .map(|repetition| {
+                    let seed = derive_seed(seed, repetition as u64);
+                    self.run_with_seed(number_of_qubits, interception_rate, seed, block_size)
+                })
+                .collect::<Vec<QKDResult>>()
+        });
+
+        let column = |metric: fn(&QKDResult) -> f64| {
+            PyArray1::from_vec(py, results.iter().map(metric).collect())
+        };
+        let dict = PyDict::new(py);
+        dict.set_item(
+            "time_us",
+            column(|result| result.elapsed_time.as_micros() as f64),
+        )?;
+        dict.set_item(
+            "is_considered_secure",
+            PyArray1::from_vec(
+                py,
+                results
+                    .iter()
+                    .map(|result| result.is_considered_secure)
+                    .collect(),
+            ),
+        )?;
+        dict.set_item(
+            "key_length",
+            column(|result| result.key_length.map_or(f64::NAN, |length| length as f64)),
+        )?;
+        dict.set_item(
+            "eve_knowledge",
+            column(|result| {
+                if result.is_considered_secure {
+                    result.eve_knowledge
+                } else {
+                    f64::NAN
+                }
+            }),
+        )?;
+        dict.set_item(
+            "qber",
+            column(|result| result.quantum_bit_error_rate.unwrap_or(f64::NAN)),
+        )?;
+        Ok(dict)
+    }
+
+    /// Maximum fraction of mismatched check bits tolerated before aborting.
+    #[getter]
+    fn qber_threshold(&self) -> f64 {
+        self.qkd.qber_threshold()
+    }
+}
+
+impl PyQkd {
+    fn run_with_seed(
+        &self,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+        block_size: Option<usize>,
+    ) -> QKDResult {
+        match block_size {
+            Some(block_size) => self.qkd.run_streaming_with_seed(
+                number_of_qubits,
+                interception_rate,
+                seed,
+                block_size,
+            ),
+            None => self
+                .qkd
+                .run_with_seed(number_of_qubits, interception_rate, seed),
+        }
+    }
+}
+
+/// Executes the BB84 protocol. The seed is random if omitted.
+#[pyfunction]
+#[pyo3(signature = (number_of_qubits, interception_rate=0.0, seed=None))]
+fn run_bb84(
+    py: Python<'_>,
+    number_of_qubits: usize,
+    interception_rate: f64,
+    seed: Option<u64>,
+) -> PyResult<PyQkdResult> {
+    PyQkd::new("BB84", "state-vector", None, None, None, None, None)?.run(
+        py,
+        number_of_qubits,
+        interception_rate,
+        seed,
+        None,
+    )
+}
+
+/// Executes the Six-State protocol. The seed is random if omitted.
+#[pyfunction]
+#[pyo3(signature = (number_of_qubits, interception_rate=0.0, seed=None))]
+fn run_six_state(
+    py: Python<'_>,
+    number_of_qubits: usize,
+    interception_rate: f64,
+    seed: Option<u64>,
+) -> PyResult<PyQkdResult> {
+    PyQkd::new("SixState", "state-vector", None, None, None, None, None)?.run(
+        py,
+        number_of_qubits,
+        interception_rate,
+        seed,
+        None,
+    )
+}
+
+/// Executes the B92 protocol. The seed is random if omitted.
+#[pyfunction]
+#[pyo3(signature = (number_of_qubits, interception_rate=0.0, seed=None))]
+fn run_b92(
+    py: Python<'_>,
+    number_of_qubits: usize,
+    interception_rate: f64,
+    seed: Option<u64>,
+) -> PyResult<PyQkdResult> {
+    PyQkd::new("B92", "state-vector", None, None, None, None, None)?.run(
+        py,
+        number_of_qubits,
+        interception_rate,
+        seed,
+        None,
+    )
+}
+
+/// Returns the names of the registered protocols.
+#[pyfunction]
+fn protocols() -> Vec<String> {
+    ProtocolRegistry::default()
+        .names()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Python extension module `qkd`, built with `maturin` and the `python` feature.
+#[pymodule]
+pub fn qkd(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    module.add_class::<PyQkd>()?;
+    module.add_class::<PyQkdResult>()?;
+    module.add_class::<PySource>()?;
+    module.add_class::<PyChannel>()?;
+    module.add_class::<PyDetector>()?;
+    module.add_function(wrap_pyfunction!(run_bb84, module)?)?;
+    module.add_function(wrap_pyfunction!(run_six_state, module)?)?;
+    module.add_function(wrap_pyfunction!(run_b92, module)?)?;
+    module.add_function(wrap_pyfunction!(protocols, module)?)?;
+    Ok(())
+}
+
+/// Returns the matrix measuring in the basis named `Z`, `X` or `Y`.
+fn measurement_basis(name: &str) -> PyResult<ComplexMatrix> {
+    match name {
+        "Z" => Ok(I),
+        "X" => Ok(H),
+        "Y" => Ok(H_Y.invert().expect("H_Y is unitary")),
+        name => Err(PyValueError::new_err(format!(
+            "Unknown basis `{}`. Bases are: [\"Z\", \"X\", \"Y\"]",
+            name
+        ))),
+    }
+}
+
+fn check_probability(name: &str, value: f64) -> PyResult<()> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "`{}` must be between 0 and 1, got {}",
+            name, value
+        )))
+    }
+}
+
+fn check_non_negative(name: &str, value: f64) -> PyResult<()> {
+    if value >= 0.0 {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "`{}` must be non-negative, got {}",
+            name, value
+        )))
+    }
+}
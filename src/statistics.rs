@@ -0,0 +1,287 @@
+use crate::protocol::QKDResult;
+use std::f64::consts::PI;
+
+/// Summary statistics of a sample.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    /// Number of values in the sample.
+    pub count: usize,
+    /// Arithmetic mean of the sample.
+    pub mean: f64,
+    /// Sample standard deviation. `None` if the sample has a single value.
+    pub standard_deviation: Option<f64>,
+    /// Student's t confidence interval of the mean. `None` if the sample has a single value.
+    pub confidence_interval: Option<(f64, f64)>,
+}
+
+impl Summary {
+    /// Computes the summary statistics of a sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - Values of the sample.
+    /// * `confidence` - Confidence level (0.0 to 1.0) of the interval.
+    ///
+    /// # Returns
+    ///
+    /// The summary of the sample, or `None` if it is empty.
+    pub fn new(sample: &[f64], confidence: f64) -> Option<Self> {
+        let count = sample.len();
+        if count == 0 {
+            return None;
+        }
+
+        let mean = sample.iter().sum::<f64>() / count as f64;
+        let mut standard_deviation = None;
+        let mut confidence_interval = None;
+        if count > 1 {
+            let variance = sample
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / (count - 1) as f64;
+            let deviation = variance.sqrt();
+            let margin =
+                student_t_quantile(1.0 - confidence, count - 1) * deviation / (count as f64).sqrt();
+            standard_deviation = Some(deviation);
+            confidence_interval = Some((mean - margin, mean + margin));
+        }
+
+        Some(Summary {
+            count,
+            mean,
+            standard_deviation,
+            confidence_interval,
+        })
+    }
+}
+
+/// Estimate of a probability from a number of successes in independent trials.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proportion {
+    /// Number of successes.
+    pub successes: usize,
+    /// Number of trials.
+    pub trials: usize,
+    /// Fraction of trials that were successes.
+    pub estimate: f64,
+    /// Wilson score confidence interval of the probability.
+    pub confidence_interval: (f64, f64),
+}
+
+impl Proportion {
+    /// Estimates a probability with a Wilson score interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `successes` - Number of successes.
+    /// * `trials` - Number of trials. Must be greater than zero.
+    /// * `confidence` - Confidence level (0.0 to 1.0) of the interval.
+    ///
+    /// The bounds are exactly 0.0 without successes and 1.0 without failures.
+    pub fn wilson(successes: usize, trials: usize, confidence: f64) -> Self {
+        let n = trials as f64;
+        let estimate = successes as f64 / n;
+        let z = -normal_quantile((1.0 - confidence) / 2.0);
+        let z2 = z * z;
+        let center = (estimate + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin =
+            z / (1.0 + z2 / n) * (estimate * (1.0 - estimate) / n + z2 / (4.0 * n * n)).sqrt();
+
+        Proportion {
+            successes,
+            trials,
+            estimate,
+            confidence_interval: (
+                if successes == 0 {
+                    0.0
+                } else {
+                    (center - margin).max(0.0)
+                },
+                if successes == trials {
+                    1.0
+                } else {
+                    (center + margin).min(1.0)
+                },
+            ),
+        }
+    }
+}
+
+/// Statistics of the results of repeated executions of the same configuration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResultStatistics {
+    /// Probability that the protocol is aborted.
+    pub abort: Proportion,
+    /// QBER of the runs that were not aborted.
+    pub quantum_bit_error_rate: Option<Summary>,
+    /// Key length of the runs that were not aborted.
+    pub key_length: Option<Summary>,
+    /// Fraction of the key known by Eve in the runs that were not aborted.
+    pub eve_knowledge: Option<Summary>,
+    /// Runtime in microseconds of every run.
+    pub time_us: Summary,
+}
+
+impl ResultStatistics {
+    /// Aggregates the results of repeated executions.
+    ///
+    /// Key metrics are only defined for runs that were not aborted, so they are
+    /// `None` if every run was aborted. Non-finite QBERs (empty keys) are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - Results of every execution. Must not be empty.
+    /// * `confidence` - Confidence level (0.0 to 1.0) of the intervals.
+    pub fn new(results: &[QKDResult], confidence: f64) -> Self {
+        let secure = results
+            .iter()
+            .filter(|result| result.is_considered_secure)
+            .collect::<Vec<&QKDResult>>();
+        let summary = |sample: Vec<f64>| Summary::new(&sample, confidence);
+
+        ResultStatistics {
+            abort: Proportion::wilson(results.len() - secure.len(), results.len(), confidence),
+            quantum_bit_error_rate: summary(
+                secure
+                    .iter()
+                    .filter_map(|result| result.quantum_bit_error_rate)
+                    .filter(|qber| qber.is_finite())
+                    .collect(),
+            ),
+            key_length: summary(
+                secure
+                    .iter()
+                    .filter_map(|result| result.key_length)
+                    .map(|length| length as f64)
+                    .collect(),
+            ),
+            eve_knowledge: summary(
+                secure
+                    .iter()
+                    .map(|result| result.eve_knowledge)
+                    .filter(|knowledge| knowledge.is_finite())
+                    .collect(),
+            ),
+            time_us: summary(
+                results
+                    .iter()
+                    .map(|result| result.elapsed_time.as_secs_f64() * 1e6)
+                    .collect(),
+            )
+            .expect("At least one result is required"),
+        }
+    }
+}
+
+/// Quantile function of the standard normal distribution.
+///
+/// Uses Acklam's rational approximation, with a relative error below 1.2e-9.
+///
+/// # Arguments
+///
+/// * `p` - Probability, strictly between 0.0 and 1.0.
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Two-tailed quantile of Student's t distribution.
+///
+/// Uses Hill's algorithm (Communications of the ACM, algorithm 396).
+///
+/// # Arguments
+///
+/// * `p` - Two-tailed probability, strictly between 0.0 and 1.0.
+/// * `degrees_of_freedom` - Degrees of freedom. Must be greater than zero.
+///
+/// # Returns
+///
+/// The value `t` such that `P(|T| > t) = p`.
+pub fn student_t_quantile(p: f64, degrees_of_freedom: usize) -> f64 {
+    let n = degrees_of_freedom as f64;
+    if degrees_of_freedom == 1 {
+        let angle = p * PI / 2.0;
+        return angle.cos() / angle.sin();
+    }
+    if degrees_of_freedom == 2 {
+        return (2.0 / (p * (2.0 - p)) - 2.0).sqrt();
+    }
+
+    let a = 1.0 / (n - 0.5);
+    let b = 48.0 / (a * a);
+    let mut c = ((20700.0 * a / b - 98.0) * a - 16.0) * a + 96.36;
+    let d = ((94.5 / (b + c) - 3.0) / b + 1.0) * (a * PI / 2.0).sqrt() * n;
+    let mut x = d * p;
+    let mut y = x.powf(2.0 / n);
+    if y > 0.05 + a {
+        x = normal_quantile(0.5 * p);
+        y = x * x;
+        if degrees_of_freedom < 5 {
+            c += 0.3 * (n - 4.5) * (x + 0.6);
+        }
+        c += (((0.05 * d * x - 5.0) * x - 7.0) * x - 2.0) * x + b;
+        y = (((((0.4 * y + 6.3) * y + 36.0) * y + 94.5) / c - y - 3.0) / b + 1.0) * x;
+        y = a * y * y;
+        y = if y > 0.002 {
+            y.exp() - 1.0
+        } else {
+            0.5 * y * y + y
+        };
+    } else {
+        y = ((1.0 / (((n + 6.0) / (n * y) - 0.089 * d - 0.822) * (n + 2.0) * 3.0)
+            + 0.5 / (n + 4.0))
+            * y
+            - 1.0)
+            * (n + 1.0)
+            / (n + 2.0)
+            + 1.0 / y;
+    }
+    (n * y).sqrt()
+}
@@ -0,0 +1,163 @@
+use std::str::FromStr;
+
+/// Maximum number of values produced by a single expression.
+pub const MAX_VALUES: usize = 1_000_000;
+
+/// Values of a numeric parameter, given by a single value, a list or a range expression.
+///
+/// Supported expressions:
+/// - `value`: a single value.
+/// - `start:end:step`: values from `start` to `end` (inclusive) in steps of `step`.
+/// - `linspace(start, end, count)`: `count` evenly spaced values from `start` to `end`.
+/// - `logspace(start, end, count)`: `count` values from `10^start` to `10^end`,
+///   evenly spaced on a logarithmic scale.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep<T>(pub Vec<T>);
+
+impl<T: Copy> Sweep<T> {
+    /// Concatenates the values of several sweeps, in order.
+    pub fn concat(sweeps: &[Sweep<T>]) -> Vec<T> {
+        sweeps
+            .iter()
+            .flat_map(|sweep| sweep.0.iter().copied())
+            .collect()
+    }
+}
+
+impl FromStr for Sweep<f64> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        expand(s).map(Sweep)
+    }
+}
+
+impl FromStr for Sweep<usize> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        expand(s)?
+            .into_iter()
+            .map(|value| {
+                let rounded = value.round();
+                if value >= 0.0 && (value - rounded).abs() <= 1e-9 * rounded.max(1.0) {
+                    Ok(rounded as usize)
+                } else {
+                    Err(format!(
+                        "`{}` yields `{}`, which is not a natural number",
+                        s, value
+                    ))
+                }
+            })
+            .collect::<Result<Vec<usize>, String>>()
+            .map(Sweep)
+    }
+}
+
+/// Expands a numeric expression into its values.
+///
+/// # Arguments
+///
+/// * `expression` - A single value, a `start:end:step` range, or a
+///   `linspace(start, end, count)` or `logspace(start, end, count)` call.
+///
+/// # Returns
+///
+/// The values of the expression, in order, or an error message if it is malformed.
+pub fn expand(expression: &str) -> Result<Vec<f64>, String> {
+    let expression = expression.trim();
+    let invalid = |reason: &str| format!("Invalid expression `{}`: {}", expression, reason);
+
+    if let Some(arguments) = function_arguments(expression, "linspace") {
+        let (start, end, count) = parse_call_arguments(arguments).map_err(|e| invalid(&e))?;
+        return Ok(linspace(start, end, count));
+    }
+    if let Some(arguments) = function_arguments(expression, "logspace") {
+        let (start, end, count) = parse_call_arguments(arguments).map_err(|e| invalid(&e))?;
+        return Ok(linspace(start, end, count)
+            .into_iter()
+            .map(|exponent| round_to_precision(10f64.powf(exponent)))
+            .collect());
+    }
+
+    let parts = expression.split(':').collect::<Vec<&str>>();
+    match parts.as_slice() {
+        [value] => Ok(vec![parse_number(value).map_err(|e| invalid(&e))?]),
+        [start, end, step] => {
+            let start = parse_number(start).map_err(|e| invalid(&e))?;
+            let end = parse_number(end).map_err(|e| invalid(&e))?;
+            let step = parse_number(step).map_err(|e| invalid(&e))?;
+            if step == 0.0 || (end - start) * step < 0.0 {
+                return Err(invalid("the step must be non-zero and go from start to end"));
+            }
+            let count = ((end - start) / step + 1e-9).floor() + 1.0;
+            if count > MAX_VALUES as f64 {
+                return Err(invalid(&format!("more than {} values", MAX_VALUES)));
+            }
+            let count = count as usize;
+            Ok((0..count)
+                .map(|i| round_to_precision(start + i as f64 * step))
+                .collect())
+        }
+        _ => Err(invalid(
+            "expected a number, `start:end:step`, `linspace(start, end, count)` or `logspace(start, end, count)`",
+        )),
+    }
+}
+
+/// Returns the text between the parentheses of a call to `function`, if `expression` is one.
+fn function_arguments<'a>(expression: &'a str, function: &str) -> Option<&'a str> {
+    expression
+        .strip_prefix(function)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Parses the `start, end, count` arguments of `linspace` and `logspace`.
+fn parse_call_arguments(arguments: &str) -> Result<(f64, f64, usize), String> {
+    let arguments = arguments.split(',').collect::<Vec<&str>>();
+    let [start, end, count] = arguments.as_slice() else {
+        return Err("expected three arguments: start, end and count".to_string());
+    };
+    let count = count
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&count| count > 0 && count <= MAX_VALUES)
+        .ok_or_else(|| {
+            format!(
+                "`{}` is not a count between 1 and {}",
+                count.trim(),
+                MAX_VALUES
+            )
+        })?;
+    Ok((parse_number(start)?, parse_number(end)?, count))
+}
+
+/// Parses a finite number.
+fn parse_number(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| format!("`{}` is not a number", value.trim()))
+}
+
+/// Returns `count` evenly spaced values from `start` to `end`, both included.
+fn linspace(start: f64, end: f64, count: usize) -> Vec<f64> {
+    if count == 1 {
+        return vec![start];
+    }
+    let step = (end - start) / (count - 1) as f64;
+    (0..count)
+        .map(|i| round_to_precision(start + i as f64 * step))
+        .collect()
+}
+
+/// Rounds a value to 13 significant digits, removing floating-point noise
+/// such as `0.15000000000000002`.
+fn round_to_precision(value: f64) -> f64 {
+    format!("{:.12e}", value).parse().unwrap_or(value)
+}
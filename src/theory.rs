@@ -0,0 +1,317 @@
+use crate::output::{group_by_configuration, Column, ColumnType, OutputRecord, ResultRecord};
+use crate::physical::Channel;
+use crate::protocol::QKDResult;
+use crate::protocols::ProtocolRegistry;
+use crate::statistics::Proportion;
+use serde_json::{json, Value};
+
+/// Analytic expectations of a protocol under an intercept-resend attack with ideal hardware.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TheoreticalPrediction {
+    /// Expected fraction of detected rounds kept after the public discussion
+    /// (check and key bits).
+    pub sift_rate: f64,
+    /// Expected fraction of sifted bits where Alice and Bob disagree.
+    pub quantum_bit_error_rate: f64,
+    /// Expected fraction of the key known by Eve.
+    pub eve_knowledge: f64,
+}
+
+impl TheoreticalPrediction {
+    /// Returns the probability that the protocol is aborted.
+    ///
+    /// Half of the sifted bits are checked, and the protocol is aborted if the
+    /// fraction of mismatched check bits exceeds the threshold. The number of
+    /// check bits is approximated by its mean.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_qubits` - Number of qubits sent by Alice.
+    /// * `detection_probability` - Probability that a qubit is detected by Bob.
+    /// * `qber_threshold` - Maximum fraction of mismatched check bits tolerated.
+    pub fn abort_probability(
+        &self,
+        number_of_qubits: usize,
+        detection_probability: f64,
+        qber_threshold: f64,
+    ) -> f64 {
+        let check_bits = number_of_qubits as f64 * detection_probability * self.sift_rate / 2.0;
+        let q = self.quantum_bit_error_rate;
+        if qber_threshold == 0.0 {
+            return 1.0 - (1.0 - q).powf(check_bits);
+        }
+        let check_bits = check_bits.round() as usize;
+        let tolerated = (qber_threshold * check_bits as f64).floor() as usize;
+        1.0 - binomial_cdf(tolerated, check_bits, q)
+    }
+}
+
+/// Metrics compared with their theoretical prediction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// Sifted bits per sent qubit, estimated as twice the key length.
+    SiftRate,
+    /// Probability that the protocol is aborted.
+    AbortProbability,
+    /// Fraction of key bits where Alice and Bob disagree.
+    QuantumBitErrorRate,
+    /// Fraction of the key known by Eve.
+    EveKnowledge,
+}
+
+impl Metric {
+    /// Every metric, in the order they are reported.
+    pub const ALL: [Metric; 4] = [
+        Metric::SiftRate,
+        Metric::AbortProbability,
+        Metric::QuantumBitErrorRate,
+        Metric::EveKnowledge,
+    ];
+
+    /// Returns the name of the metric in outputs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::SiftRate => "sift_rate",
+            Metric::AbortProbability => "abort_probability",
+            Metric::QuantumBitErrorRate => "qber",
+            Metric::EveKnowledge => "eve_knowledge",
+        }
+    }
+}
+
+/// Comparison of a simulated metric of a configuration with its theoretical prediction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonRecord {
+    /// Name of the simulated protocol.
+    pub protocol: String,
+    /// Number of qubits sent.
+    pub number_of_qubits: usize,
+    /// Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    pub interception_rate: f64,
+    /// Length in kilometers of the fiber, if a channel was simulated.
+    pub distance_km: Option<f64>,
+    /// Compared metric.
+    pub metric: Metric,
+    /// Value predicted by the analytic model.
+    pub predicted: f64,
+    /// Value observed in the simulation, pooled across repetitions.
+    /// `None` if it is not defined, e.g. the QBER when every run was aborted.
+    pub observed: Option<Proportion>,
+}
+
+impl ComparisonRecord {
+    /// Compares the results of every configuration with the predictions of its protocol.
+    ///
+    /// The counts behind every metric (aborted runs, sifted bits, mismatched key
+    /// bits and key bits known by Eve) are pooled across the repetitions of a
+    /// configuration and estimated with Wilson intervals, so single runs can also
+    /// be compared. Protocols are assumed to be built by the registry with the
+    /// default settings, and the channel to be a fiber of the given distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - Results of every experiment.
+    /// * `registry` - Registry providing the protocol of every record.
+    /// * `confidence` - Confidence level (0.0 to 1.0) of the intervals.
+    ///
+    /// # Returns
+    ///
+    /// One record per metric and configuration, skipping protocols without a
+    /// theoretical prediction.
+    pub fn compare(
+        records: &[ResultRecord],
+        registry: &ProtocolRegistry,
+        confidence: f64,
+    ) -> Vec<ComparisonRecord> {
+        let mut comparisons = Vec::new();
+        for runs in group_by_configuration(records) {
+            let record = runs[0];
+            let Some(prediction) = registry
+                .get(&record.protocol)
+                .and_then(|protocol| protocol.theoretical_prediction(record.interception_rate))
+            else {
+                continue;
+            };
+            let detection_probability = record.distance_km.map_or(1.0, |distance_km| {
+                Channel::fiber(distance_km).transmittance()
+            });
+
+            let accepted = runs
+                .iter()
+                .filter_map(|record| {
+                    let key_length = record.result.key_length?;
+                    Some((record.number_of_qubits, key_length, &record.result))
+                })
+                .collect::<Vec<_>>();
+            let key_bits = accepted.iter().map(|(_, key_length, _)| key_length).sum();
+            let key_fraction = |fraction: fn(&QKDResult) -> f64| {
+                accepted
+                    .iter()
+                    .map(|(_, key_length, result)| {
+                        (fraction(result) * *key_length as f64).round() as usize
+                    })
+                    .sum::<usize>()
+            };
+            let proportion = |successes: usize, trials: usize| {
+                (trials > 0).then(|| Proportion::wilson(successes.min(trials), trials, confidence))
+            };
+
+            for metric in Metric::ALL {
+                let (predicted, observed) = match metric {
+                    Metric::SiftRate => (
+                        detection_probability * prediction.sift_rate,
+                        proportion(
+                            accepted
+                                .iter()
+                                .map(|(_, key_length, _)| 2 * key_length)
+                                .sum(),
+                            accepted.iter().map(|(n, _, _)| n).sum(),
+                        ),
+                    ),
+                    Metric::AbortProbability => (
+                        prediction.abort_probability(
+                            record.number_of_qubits,
+                            detection_probability,
+                            0.0,
+                        ),
+                        proportion(runs.len() - accepted.len(), runs.len()),
+                    ),
+                    Metric::QuantumBitErrorRate => (
+                        prediction.quantum_bit_error_rate,
+                        proportion(
+                            key_fraction(|result| {
+                                result.quantum_bit_error_rate.unwrap_or_default()
+                            }),
+                            key_bits,
+                        ),
+                    ),
+                    Metric::EveKnowledge => (
+                        prediction.eve_knowledge,
+                        proportion(key_fraction(|result| result.eve_knowledge), key_bits),
+                    ),
+                };
+                comparisons.push(ComparisonRecord {
+                    protocol: record.protocol.clone(),
+                    number_of_qubits: record.number_of_qubits,
+                    interception_rate: record.interception_rate,
+                    distance_km: record.distance_km,
+                    metric,
+                    predicted,
+                    observed,
+                });
+            }
+        }
+        comparisons
+    }
+
+    /// Returns `true` if the prediction is outside the confidence interval of the observed value.
+    pub fn is_significant(&self) -> bool {
+        self.observed.is_some_and(|observed| {
+            let (low, high) = observed.confidence_interval;
+            self.predicted < low || self.predicted > high
+        })
+    }
+
+    /// Returns the comparison formatted for the terminal, in the order of
+    /// `THEORY_TABLE_HEADER`.
+    pub fn to_table_row(&self) -> [String; 8] {
+        let (observed, interval) = match self.observed {
+            Some(observed) => (
+                format!("{:.4}", observed.estimate),
+                format!(
+                    "[{:.4}, {:.4}]",
+                    observed.confidence_interval.0, observed.confidence_interval.1
+                ),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        [
+            self.protocol.clone(),
+            self.number_of_qubits.to_string(),
+            self.interception_rate.to_string(),
+            self.distance_km
+                .map_or_else(|| "-".to_string(), |distance_km| distance_km.to_string()),
+            self.metric.name().to_string(),
+            format!("{:.4}", self.predicted),
+            format!("{} {}", observed, interval),
+            if self.is_significant() { "*" } else { "" }.to_string(),
+        ]
+    }
+}
+
+/// Header of the comparison table printed in the terminal.
+pub const THEORY_TABLE_HEADER: [&str; 8] = [
+    "PROTOCOL",
+    "number_of_qubits",
+    "interception_rate",
+    "distance_km",
+    "metric",
+    "predicted",
+    "observed",
+    "deviation",
+];
+
+impl OutputRecord for ComparisonRecord {
+    const COLUMNS: &'static [Column] = &[
+        Column::new("protocol", ColumnType::Text, false),
+        Column::new("number_of_qubits", ColumnType::Integer, false),
+        Column::new("interception_rate", ColumnType::Float, false),
+        Column::new("distance_km", ColumnType::Float, true),
+        Column::new("metric", ColumnType::Text, false),
+        Column::new("predicted", ColumnType::Float, false),
+        Column::new("observed", ColumnType::Float, true),
+        Column::new("ci_low", ColumnType::Float, true),
+        Column::new("ci_high", ColumnType::Float, true),
+        Column::new("trials", ColumnType::Integer, false),
+        Column::new("significant", ColumnType::Boolean, false),
+    ];
+
+    /// Values that are not defined are written as empty fields.
+    fn to_csv_row(&self) -> Vec<String> {
+        let json = self.to_json();
+        Self::COLUMNS
+            .iter()
+            .map(|column| match &json[column.name] {
+                Value::Null => String::new(),
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        let interval = self.observed.map(|observed| observed.confidence_interval);
+        json!({
+            "protocol": self.protocol,
+            "number_of_qubits": self.number_of_qubits,
+            "interception_rate": self.interception_rate,
+            "distance_km": self.distance_km,
+            "metric": self.metric.name(),
+            "predicted": self.predicted,
+            "observed": self.observed.map(|observed| observed.estimate),
+            "ci_low": interval.map(|(low, _)| low),
+            "ci_high": interval.map(|(_, high)| high),
+            "trials": self.observed.map_or(0, |observed| observed.trials),
+            "significant": self.is_significant(),
+        })
+    }
+}
+
+/// Returns the probability that a binomial variable with `trials` trials and
+/// success probability `p` is at most `k`.
+fn binomial_cdf(k: usize, trials: usize, p: f64) -> f64 {
+    if k >= trials || p <= 0.0 {
+        return 1.0;
+    }
+    if p >= 1.0 {
+        return 0.0;
+    }
+    let odds = (p / (1.0 - p)).ln();
+    let mut log_term = trials as f64 * (1.0 - p).ln();
+    let mut cdf = 0.0;
+    for i in 0..=k {
+        cdf += log_term.exp();
+        log_term += ((trials - i) as f64 / (i + 1) as f64).ln() + odds;
+    }
+    cdf.min(1.0)
+}
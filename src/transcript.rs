@@ -0,0 +1,340 @@
+use crate::postprocessing::SiftedKey;
+use crate::protocol::{KeyStatistics, PublicDiscussionResult, QExecutionResult, QKDResult, QKD};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use web_time::Instant;
+
+/// Role assigned to a round during the public discussion phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RoundRole {
+    /// The round was discarded during sifting.
+    Discarded,
+    /// The round was publicly compared to detect eavesdropping.
+    Check,
+    /// The round was kept to build the final key.
+    Key,
+}
+
+impl RoundRole {
+    /// Returns the name used to store the role in a transcript file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundRole::Discarded => "discarded",
+            RoundRole::Check => "check",
+            RoundRole::Key => "key",
+        }
+    }
+}
+
+impl FromStr for RoundRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "discarded" => Ok(RoundRole::Discarded),
+            "check" => Ok(RoundRole::Check),
+            "key" => Ok(RoundRole::Key),
+            _ => Err(format!("`{}` is not a valid round role", s)),
+        }
+    }
+}
+
+/// A single round of a transcript.
+///
+/// Values are stored as produced by the quantum phase, before any
+/// modification done by the sifting strategy.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TranscriptRound {
+    /// Values and bases of Alice, Bob and Eve in this round.
+    pub result: QExecutionResult,
+    /// Role assigned to the round during the public discussion phase.
+    pub role: RoundRole,
+}
+
+/// Full record of a protocol execution.
+///
+/// Contains the configuration needed to repeat the post-processing and
+/// every round of the quantum phase with its sifting decision.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transcript {
+    /// Name of the protocol that generated the transcript.
+    pub protocol: String,
+    /// Probability (0.0 to 1.0) that Eve intercepted a qubit.
+    pub interception_rate: f64,
+    /// Base seed of the simulation.
+    pub seed: u64,
+    /// Every round of the quantum phase, in order.
+    pub rounds: Vec<TranscriptRound>,
+}
+
+/// Column names of the rounds stored in a transcript file.
+const TRANSCRIPT_HEADER: [&str; 9] = [
+    "round",
+    "alice_value",
+    "alice_basis",
+    "bob_value",
+    "bob_basis",
+    "eve_value",
+    "eve_basis",
+    "role",
+    "announcement",
+];
+
+impl Transcript {
+    /// Executes a protocol and records its transcript.
+    ///
+    /// The returned `QKDResult` is the same as the one of
+    /// `QKD::run_with_seed` with the same arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - Name of the protocol, stored in the transcript.
+    /// * `qkd` - Protocol instance to execute.
+    /// * `number_of_qubits` - Number of qubits to use in the protocol.
+    /// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+    /// * `seed` - Base seed of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// The result of the execution and its transcript.
+    pub fn record(
+        protocol: &str,
+        qkd: &QKD,
+        number_of_qubits: usize,
+        interception_rate: f64,
+        seed: u64,
+    ) -> (QKDResult, Transcript) {
+        let initial_time = Instant::now();
+        let results = qkd.simulate(number_of_qubits, interception_rate, seed);
+        let discussion_result = qkd.sift_block(results.clone(), seed, 0);
+        let result = check_and_accumulate(qkd, &discussion_result, initial_time);
+        let rounds = results
+            .into_iter()
+            .zip(roles(&discussion_result))
+            .map(|(result, role)| TranscriptRound { result, role })
+            .collect::<Vec<TranscriptRound>>();
+
+        let transcript = Transcript {
+            protocol: protocol.to_string(),
+            interception_rate,
+            seed,
+            rounds,
+        };
+        (result, transcript)
+    }
+
+    /// Repeats the post-processing of the transcript without regenerating the quantum phase.
+    ///
+    /// The sifting strategy of `qkd` is applied to the recorded rounds with the
+    /// recorded seed, so the original result is reproduced when using the same protocol.
+    ///
+    /// # Arguments
+    ///
+    /// * `qkd` - Protocol instance whose sifting strategy is applied.
+    ///
+    /// # Returns
+    ///
+    /// A `QKDResult` whose elapsed time only accounts for the post-processing, or an
+    /// `InvalidData` error if the sifting strategy does not assign the recorded roles.
+    pub fn replay(&self, qkd: &QKD) -> io::Result<QKDResult> {
+        let initial_time = Instant::now();
+        let discussion_result = self.public_discussion(qkd)?;
+        Ok(check_and_accumulate(qkd, &discussion_result, initial_time))
+    }
+
+    /// Repeats the public discussion of the transcript and returns the sifted key.
+    ///
+    /// # Arguments
+    ///
+    /// * `qkd` - Protocol instance whose sifting strategy is applied.
+    ///
+    /// # Returns
+    ///
+    /// The `SiftedKey` of Alice and Bob, ready for post-processing, or an
+    /// `InvalidData` error if the sifting strategy does not assign the recorded roles.
+    pub fn sifted_key(&self, qkd: &QKD) -> io::Result<SiftedKey> {
+        Ok(SiftedKey::new(&self.public_discussion(qkd)?))
+    }
+
+    /// Repeats the public discussion of the recorded rounds and verifies that
+    /// every round is assigned its recorded role.
+    fn public_discussion(&self, qkd: &QKD) -> io::Result<PublicDiscussionResult> {
+        let results = self
+            .rounds
+            .iter()
+            .map(|round| round.result.clone())
+            .collect::<Vec<QExecutionResult>>();
+
+        let discussion_result = qkd.sift_block(results, self.seed, 0);
+        let roles = roles(&discussion_result);
+        let mismatch = self
+            .rounds
+            .iter()
+            .zip(&roles)
+            .position(|(round, role)| round.role != *role);
+        match mismatch {
+            Some(i) => Err(invalid_data(format!(
+                "Round {} is recorded as `{}`, but the sifting strategy of the protocol \
+                 assigns it the `{}` role",
+                i,
+                self.rounds[i].role.as_str(),
+                roles[i].as_str()
+            ))),
+            None => Ok(discussion_result),
+        }
+    }
+
+    /// Writes the transcript to a CSV file.
+    ///
+    /// The configuration is stored in `#`-prefixed lines before the CSV header.
+    /// Eve's value and basis are left empty in rounds she did not intercept,
+    /// and the announcement in protocols without a relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to create.
+    pub fn write_to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "# protocol: {}", self.protocol)?;
+        writeln!(file, "# interception_rate: {}", self.interception_rate)?;
+        writeln!(file, "# seed: {}", self.seed)?;
+
+        let mut writer = csv::Writer::from_writer(file);
+        writer.write_record(TRANSCRIPT_HEADER)?;
+        for (i, round) in self.rounds.iter().enumerate() {
+            let result = &round.result;
+            writer.write_record([
+                i.to_string(),
+                result.alice_value.to_string(),
+                result.alice_basis.to_string(),
+                result.bob_value.to_string(),
+                result.bob_basis.to_string(),
+                result.eve_value.map_or(String::new(), |v| v.to_string()),
+                result.eve_basis.map_or(String::new(), |b| b.to_string()),
+                round.role.as_str().to_string(),
+                result
+                    .announcement
+                    .map_or(String::new(), |state| state.as_str().to_string()),
+            ])?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a transcript from a CSV file written by `write_to_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to read.
+    ///
+    /// # Returns
+    ///
+    /// The transcript, or an `InvalidData` error if the file is malformed.
+    pub fn read_from_path<P: AsRef<Path>>(path: P) -> io::Result<Transcript> {
+        let content = fs::read_to_string(path)?;
+        let (mut protocol, mut interception_rate, mut seed) = (None, None, None);
+        for line in content.lines().take_while(|line| line.starts_with('#')) {
+            let (key, value) = line[1..]
+                .split_once(':')
+                .ok_or_else(|| invalid_data(format!("Malformed metadata line `{}`", line)))?;
+            let value = value.trim();
+            match key.trim() {
+                "protocol" => protocol = Some(value.to_string()),
+                "interception_rate" => interception_rate = Some(parse_field(value, key)?),
+                "seed" => seed = Some(parse_field(value, key)?),
+                _ => {}
+            }
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_reader(content.as_bytes());
+        let mut rounds = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            if record.len() != TRANSCRIPT_HEADER.len() {
+                return Err(invalid_data(format!(
+                    "Expected {} columns in round {}, found {}",
+                    TRANSCRIPT_HEADER.len(),
+                    rounds.len(),
+                    record.len()
+                )));
+            }
+            let optional = |i: usize| Some(&record[i]).filter(|field| !field.is_empty());
+            let mut result = QExecutionResult::new(
+                parse_field(&record[1], TRANSCRIPT_HEADER[1])?,
+                parse_field(&record[2], TRANSCRIPT_HEADER[2])?,
+                parse_field(&record[3], TRANSCRIPT_HEADER[3])?,
+                parse_field(&record[4], TRANSCRIPT_HEADER[4])?,
+                optional(5)
+                    .map(|field| parse_field(field, TRANSCRIPT_HEADER[5]))
+                    .transpose()?,
+                optional(6)
+                    .map(|field| parse_field(field, TRANSCRIPT_HEADER[6]))
+                    .transpose()?,
+            );
+            result.announcement = optional(8)
+                .map(|field| parse_field(field, TRANSCRIPT_HEADER[8]))
+                .transpose()?;
+            rounds.push(TranscriptRound {
+                result,
+                role: parse_field(&record[7], TRANSCRIPT_HEADER[7])?,
+            });
+        }
+
+        Ok(Transcript {
+            protocol: protocol.ok_or_else(|| invalid_data("Missing `protocol` metadata"))?,
+            interception_rate: interception_rate
+                .ok_or_else(|| invalid_data("Missing `interception_rate` metadata"))?,
+            seed: seed.ok_or_else(|| invalid_data("Missing `seed` metadata"))?,
+            rounds,
+        })
+    }
+}
+
+/// Returns the role assigned to every round in a public discussion.
+fn roles(discussion_result: &PublicDiscussionResult) -> Vec<RoundRole> {
+    let mut roles = vec![RoundRole::Discarded; discussion_result.results.len()];
+    for &i in &discussion_result.indexes_to_check {
+        roles[i] = RoundRole::Check;
+    }
+    for &i in &discussion_result.indexes_to_key {
+        roles[i] = RoundRole::Key;
+    }
+    roles
+}
+
+/// Checks the public values and accumulates the key of a public discussion.
+///
+/// # Returns
+///
+/// The `QKDResult` of the execution started at `initial_time`.
+fn check_and_accumulate(
+    qkd: &QKD,
+    discussion_result: &PublicDiscussionResult,
+    initial_time: Instant,
+) -> QKDResult {
+    let is_considered_secure = qkd.check_public_values(discussion_result);
+    let mut statistics = KeyStatistics::default();
+    if is_considered_secure {
+        statistics.add_key(discussion_result);
+    }
+    statistics.into_result(is_considered_secure, initial_time.elapsed())
+}
+
+/// Parses a field of a transcript file, reporting its name on failure.
+fn parse_field<T: FromStr>(value: &str, name: &str) -> io::Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid_data(format!("Invalid value `{}` for `{}`", value, name.trim())))
+}
+
+/// Builds an `InvalidData` I/O error with the given message.
+fn invalid_data<M: Into<String>>(message: M) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -0,0 +1,243 @@
+use crate::monitor::{histogram, ConfigurationProgress, SweepProgress};
+use crate::output::ResultRecord;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{BarChart, Block, Gauge, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// Number of bins of the histograms.
+const HISTOGRAM_BINS: usize = 10;
+
+/// Time waited for a key press between two refreshes of the dashboard.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Shows the live progress of a sweep in the terminal until every run is received.
+///
+/// Pressing `q`, `Esc` or `Ctrl+C` sets `cancelled`, so the simulation should
+/// stop starting new runs and close the channel once the running ones finish.
+///
+/// # Arguments
+///
+/// * `progress` - Progress of the sweep before any run is completed.
+/// * `receiver` - Channel receiving every completed run with the index of its configuration.
+/// * `cancelled` - Flag set when the user cancels the sweep.
+pub fn run_dashboard(
+    progress: SweepProgress,
+    receiver: Receiver<(usize, ResultRecord)>,
+    cancelled: &AtomicBool,
+) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let outcome = Dashboard {
+        progress,
+        started: Instant::now(),
+        table: TableState::default().with_selected(0),
+    }
+    .run(&mut terminal, receiver, cancelled);
+    ratatui::restore();
+    outcome
+}
+
+/// State of the dashboard between refreshes.
+struct Dashboard {
+    progress: SweepProgress,
+    started: Instant,
+    table: TableState,
+}
+
+impl Dashboard {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        receiver: Receiver<(usize, ResultRecord)>,
+        cancelled: &AtomicBool,
+    ) -> io::Result<()> {
+        loop {
+            let finished = loop {
+                match receiver.try_recv() {
+                    Ok((configuration, record)) => self.progress.record(configuration, &record),
+                    Err(TryRecvError::Empty) => break false,
+                    Err(TryRecvError::Disconnected) => break true,
+                }
+            };
+            if finished {
+                return Ok(());
+            }
+            let is_cancelled = cancelled.load(Ordering::Relaxed);
+            terminal.draw(|frame| self.draw(frame, is_cancelled))?;
+
+            if !event::poll(REFRESH_INTERVAL)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => cancelled.store(true, Ordering::Relaxed),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    cancelled.store(true, Ordering::Relaxed)
+                }
+                KeyCode::Up => self.table.select_previous(),
+                KeyCode::Down => {
+                    let last = self.progress.configurations.len().saturating_sub(1);
+                    let selected = self.table.selected().unwrap_or(0);
+                    self.table.select(Some((selected + 1).min(last)));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, is_cancelled: bool) {
+        let [gauge, table, charts, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(12),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.draw_gauge(frame, gauge);
+        self.draw_table(frame, table);
+        let selected = self.table.selected().unwrap_or(0);
+        if let Some(progress) = self.progress.configurations.get(selected) {
+            draw_histograms(frame, charts, progress);
+        }
+        let help = if is_cancelled {
+            "Cancelling: waiting for the running experiments to finish..."
+        } else {
+            "↑/↓ select configuration · q cancel and keep the results so far"
+        };
+        frame.render_widget(Paragraph::new(help), footer);
+    }
+
+    fn draw_gauge(&self, frame: &mut Frame, area: Rect) {
+        let (completed, total) = (self.progress.completed_runs(), self.progress.total_runs());
+        let elapsed = self.started.elapsed();
+        let time_left = self
+            .progress
+            .estimated_time_left(elapsed)
+            .map_or_else(|| "-".to_string(), format_duration);
+        let label = format!(
+            "{}/{} runs · {} aborted · elapsed {} · ETA {}",
+            completed,
+            total,
+            self.progress.aborted_runs(),
+            format_duration(elapsed),
+            time_left
+        );
+        let ratio = if total == 0 {
+            1.0
+        } else {
+            completed as f64 / total as f64
+        };
+        let gauge = Gauge::default()
+            .block(Block::bordered().title("Sweep"))
+            .ratio(ratio)
+            .label(label);
+        frame.render_widget(gauge, area);
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let format_mean = |mean: Option<f64>, precision: usize| {
+            mean.map_or_else(|| "-".to_string(), |mean| format!("{:.*}", precision, mean))
+        };
+        let rows = self.progress.configurations.iter().map(|progress| {
+            let configuration = &progress.configuration;
+            Row::new([
+                configuration.label(),
+                configuration.number_of_qubits.to_string(),
+                configuration.interception_rate.to_string(),
+                configuration
+                    .distance_km
+                    .map_or_else(|| "-".to_string(), |distance_km| distance_km.to_string()),
+                format!("{}/{}", progress.completed_runs, configuration.runs),
+                progress.aborted_runs.to_string(),
+                format_mean(progress.mean_quantum_bit_error_rate(), 4),
+                format_mean(progress.mean_key_length(), 1),
+            ])
+            .style(if progress.is_finished() {
+                Style::default().add_modifier(Modifier::DIM)
+            } else {
+                Style::default()
+            })
+        });
+        let table = Table::new(rows, [Constraint::Fill(1); 8])
+            .header(
+                Row::new([
+                    "protocol",
+                    "qubits",
+                    "interception",
+                    "distance_km",
+                    "runs",
+                    "aborted",
+                    "QBER",
+                    "key_length",
+                ])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Block::bordered().title("Configurations"));
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+}
+
+/// Draws the QBER and key-length histograms of the accepted runs of a configuration.
+fn draw_histograms(frame: &mut Frame, area: Rect, progress: &ConfigurationProgress) {
+    let [qber, key_length] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+    let key_lengths = progress
+        .key_lengths
+        .iter()
+        .map(|&key_length| key_length as f64)
+        .collect::<Vec<f64>>();
+    let charts = [
+        (qber, "QBER", &progress.quantum_bit_error_rates, 3),
+        (key_length, "Key length", &key_lengths, 0),
+    ];
+
+    for (area, title, values, precision) in charts {
+        let bins = histogram(values, HISTOGRAM_BINS)
+            .into_iter()
+            .map(|(edge, count)| (format!("{:.*}", precision, edge), count as u64))
+            .collect::<Vec<(String, u64)>>();
+        let data = bins
+            .iter()
+            .map(|(label, count)| (label.as_str(), *count))
+            .collect::<Vec<(&str, u64)>>();
+        let bar_width = bins
+            .iter()
+            .map(|(label, _)| label.len() as u16)
+            .max()
+            .unwrap_or(1)
+            .max(3);
+        let chart = BarChart::default()
+            .block(Block::bordered().title(Line::from(format!(
+                "{} of the accepted runs of {}",
+                title,
+                progress.configuration.label()
+            ))))
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1);
+        frame.render_widget(chart, area);
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
@@ -1,9 +1,15 @@
+use crate::utils::rand_float;
 use num_complex::Complex64;
-use std::ops::{Add, Div};
+use rand::Rng;
+use std::ops::{Add, Div, Mul};
 
 // TODO: Use a standard library for matrices.
 /// Represents a 2x2 matrix of complex numbers.
-#[derive(Clone, Copy)]
+///
+/// With the `serde` feature, it is serialized as a row-major 2x2 array of
+/// `[re, im]` pairs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComplexMatrix(pub [[Complex64; 2]; 2]);
 
 impl ComplexMatrix {
@@ -26,8 +32,33 @@ impl ComplexMatrix {
             [-c * inv_det, a * inv_det],
         ]))
     }
+
+    /// Returns the adjoint (conjugate transpose) of the matrix, which is its
+    /// inverse if the matrix is unitary.
+    pub fn adjoint(&self) -> ComplexMatrix {
+        ComplexMatrix([
+            [self.0[0][0].conj(), self.0[1][0].conj()],
+            [self.0[0][1].conj(), self.0[1][1].conj()],
+        ])
+    }
+
+    /// Checks whether the matrix is unitary, i.e. whether its product with its
+    /// adjoint is the identity up to `UNITARY_TOLERANCE` in every entry.
+    pub fn is_unitary(&self) -> bool {
+        let product = *self * self.adjoint();
+        (0..2).all(|row| {
+            (0..2).all(|column| {
+                let identity = if row == column { 1.0 } else { 0.0 };
+                (product.0[row][column] - identity).norm() <= UNITARY_TOLERANCE
+            })
+        })
+    }
 }
 
+/// Maximum deviation from the identity accepted by `ComplexMatrix::is_unitary`,
+/// which absorbs the rounding errors of matrices built from angles.
+pub const UNITARY_TOLERANCE: f64 = 1e-9;
+
 /// Implements matrix addition for `ComplexMatrix`.
 impl Add<ComplexMatrix> for ComplexMatrix {
     type Output = Self;
@@ -39,10 +70,23 @@ impl Add<ComplexMatrix> for ComplexMatrix {
     }
 }
 
+/// Implements matrix multiplication for `ComplexMatrix`.
+///
+/// `a * b` applies `b` first and then `a` when used as a transformation.
+impl Mul<ComplexMatrix> for ComplexMatrix {
+    type Output = Self;
+    fn mul(self, matrix: Self) -> Self::Output {
+        let entry = |row: usize, column: usize| {
+            self.0[row][0] * matrix.0[0][column] + self.0[row][1] * matrix.0[1][column]
+        };
+        ComplexMatrix([[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]])
+    }
+}
+
 /// Allows conversion from a 2x2 array of `Complex64` to `ComplexMatrix`.
-impl Into<ComplexMatrix> for [[Complex64; 2]; 2] {
-    fn into(self) -> ComplexMatrix {
-        ComplexMatrix(self)
+impl From<[[Complex64; 2]; 2]> for ComplexMatrix {
+    fn from(matrix: [[Complex64; 2]; 2]) -> Self {
+        ComplexMatrix(matrix)
     }
 }
 
@@ -50,7 +94,6 @@ impl Into<ComplexMatrix> for [[Complex64; 2]; 2] {
 impl Div<f64> for ComplexMatrix {
     type Output = Self;
     fn div(self, divisor: f64) -> Self::Output {
-        let divisor = divisor as f64;
         ComplexMatrix([
             [self.0[0][0] / divisor, self.0[0][1] / divisor],
             [self.0[1][0] / divisor, self.0[1][1] / divisor],
@@ -59,6 +102,7 @@ impl Div<f64> for ComplexMatrix {
 }
 
 /// Represents a qubit with a quantum state as a linear combination of |0⟩ and |1⟩.
+#[derive(Clone, Debug)]
 pub struct Qubit {
     state: (Complex64, Complex64),
 }
@@ -71,6 +115,15 @@ impl Qubit {
         }
     }
 
+    /// Creates a qubit in the state `zero_coef`|0⟩ + `one_coef`|1⟩.
+    ///
+    /// The coefficients are expected to be normalized.
+    pub fn from_coefs(zero_coef: Complex64, one_coef: Complex64) -> Self {
+        Qubit {
+            state: (zero_coef, one_coef),
+        }
+    }
+
     /// Resets the qubit to the |0⟩ state.
     pub fn reset(&mut self) {
         *self = Qubit::new();
@@ -94,3 +147,222 @@ impl Qubit {
         self.state.1
     }
 }
+
+impl Default for Qubit {
+    fn default() -> Self {
+        Qubit::new()
+    }
+}
+
+/// Represents the state vector of a register of N qubits.
+///
+/// Amplitudes are indexed by the computational basis states |q₀q₁…qₙ₋₁⟩, with
+/// qubit 0 as the most significant bit, so that `a.kron(&b)` places the qubits
+/// of `a` before those of `b`. Single-qubit protocols can keep using `Qubit`,
+/// which converts to and from one-qubit registers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Register {
+    amplitudes: Vec<Complex64>,
+}
+
+impl Register {
+    /// Creates a register of `number_of_qubits` qubits in the |0…0⟩ state.
+    pub fn new(number_of_qubits: usize) -> Self {
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); 1 << number_of_qubits];
+        amplitudes[0] = Complex64::new(1.0, 0.0);
+        Register { amplitudes }
+    }
+
+    /// Creates a register from the amplitudes of its computational basis states.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitudes` - Amplitude of every basis state, normalized by this function.
+    ///
+    /// # Returns
+    ///
+    /// The register, or `None` if the number of amplitudes is not a power of
+    /// two or all of them are zero.
+    pub fn from_amplitudes(amplitudes: Vec<Complex64>) -> Option<Self> {
+        let norm = amplitudes
+            .iter()
+            .map(Complex64::norm_sqr)
+            .sum::<f64>()
+            .sqrt();
+        if !amplitudes.len().is_power_of_two() || norm == 0.0 || !norm.is_finite() {
+            return None;
+        }
+        Some(Register {
+            amplitudes: amplitudes
+                .into_iter()
+                .map(|amplitude| amplitude / norm)
+                .collect(),
+        })
+    }
+
+    /// Creates a register in the product state of the given qubits, in order.
+    pub fn from_qubits(qubits: &[Qubit]) -> Self {
+        qubits.iter().fold(Register::new(0), |register, qubit| {
+            register.kron(&Register::from(qubit.clone()))
+        })
+    }
+
+    /// Returns the number of qubits of the register.
+    pub fn number_of_qubits(&self) -> usize {
+        self.amplitudes.len().trailing_zeros() as usize
+    }
+
+    /// Returns the amplitudes of the computational basis states.
+    pub fn amplitudes(&self) -> &[Complex64] {
+        &self.amplitudes
+    }
+
+    /// Computes the Kronecker (tensor) product of two registers.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Register whose qubits follow the qubits of `self`.
+    ///
+    /// # Returns
+    ///
+    /// A register of `self.number_of_qubits() + other.number_of_qubits()` qubits.
+    pub fn kron(&self, other: &Register) -> Register {
+        Register {
+            amplitudes: self
+                .amplitudes
+                .iter()
+                .flat_map(|a| other.amplitudes.iter().map(move |b| a * b))
+                .collect(),
+        }
+    }
+
+    /// Applies a single-qubit transformation (unitary matrix) to a qubit.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Index of the qubit.
+    /// * `matrix` - Transformation applied as in `Qubit::apply_transformation`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` is not a qubit of the register.
+    pub fn apply_transformation(&mut self, target: usize, matrix: &ComplexMatrix) {
+        self.apply_controlled(&[], target, matrix);
+    }
+
+    /// Applies a single-qubit transformation to a qubit, conditioned on a set
+    /// of control qubits being |1⟩, e.g. a CNOT with `X` and one control.
+    ///
+    /// # Arguments
+    ///
+    /// * `controls` - Indices of the control qubits.
+    /// * `target` - Index of the qubit transformed.
+    /// * `matrix` - Transformation applied when every control is |1⟩.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a qubit is not part of the register or `target` is also a control.
+    pub fn apply_controlled(&mut self, controls: &[usize], target: usize, matrix: &ComplexMatrix) {
+        assert!(
+            !controls.contains(&target),
+            "Qubit {} cannot be both control and target",
+            target
+        );
+        let target_mask = self.mask(target);
+        let control_mask = controls
+            .iter()
+            .fold(0, |mask, &control| mask | self.mask(control));
+
+        for zero in 0..self.amplitudes.len() {
+            if zero & target_mask != 0 || zero & control_mask != control_mask {
+                continue;
+            }
+            let one = zero | target_mask;
+            let (a, b) = (self.amplitudes[zero], self.amplitudes[one]);
+            self.amplitudes[zero] = a * matrix.0[0][0] + b * matrix.0[0][1];
+            self.amplitudes[one] = a * matrix.0[1][0] + b * matrix.0[1][1];
+        }
+    }
+
+    /// Returns the probability of measuring a qubit as |1⟩.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `qubit` is not part of the register.
+    pub fn one_probability(&self, qubit: usize) -> f64 {
+        let mask = self.mask(qubit);
+        self.amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| index & mask != 0)
+            .map(|(_, amplitude)| amplitude.norm_sqr())
+            .sum()
+    }
+
+    /// Measures a qubit in the computational basis.
+    ///
+    /// The state collapses to the outcome, so the rest of the register keeps
+    /// the correlations with the measured qubit.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit` - Index of the qubit.
+    /// * `rng` - Random number generator used to sample the outcome.
+    ///
+    /// # Returns
+    ///
+    /// The classical bit value obtained (false for |0⟩, true for |1⟩).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `qubit` is not part of the register.
+    pub fn measure<R: Rng + ?Sized>(&mut self, qubit: usize, rng: &mut R) -> bool {
+        let one_probability = self.one_probability(qubit);
+        let outcome = rand_float(rng) < one_probability;
+        let probability = if outcome {
+            one_probability
+        } else {
+            1.0 - one_probability
+        };
+        let mask = self.mask(qubit);
+        let norm = probability.sqrt();
+        for (index, amplitude) in self.amplitudes.iter_mut().enumerate() {
+            *amplitude = if (index & mask != 0) == outcome {
+                *amplitude / norm
+            } else {
+                Complex64::new(0.0, 0.0)
+            };
+        }
+        outcome
+    }
+
+    /// Returns the state of a one-qubit register as a `Qubit`, or `None` if
+    /// the register has a different number of qubits.
+    pub fn qubit(&self) -> Option<Qubit> {
+        match self.amplitudes[..] {
+            [zero_coef, one_coef] => Some(Qubit::from_coefs(zero_coef, one_coef)),
+            _ => None,
+        }
+    }
+
+    /// Returns the bit of the amplitude indices that corresponds to a qubit.
+    fn mask(&self, qubit: usize) -> usize {
+        let number_of_qubits = self.number_of_qubits();
+        assert!(
+            qubit < number_of_qubits,
+            "Qubit {} is out of range for a register of {} qubits",
+            qubit,
+            number_of_qubits
+        );
+        1 << (number_of_qubits - 1 - qubit)
+    }
+}
+
+/// Allows conversion from a `Qubit` to a one-qubit `Register`.
+impl From<Qubit> for Register {
+    fn from(qubit: Qubit) -> Self {
+        Register {
+            amplitudes: vec![qubit.get_zero_coef(), qubit.get_one_coef()],
+        }
+    }
+}
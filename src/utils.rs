@@ -1,45 +1,84 @@
 use crate::types::ComplexMatrix;
 use num_complex::Complex64;
 use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::{rng, Rng};
-use std::f64::consts::SQRT_2;
+use rand::{Rng, RngCore, SeedableRng};
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+use std::sync::Mutex;
 
-/// Randomly selects an element from a vector.
+/// Randomly selects an element from a slice.
 ///
 /// # Arguments
 ///
-/// * `vec` - A non-empty vector of elements to choose from.
+/// * `slice` - A non-empty slice of elements to choose from.
+/// * `rng` - Random number generator used for the selection.
 ///
 /// # Returns
 ///
-/// A randomly selected element from the vector.
+/// A randomly selected element from the slice.
 ///
 /// # Panics
 ///
-/// Panics if the input vector is empty.
-pub fn rand_choose<T: Clone>(vec: Vec<T>) -> T {
-    let mut rng = rng();
-    vec.choose(&mut rng).cloned().expect("Vec cannot be empty")
+/// Panics if the input slice is empty.
+pub fn rand_choose<T: Clone, R: Rng + ?Sized>(slice: &[T], rng: &mut R) -> T {
+    slice.choose(rng).cloned().expect("Slice cannot be empty")
+}
+
+/// Randomly selects an index in the range [0, len).
+///
+/// # Arguments
+///
+/// * `len` - Number of elements to choose from. Must be greater than zero.
+/// * `rng` - Random number generator used for the selection.
+///
+/// # Returns
+///
+/// A uniformly distributed index.
+pub fn rand_index<R: Rng + ?Sized>(len: usize, rng: &mut R) -> usize {
+    rng.random_range(0..len)
 }
 
 /// Generates a random boolean value.
 ///
+/// # Arguments
+///
+/// * `rng` - Random number generator used for the draw.
+///
 /// # Returns
 ///
 /// `true` or `false` with equal probability (50% each).
-pub fn rand_bool() -> bool {
-    let mut rng = rng();
-    *[true, false].choose(&mut rng).unwrap()
+pub fn rand_bool<R: Rng + ?Sized>(rng: &mut R) -> bool {
+    rng.random()
 }
 
 /// Generates a random floating-point number in the range [0, 1).
 ///
+/// # Arguments
+///
+/// * `rng` - Random number generator used for the draw.
+///
 /// # Returns
 ///
 /// A random `f64` value uniformly distributed in the interval [0, 1).
-pub fn rand_float() -> f64 {
-    rng().random()
+pub fn rand_float<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    rng.random()
+}
+
+/// Generates a random number from the standard normal distribution.
+///
+/// Uses the Box-Muller transform, drawing two uniform numbers per sample.
+///
+/// # Arguments
+///
+/// * `rng` - Random number generator used for the draw.
+///
+/// # Returns
+///
+/// A random `f64` value with mean 0 and variance 1.
+pub fn rand_gaussian<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let radius = (-2.0 * (1.0 - rand_float(rng)).ln()).sqrt();
+    radius * (std::f64::consts::TAU * rand_float(rng)).cos()
 }
 
 /// Randomly shuffles a vector and splits it into two halves.
@@ -47,22 +86,127 @@ pub fn rand_float() -> f64 {
 /// # Arguments
 ///
 /// * `vector` - The vector to shuffle and split.
+/// * `rng` - Random number generator used for the shuffle.
 ///
 /// # Returns
 ///
 /// A tuple containing two new vectors:
 /// - The first half of the shuffled vector.
 /// - The second half of the shuffled vector.
-pub fn shuffle_and_split<T>(mut vector: Vec<T>) -> (Vec<T>, Vec<T>)
-where
-    T: Clone,
-{
-    let mut rng = rng();
-    vector.shuffle(&mut rng);
-    let half = vector.len() / 2;
-    let first_half = vector[..half].to_vec();
-    let second_half = vector[half..].to_vec();
-    (first_half, second_half)
+pub fn shuffle_and_split<T, R: Rng + ?Sized>(mut vector: Vec<T>, rng: &mut R) -> (Vec<T>, Vec<T>) {
+    vector.shuffle(rng);
+    let second_half = vector.split_off(vector.len() / 2);
+    (vector, second_half)
+}
+
+/// Derives an independent seed for a given stream from a base seed.
+///
+/// Uses the SplitMix64 finalizer, so that consecutive stream numbers
+/// produce uncorrelated seeds.
+///
+/// # Arguments
+///
+/// * `seed` - Base seed of the simulation.
+/// * `stream` - Identifier of the stream (e.g. a chunk or experiment index).
+///
+/// # Returns
+///
+/// A seed for the given stream.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Creates a deterministic random number generator for a given stream.
+///
+/// # Arguments
+///
+/// * `seed` - Base seed of the simulation.
+/// * `stream` - Identifier of the stream.
+///
+/// # Returns
+///
+/// A `StdRng` seeded from `derive_seed(seed, stream)`.
+pub fn seeded_rng(seed: u64, stream: u64) -> StdRng {
+    StdRng::seed_from_u64(derive_seed(seed, stream))
+}
+
+/// Generator replacing the default source of `random_seed`, if any.
+static SEED_SOURCE: Mutex<Option<Box<dyn RngCore + Send>>> = Mutex::new(None);
+
+/// Generates a random seed for simulations run without an explicit seed.
+///
+/// Seeds are drawn from the generator installed with `set_seed_source`. By
+/// default, they come from the operating system entropy source, or from
+/// `Math.random()` on `wasm32` targets with the `wasm` feature.
+///
+/// # Returns
+///
+/// A random `u64` suitable as the base seed of a simulation.
+///
+/// # Panics
+///
+/// Panics on `wasm32` targets without the `wasm` feature if no source was set.
+pub fn random_seed() -> u64 {
+    match SEED_SOURCE.lock() {
+        Ok(mut source) => match source.as_mut() {
+            Some(rng) => rng.next_u64(),
+            None => default_random_seed(),
+        },
+        Err(_) => default_random_seed(),
+    }
+}
+
+/// Replaces the source of the seeds returned by `random_seed`.
+///
+/// # Arguments
+///
+/// * `rng` - Generator of the seeds, or `None` to restore the default source.
+pub fn set_seed_source(rng: Option<Box<dyn RngCore + Send>>) {
+    let mut source = SEED_SOURCE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *source = rng;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_random_seed() -> u64 {
+    rand::random()
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+fn default_random_seed() -> u64 {
+    crate::wasm::math_random_seed()
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+fn default_random_seed() -> u64 {
+    panic!(
+        "No entropy source available: call `set_seed_source` before running unseeded simulations"
+    )
+}
+
+/// Serialization of `Duration` values as an integer number of nanoseconds.
+///
+/// Used with `#[serde(with = "crate::utils::serde_duration_nanos")]`.
+#[cfg(feature = "serde")]
+pub mod serde_duration_nanos {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    /// Serializes a `Duration` as nanoseconds, saturating at `u64::MAX`.
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        u64::try_from(duration.as_nanos())
+            .unwrap_or(u64::MAX)
+            .serialize(serializer)
+    }
+
+    /// Deserializes a `Duration` from nanoseconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
 }
 
 /// Identity matrix (I) for quantum operations.
@@ -137,3 +281,170 @@ pub const H_Y: ComplexMatrix = ComplexMatrix([
         Complex64::new(0.0, -1.0 / SQRT_2),
     ],
 ]);
+
+/// Pauli-Y matrix (Y) for quantum operations.
+///
+/// Mathematically equivalent to:
+/// ```text
+/// | 0  -i |
+/// | i   0 |
+/// ```
+/// Transforms |0⟩ to i|1⟩ and |1⟩ to -i|0⟩.
+pub const Y: ComplexMatrix = ComplexMatrix([
+    [Complex64::new(0.0, 0.0), Complex64::new(0.0, -1.0)],
+    [Complex64::new(0.0, 1.0), Complex64::new(0.0, 0.0)],
+]);
+
+/// Pauli-Z matrix (Z) for quantum operations.
+///
+/// Represents the phase-flip gate.
+/// Mathematically equivalent to:
+/// ```text
+/// | 1   0 |
+/// | 0  -1 |
+/// ```
+/// Transforms |+⟩ to |-⟩ and |-⟩ to |+⟩.
+pub const Z: ComplexMatrix = ComplexMatrix([
+    [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+    [Complex64::new(0.0, 0.0), Complex64::new(-1.0, 0.0)],
+]);
+
+/// Phase matrix (S) for quantum operations.
+///
+/// Represents a quarter turn around the Z axis of the Bloch sphere, so that
+/// S² = Z. Mathematically equivalent to:
+/// ```text
+/// | 1  0 |
+/// | 0  i |
+/// ```
+/// Transforms |+⟩ to |+i⟩.
+pub const S: ComplexMatrix = ComplexMatrix([
+    [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+    [Complex64::new(0.0, 0.0), Complex64::new(0.0, 1.0)],
+]);
+
+/// π/8 matrix (T) for quantum operations.
+///
+/// Represents an eighth of a turn around the Z axis of the Bloch sphere, so
+/// that T² = S. Mathematically equivalent to:
+/// ```text
+/// | 1  0        |
+/// | 0  e^(iπ/4) |
+/// ```
+pub const T: ComplexMatrix = ComplexMatrix([
+    [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+    [
+        Complex64::new(0.0, 0.0),
+        Complex64::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    ],
+]);
+
+/// Rotation around the X axis of the Bloch sphere.
+///
+/// # Arguments
+///
+/// * `theta` - Angle of the rotation in radians.
+///
+/// # Returns
+///
+/// The matrix
+/// ```text
+/// |  cos(θ/2)    -i sin(θ/2) |
+/// | -i sin(θ/2)   cos(θ/2)   |
+/// ```
+pub fn rx(theta: f64) -> ComplexMatrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    ComplexMatrix([
+        [Complex64::new(cos, 0.0), Complex64::new(0.0, -sin)],
+        [Complex64::new(0.0, -sin), Complex64::new(cos, 0.0)],
+    ])
+}
+
+/// Rotation around the Y axis of the Bloch sphere.
+///
+/// # Arguments
+///
+/// * `theta` - Angle of the rotation in radians.
+///
+/// # Returns
+///
+/// The matrix
+/// ```text
+/// | cos(θ/2)  -sin(θ/2) |
+/// | sin(θ/2)   cos(θ/2) |
+/// ```
+pub fn ry(theta: f64) -> ComplexMatrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    ComplexMatrix([
+        [Complex64::new(cos, 0.0), Complex64::new(-sin, 0.0)],
+        [Complex64::new(sin, 0.0), Complex64::new(cos, 0.0)],
+    ])
+}
+
+/// Rotation around the Z axis of the Bloch sphere.
+///
+/// # Arguments
+///
+/// * `theta` - Angle of the rotation in radians.
+///
+/// # Returns
+///
+/// The matrix
+/// ```text
+/// | e^(-iθ/2)  0        |
+/// | 0          e^(iθ/2) |
+/// ```
+pub fn rz(theta: f64) -> ComplexMatrix {
+    ComplexMatrix([
+        [
+            Complex64::from_polar(1.0, -theta / 2.0),
+            Complex64::new(0.0, 0.0),
+        ],
+        [
+            Complex64::new(0.0, 0.0),
+            Complex64::from_polar(1.0, theta / 2.0),
+        ],
+    ])
+}
+
+/// Basis used by a sender to prepare the states along a Bloch-sphere axis.
+///
+/// The basis maps |0⟩ to |ψ⟩ = cos(θ/2)|0⟩ + e^(iφ) sin(θ/2)|1⟩ and |1⟩ to the
+/// orthogonal state on the opposite side of the sphere, so that
+/// `preparation_basis(PI / 2.0, 0.0)` is `H` and
+/// `preparation_basis(PI / 2.0, PI / 2.0)` is `H_Y`.
+///
+/// # Arguments
+///
+/// * `theta` - Polar angle of the axis in radians, from |0⟩.
+/// * `phi` - Azimuthal angle of the axis in radians, from |+⟩ towards |+i⟩.
+///
+/// # Returns
+///
+/// The unitary matrix whose columns are the two states of the basis.
+pub fn preparation_basis(theta: f64, phi: f64) -> ComplexMatrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    let phase = Complex64::from_polar(1.0, phi);
+    ComplexMatrix([
+        [Complex64::new(cos, 0.0), Complex64::new(sin, 0.0)],
+        [phase * sin, -phase * cos],
+    ])
+}
+
+/// Basis used by a receiver to measure along a Bloch-sphere axis.
+///
+/// Measuring with this basis yields `false` for |ψ⟩ and `true` for the
+/// orthogonal state of `preparation_basis(theta, phi)`, of which it is the
+/// adjoint.
+///
+/// # Arguments
+///
+/// * `theta` - Polar angle of the axis in radians, from |0⟩.
+/// * `phi` - Azimuthal angle of the axis in radians, from |+⟩ towards |+i⟩.
+///
+/// # Returns
+///
+/// The unitary matrix applied to a qubit before its measurement.
+pub fn measurement_basis(theta: f64, phi: f64) -> ComplexMatrix {
+    preparation_basis(theta, phi).adjoint()
+}
@@ -0,0 +1,124 @@
+use crate::config::ExperimentConfig;
+use crate::protocol::QKDResult;
+use crate::protocols::ProtocolRegistry;
+use crate::transcript::Transcript;
+use crate::utils::{random_seed, set_seed_source};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Largest seed represented exactly by a JavaScript number (`Number.MAX_SAFE_INTEGER`).
+pub const MAX_SAFE_SEED: u64 = (1 << 53) - 1;
+
+/// Outcome of `runExperiment`.
+#[derive(Serialize)]
+struct ExperimentRun<'a> {
+    seed: u64,
+    result: &'a QKDResult,
+}
+
+/// Outcome of `recordTranscript`.
+#[derive(Serialize)]
+struct TranscriptRun<'a> {
+    result: &'a QKDResult,
+    transcript: &'a Transcript,
+}
+
+/// Returns the names of the registered protocols, sorted.
+#[wasm_bindgen]
+pub fn protocols() -> Vec<String> {
+    ProtocolRegistry::default()
+        .names()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Runs an experiment described by an object with the fields of an
+/// `ExperimentConfig`, e.g. `{ protocol: "BB84", number_of_qubits: 1000 }`.
+///
+/// # Arguments
+///
+/// * `experiment` - Experiment to run. Its seed is random if omitted.
+///
+/// # Returns
+///
+/// An object `{ seed, result }` with the seed used, which repeats the run, and
+/// the `QKDResult` with the keys of the JSON output.
+#[wasm_bindgen(js_name = runExperiment)]
+pub fn run_experiment(experiment: JsValue) -> Result<JsValue, JsError> {
+    let experiment = experiment_config(experiment)?;
+    let result = experiment
+        .run(&ProtocolRegistry::default())
+        .map_err(|message| JsError::new(&message))?;
+    to_js(&ExperimentRun {
+        seed: experiment.seed.unwrap_or_default(),
+        result: &result,
+    })
+}
+
+/// Runs an experiment and records its per-round transcript.
+///
+/// # Arguments
+///
+/// * `experiment` - Experiment to run, as in `runExperiment`.
+///
+/// # Returns
+///
+/// An object `{ result, transcript }` whose transcript holds the seed and an
+/// array of `rounds`, each with the values and bases of Alice, Bob and Eve and
+/// the `role` of the round (`"discarded"`, `"check"` or `"key"`).
+#[wasm_bindgen(js_name = recordTranscript)]
+pub fn record_transcript(experiment: JsValue) -> Result<JsValue, JsError> {
+    let experiment = experiment_config(experiment)?;
+    let (result, transcript) = experiment
+        .record(&ProtocolRegistry::default())
+        .map_err(|message| JsError::new(&message))?;
+    to_js(&TranscriptRun {
+        result: &result,
+        transcript: &transcript,
+    })
+}
+
+/// Makes the random seeds of unseeded experiments reproducible.
+///
+/// # Arguments
+///
+/// * `seed` - Seed of the generator of random seeds, or `undefined` to
+///   restore `Math.random()`.
+#[wasm_bindgen(js_name = seedRandom)]
+pub fn seed_random(seed: Option<u32>) {
+    set_seed_source(seed.map(|seed| {
+        Box::new(StdRng::seed_from_u64(u64::from(seed))) as Box<dyn rand::RngCore + Send>
+    }));
+}
+
+/// Draws a seed from two calls to `Math.random()`, each providing 32 bits.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn math_random_seed() -> u64 {
+    let bits = || (js_sys::Math::random() * 4_294_967_296.0) as u64;
+    (bits() << 32) | bits()
+}
+
+/// Parses an experiment and fixes its seed, so that it is returned to JavaScript.
+fn experiment_config(value: JsValue) -> Result<ExperimentConfig, JsError> {
+    let mut experiment: ExperimentConfig = serde_wasm_bindgen::from_value(value)?;
+    match experiment.seed {
+        Some(seed) if seed > MAX_SAFE_SEED => {
+            return Err(JsError::new(&format!(
+                "Seeds must be at most {} to be represented as JavaScript numbers",
+                MAX_SAFE_SEED
+            )))
+        }
+        Some(_) => {}
+        None => experiment.seed = Some(random_seed() & MAX_SAFE_SEED),
+    }
+    Ok(experiment)
+}
+
+/// Converts a value to a plain JavaScript object, with `null` for missing values.
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
@@ -0,0 +1,115 @@
+use qkd::backend::Backend;
+use qkd::protocol::QExecutionResult;
+use qkd::protocols::ProtocolRegistry;
+
+const NUMBER_OF_QUBITS: usize = 200_000;
+const INTERCEPTION_RATE: f64 = 0.5;
+const TOLERANCE: f64 = 0.01;
+
+/// Frequencies of the events that determine the outcome of a protocol run.
+struct RoundStatistics {
+    intercepted: f64,
+    matching_basis: f64,
+    alice_one: f64,
+    bob_one: f64,
+    errors_in_matching_basis: f64,
+    eve_correct: f64,
+}
+
+impl RoundStatistics {
+    fn new(results: &[QExecutionResult]) -> Self {
+        let total = results.len() as f64;
+        let matching = results
+            .iter()
+            .filter(|r| r.alice_basis == r.bob_basis)
+            .collect::<Vec<_>>();
+        let intercepted = results
+            .iter()
+            .filter(|r| r.eve_value.is_some())
+            .collect::<Vec<_>>();
+
+        RoundStatistics {
+            intercepted: intercepted.len() as f64 / total,
+            matching_basis: matching.len() as f64 / total,
+            alice_one: results.iter().filter(|r| r.alice_value).count() as f64 / total,
+            bob_one: results.iter().filter(|r| r.bob_value).count() as f64 / total,
+            errors_in_matching_basis: matching
+                .iter()
+                .filter(|r| r.alice_value != r.bob_value)
+                .count() as f64
+                / matching.len() as f64,
+            eve_correct: intercepted
+                .iter()
+                .filter(|r| r.eve_value == Some(r.alice_value))
+                .count() as f64
+                / intercepted.len() as f64,
+        }
+    }
+
+    fn as_array(&self) -> [(&'static str, f64); 6] {
+        [
+            ("intercepted", self.intercepted),
+            ("matching_basis", self.matching_basis),
+            ("alice_one", self.alice_one),
+            ("bob_one", self.bob_one),
+            ("errors_in_matching_basis", self.errors_in_matching_basis),
+            ("eve_correct", self.eve_correct),
+        ]
+    }
+}
+
+#[test]
+fn statistical_backend_matches_state_vector_statistics() {
+    let registry = ProtocolRegistry::default();
+    for protocol in registry.iter() {
+        let state_vector = protocol
+            .build()
+            .with_backend(Backend::StateVector)
+            .simulate(NUMBER_OF_QUBITS, INTERCEPTION_RATE, 1);
+        let statistical = protocol
+            .build()
+            .with_backend(Backend::Statistical)
+            .simulate(NUMBER_OF_QUBITS, INTERCEPTION_RATE, 2);
+
+        let expected = RoundStatistics::new(&state_vector).as_array();
+        let actual = RoundStatistics::new(&statistical).as_array();
+        for ((name, expected), (_, actual)) in expected.into_iter().zip(actual) {
+            assert!(
+                (expected - actual).abs() < TOLERANCE,
+                "{}: `{}` differs between backends ({} vs {})",
+                protocol.name(),
+                name,
+                expected,
+                actual
+            );
+        }
+    }
+}
+
+#[test]
+fn statistical_backend_without_eve_has_no_errors() {
+    let registry = ProtocolRegistry::default();
+    for protocol in registry.iter() {
+        let result = protocol
+            .build()
+            .with_backend(Backend::Statistical)
+            .run_with_seed(10_000, 0.0, 3);
+        assert!(result.is_considered_secure);
+        assert_eq!(result.quantum_bit_error_rate, Some(0.0));
+    }
+}
+
+#[test]
+fn statistical_backend_samples_partial_blocks() {
+    let registry = ProtocolRegistry::default();
+    for protocol in registry.iter() {
+        let qkd = protocol.build().with_backend(Backend::Statistical);
+        if !qkd.supports_statistical_backend() {
+            continue;
+        }
+        let rounds = qkd.simulate(1_000, 0.3, 4);
+        assert_eq!(rounds.len(), 1_000, "{}", protocol.name());
+        assert_eq!(rounds, qkd.simulate(1_000, 0.3, 4));
+        assert_eq!(rounds[..64], qkd.simulate(100, 0.3, 4)[..64]);
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use qkd::checkpoint::Checkpoint;
+use qkd::output::{ResultRecord, RunMetadata};
+use serde_json::json;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+fn record(id: usize) -> ResultRecord {
+    common::bb84_record(id, 200, 0.5)
+}
+
+#[test]
+fn checkpoints_are_named_after_the_output() {
+    assert_eq!(
+        Checkpoint::path_for(Path::new("output/results.csv")),
+        Path::new("output/results.csv.checkpoint")
+    );
+}
+
+#[test]
+fn completed_configurations_are_saved_and_resumed() {
+    let directory = std::env::temp_dir().join(format!("qkd-checkpoint-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("results.csv.checkpoint");
+    let metadata = RunMetadata::new(42, json!({ "repetitions": 3 }));
+
+    let mut checkpoint = Checkpoint::create(&path, metadata.clone(), 3).unwrap();
+    for id in [4, 0, 2, 5, 1, 3, 7] {
+        checkpoint.record(&record(id)).unwrap();
+    }
+    assert_eq!(
+        checkpoint.completed.keys().copied().collect::<Vec<usize>>(),
+        [0, 1]
+    );
+    drop(checkpoint);
+
+    let mut resumed = Checkpoint::open(&path).unwrap();
+    assert_eq!(resumed.metadata, metadata);
+    assert_eq!(resumed.runs_per_configuration, 3);
+    let ids = resumed
+        .records()
+        .iter()
+        .map(|record| record.id)
+        .collect::<Vec<usize>>();
+    assert_eq!(ids, [0, 1, 2, 3, 4, 5]);
+    let first = &resumed.completed[&0][0];
+    assert_eq!(first.result.key_length, record(0).result.key_length);
+
+    // The run of configuration 2 recorded before the interruption is lost.
+    for id in [6, 8] {
+        resumed.record(&record(id)).unwrap();
+    }
+    assert!(!resumed.completed.contains_key(&2));
+    resumed.record(&record(7)).unwrap();
+    drop(resumed);
+    assert_eq!(Checkpoint::open(&path).unwrap().records().len(), 9);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn existing_checkpoints_are_not_replaced() {
+    let directory =
+        std::env::temp_dir().join(format!("qkd-checkpoint-existing-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("results.csv.checkpoint");
+
+    let mut checkpoint = Checkpoint::create(&path, RunMetadata::new(1, json!({})), 1).unwrap();
+    checkpoint.record(&record(0)).unwrap();
+    drop(checkpoint);
+    let error = Checkpoint::create(&path, RunMetadata::new(2, json!({})), 1).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+
+    let resumed = Checkpoint::open(&path).unwrap();
+    assert_eq!(resumed.metadata.seed, 1);
+    assert_eq!(resumed.completed.len(), 1);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn truncated_last_lines_are_discarded() {
+    let directory =
+        std::env::temp_dir().join(format!("qkd-checkpoint-truncated-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("results.json.checkpoint");
+
+    let mut checkpoint = Checkpoint::create(&path, RunMetadata::new(1, json!({})), 1).unwrap();
+    checkpoint.record(&record(0)).unwrap();
+    drop(checkpoint);
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"{\"configuration\": 1, \"resu").unwrap();
+    drop(file);
+
+    let mut resumed = Checkpoint::open(&path).unwrap();
+    assert_eq!(resumed.completed.len(), 1);
+    resumed.record(&record(1)).unwrap();
+    drop(resumed);
+    assert_eq!(Checkpoint::open(&path).unwrap().completed.len(), 2);
+
+    std::fs::write(&path, "{\"results\": []}\n").unwrap();
+    assert!(Checkpoint::open(&path).is_err());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,42 @@
+//! Records shared by the tests of the outputs, statistics, plots, checkpoints
+//! and monitor of the simulator.
+#![allow(dead_code)]
+
+use qkd::output::ResultRecord;
+use qkd::protocol::QKDResult;
+use qkd::protocols::{QkdProtocol, BB84};
+
+/// Returns the record of a run of a discrete-variable protocol without a fiber.
+///
+/// # Arguments
+///
+/// * `id` - Identifier of the run.
+/// * `protocol` - Name of the protocol.
+/// * `number_of_qubits` - Number of qubits sent.
+/// * `interception_rate` - Probability (0.0 to 1.0) that Eve intercepts a qubit.
+/// * `result` - Result of the run.
+pub fn record(
+    id: usize,
+    protocol: &str,
+    number_of_qubits: usize,
+    interception_rate: f64,
+    result: QKDResult,
+) -> ResultRecord {
+    ResultRecord {
+        id,
+        scenario: None,
+        protocol: protocol.to_string(),
+        number_of_qubits,
+        interception_rate,
+        distance_km: None,
+        parameters: None,
+        result,
+        estimate: None,
+    }
+}
+
+/// Returns the record of a run of BB84 without a fiber, seeded with its id.
+pub fn bb84_record(id: usize, number_of_qubits: usize, interception_rate: f64) -> ResultRecord {
+    let result = BB84.run_with_seed(number_of_qubits, interception_rate, id as u64);
+    record(id, "BB84", number_of_qubits, interception_rate, result)
+}
@@ -0,0 +1,221 @@
+use qkd::cvqkd::{CvParameters, Detection, GG02Homodyne, GG02};
+use qkd::output::{
+    read_records, AggregateRecord, OutputFormat, ResultRecord, ResultWriter, RunMetadata,
+};
+use qkd::physical::{Channel, PhysicalLayer};
+use qkd::protocol::{QKDResult, QKD};
+use qkd::protocols::{ProtocolRegistry, QkdProtocol, BB84};
+use serde_json::json;
+
+fn fiber(distance_km: f64) -> PhysicalLayer {
+    PhysicalLayer {
+        channel: Channel::fiber(distance_km),
+        ..PhysicalLayer::default()
+    }
+}
+
+#[test]
+fn eve_learns_nothing_from_an_ideal_channel() {
+    for detection in [Detection::Homodyne, Detection::Heterodyne] {
+        let estimate = GG02::builder()
+            .detection(detection)
+            .reconciliation_efficiency(1.0)
+            .build()
+            .estimate(1.0, 0.0);
+        assert!(estimate.holevo_bound.abs() < 1e-9, "{:?}", estimate);
+        assert!((estimate.key_rate - estimate.mutual_information).abs() < 1e-9);
+    }
+    let homodyne = GG02::builder().build().estimate(1.0, 0.0);
+    assert!((homodyne.mutual_information - 0.5 * 5f64.log2()).abs() < 1e-9);
+}
+
+#[test]
+fn reverse_reconciliation_beats_the_three_db_limit() {
+    let gg02 = GG02::builder()
+        .modulation_variance(20.0)
+        .reconciliation_efficiency(1.0)
+        .build();
+    let estimate = gg02.estimate(0.1, 0.0);
+    assert!(estimate.key_rate > 0.0, "{:?}", estimate);
+    assert!(gg02.estimate(0.1, 0.1).key_rate < estimate.key_rate);
+    assert!(gg02.estimate(0.1, 0.5).key_rate < 0.0);
+}
+
+#[test]
+fn estimates_recover_the_channel_parameters() {
+    let physical_layer = fiber(10.0);
+    for detection in [Detection::Homodyne, Detection::Heterodyne] {
+        let result = GG02::builder()
+            .detection(detection)
+            .excess_noise(0.05)
+            .physical_layer(physical_layer)
+            .build()
+            .run_with_seed(400_000, 0.0, 7);
+        let estimate = result.estimate.unwrap();
+        let transmittance = physical_layer.channel.transmittance();
+        assert!(
+            (estimate.transmittance - transmittance).abs() < 0.02,
+            "{:?}",
+            estimate
+        );
+        assert!(
+            (estimate.excess_noise - 0.05).abs() < 0.03,
+            "{:?}",
+            estimate
+        );
+        assert!(result.is_considered_secure);
+        let key_rate = result.key_length.unwrap() as f64 / 200_000.0;
+        assert!((key_rate - estimate.key_rate).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn intercept_resend_is_detected_as_excess_noise() {
+    let gg02 = GG02::builder().build();
+    let result = gg02.run_with_seed(100_000, 1.0, 3);
+    let estimate = result.estimate.unwrap();
+    assert!((estimate.excess_noise - 2.0).abs() < 0.1, "{:?}", estimate);
+    assert!(!result.is_considered_secure);
+    assert_eq!(result.key_length, None);
+
+    let result = QKDResult::from(result);
+    assert!(!result.is_considered_secure);
+    assert_eq!(result.quantum_bit_error_rate, None);
+}
+
+#[test]
+fn seeded_runs_are_reproducible() {
+    let registry = ProtocolRegistry::default();
+    let gg02 = registry.get_continuous("GG02-heterodyne").unwrap().build();
+    let first = gg02.run_with_seed(10_000, 0.1, 11);
+    let second = gg02.run_with_seed(10_000, 0.1, 11);
+    assert_eq!(first.estimate, second.estimate);
+    assert_eq!(first.key_length, second.key_length);
+    assert!(registry.get_continuous("BB84").is_none());
+}
+
+#[test]
+fn registering_a_protocol_replaces_the_one_with_the_same_name() {
+    let mut registry = ProtocolRegistry::default();
+    assert_eq!(
+        registry.continuous_names(),
+        ["GG02-heterodyne", "GG02-homodyne"]
+    );
+    assert!(registry.get("GG02-homodyne").is_none());
+
+    registry.register(Renamed("GG02-homodyne"));
+    assert!(registry.get("GG02-homodyne").is_some());
+    assert!(registry.get_continuous("GG02-homodyne").is_none());
+
+    registry.register_continuous(GG02Homodyne);
+    assert!(registry.get("GG02-homodyne").is_none());
+    assert!(registry.get_continuous("GG02-homodyne").is_some());
+}
+
+#[test]
+fn estimates_are_read_back_from_every_text_format() {
+    let gg02 = GG02::builder().physical_layer(fiber(10.0)).build();
+    let result = gg02.run_with_seed(20_000, 0.0, 5);
+    let estimate = result.estimate;
+    assert!(estimate.is_some());
+    let records = [
+        ResultRecord {
+            id: 0,
+            scenario: None,
+            protocol: "GG02-homodyne".to_string(),
+            number_of_qubits: 20_000,
+            interception_rate: 0.0,
+            distance_km: Some(10.0),
+            parameters: Some(gg02.parameters()),
+            result: result.into(),
+            estimate,
+        },
+        ResultRecord {
+            id: 1,
+            scenario: None,
+            protocol: "BB84".to_string(),
+            number_of_qubits: 1_000,
+            interception_rate: 0.0,
+            distance_km: Some(10.0),
+            parameters: None,
+            result: BB84.build().run_with_seed(1_000, 0.0, 5),
+            estimate: None,
+        },
+    ];
+    let directory = std::env::temp_dir().join(format!("qkd-cvqkd-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let metadata = RunMetadata::new(5, json!({}));
+
+    for (format, file) in [
+        (OutputFormat::Csv, "results.csv"),
+        (OutputFormat::Json, "results.json"),
+        (OutputFormat::Jsonl, "results.jsonl"),
+    ] {
+        let path = directory.join(file);
+        let mut writer = ResultWriter::create(&path, format, &metadata).unwrap();
+        for record in &records {
+            writer.write(record).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let read = read_records(&path).unwrap();
+        assert_eq!(read[0].estimate, estimate, "{:?}", format);
+        assert_eq!(read[1].estimate, None, "{:?}", format);
+        assert_eq!(read[0].parameters, records[0].parameters, "{:?}", format);
+        assert_eq!(read[1].parameters, None, "{:?}", format);
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn runs_with_different_parameters_are_aggregated_separately() {
+    let records = [0.0, 0.0, 0.1, 0.1]
+        .into_iter()
+        .enumerate()
+        .map(|(id, excess_noise)| {
+            let gg02 = GG02::builder().excess_noise(excess_noise).build();
+            let result = gg02.run_with_seed(2_000, 0.0, id as u64);
+            ResultRecord {
+                id,
+                scenario: None,
+                protocol: "GG02-homodyne".to_string(),
+                number_of_qubits: 2_000,
+                interception_rate: 0.0,
+                distance_km: None,
+                parameters: Some(gg02.parameters()),
+                estimate: result.estimate,
+                result: result.into(),
+            }
+        })
+        .collect::<Vec<ResultRecord>>();
+
+    let aggregates = AggregateRecord::group(&records, 0.95);
+    let parameters = aggregates
+        .iter()
+        .map(|aggregate| (aggregate.parameters, aggregate.statistics.abort.trials))
+        .collect::<Vec<_>>();
+    let expected = |excess_noise| {
+        Some(CvParameters {
+            excess_noise,
+            modulation_variance: 4.0,
+        })
+    };
+    assert_eq!(parameters, [(expected(0.0), 2), (expected(0.1), 2)]);
+}
+
+/// BB84 registered under another name.
+struct Renamed(&'static str);
+
+impl QkdProtocol for Renamed {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn description(&self) -> &str {
+        BB84.description()
+    }
+
+    fn build(&self) -> QKD {
+        BB84.build()
+    }
+}
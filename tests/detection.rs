@@ -0,0 +1,98 @@
+use qkd::backend::Backend;
+use qkd::detection::{roc_figure, DetectionAnalysis};
+use qkd::mdi::MDI;
+use qkd::physical::{Channel, PhysicalLayer};
+use qkd::protocols::{QkdProtocol, BB84, RFI};
+
+fn analysis() -> DetectionAnalysis {
+    DetectionAnalysis::builder()
+        .interception_rates(vec![0.0, 0.1, 0.5])
+        .sample_sizes(vec![20, 100])
+        .thresholds(vec![0.0, 0.05, 0.1])
+        .trials(200)
+        .build()
+}
+
+#[test]
+fn detection_grows_with_sample_size_and_interception_rate() {
+    let protocol = BB84.build().with_backend(Backend::Statistical);
+    let records = analysis().run("BB84", &protocol, 7).unwrap();
+    assert_eq!(records.len(), 2 * 2 * 3);
+    assert_eq!(records, analysis().run("BB84", &protocol, 7).unwrap());
+
+    let detection = |rate: f64, sample_size: usize, threshold: f64| {
+        records
+            .iter()
+            .find(|record| {
+                record.interception_rate == rate
+                    && record.sample_size == sample_size
+                    && record.threshold == threshold
+            })
+            .unwrap()
+            .detection
+            .estimate
+    };
+    assert!(records
+        .iter()
+        .all(|record| record.false_abort.successes == 0));
+    assert!(detection(0.1, 20, 0.0) < detection(0.1, 100, 0.0));
+    assert!(detection(0.1, 100, 0.0) < detection(0.5, 100, 0.0));
+    assert!(detection(0.5, 100, 0.1) < detection(0.5, 100, 0.0));
+    assert_eq!(detection(0.5, 100, 0.0), 1.0);
+
+    let figure = roc_figure(&records).unwrap();
+    assert_eq!(figure.series.len(), 4);
+    assert_eq!(figure.series[0].points.len(), 3);
+}
+
+#[test]
+fn honest_noise_causes_false_aborts() {
+    let protocol = BB84.build().with_physical_layer(PhysicalLayer {
+        channel: Channel {
+            depolarization: 0.2,
+            ..Channel::default()
+        },
+        ..PhysicalLayer::default()
+    });
+    let records = analysis().run("BB84", &protocol, 7).unwrap();
+    let false_abort = |threshold: f64| {
+        records
+            .iter()
+            .find(|record| record.sample_size == 100 && record.threshold == threshold)
+            .unwrap()
+            .false_abort
+            .estimate
+    };
+    assert!(false_abort(0.0) > 0.99);
+    assert!(false_abort(0.1) < false_abort(0.05));
+    assert!(records.iter().all(|record| record.noise == 0.2));
+
+    let mut both = analysis().run("BB84", &BB84.build(), 7).unwrap();
+    both.extend(records.iter().cloned());
+    assert_eq!(roc_figure(&both).unwrap().series.len(), 2 * 4);
+
+    let error = DetectionAnalysis::builder()
+        .interception_rates(vec![0.0])
+        .sample_sizes(vec![10])
+        .thresholds(vec![0.0])
+        .build()
+        .run("BB84", &protocol, 1)
+        .unwrap_err();
+    assert!(error.contains("positive interception rate"));
+}
+
+#[test]
+fn protocols_without_a_qber_threshold_are_rejected() {
+    let error = analysis().run("RFI", &RFI.build(), 7).unwrap_err();
+    assert!(error.contains("QBER threshold"), "{}", error);
+
+    let records = DetectionAnalysis::builder()
+        .interception_rates(vec![0.1])
+        .sample_sizes(vec![50])
+        .thresholds(vec![0.0])
+        .trials(20)
+        .build()
+        .run("MDI", &MDI.build(), 7)
+        .unwrap();
+    assert_eq!(records[0].false_abort.successes, 0);
+}
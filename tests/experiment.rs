@@ -0,0 +1,195 @@
+#![cfg(feature = "toml")]
+
+use qkd::config::ExperimentFile;
+use qkd::output::OutputFormat;
+use qkd::physical::{Channel, Detector, PhysicalLayer};
+use qkd::protocols::ProtocolRegistry;
+
+const EXPERIMENT: &str = r#"
+seed = 7
+
+[[protocol]]
+name = "Z"
+alice_basis = [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]]
+bob_basis = [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]]
+
+[[scenario]]
+name = "lossy"
+protocol = "BB84"
+number_of_qubits = 20000
+channel = { loss = 0.5, depolarization = 0.1 }
+detector = { efficiency = 0.8 }
+post_processing = { qber_threshold = 0.11 }
+
+[[scenario]]
+name = "custom"
+protocol = "Z"
+number_of_qubits = 1000
+repetitions = 3
+
+[[output]]
+path = "results.parquet"
+format = "parquet"
+"#;
+
+#[test]
+fn experiment_file_is_parsed_with_defaults() {
+    let registry = ProtocolRegistry::default();
+    let file = ExperimentFile::from_toml(EXPERIMENT, &registry).unwrap();
+    assert_eq!(file.seed, Some(7));
+    assert_eq!(file.outputs[0].format, OutputFormat::Parquet);
+
+    let lossy = &file.scenarios[0];
+    assert_eq!(lossy.repetitions, 1);
+    assert_eq!(
+        lossy.physical_layer(),
+        PhysicalLayer {
+            channel: Channel {
+                loss: 0.5,
+                depolarization: 0.1,
+                ..Channel::default()
+            },
+            detector: Detector {
+                efficiency: 0.8,
+                dark_count_probability: 0.0,
+            },
+            ..PhysicalLayer::default()
+        }
+    );
+
+    let registry = file.registry(&registry);
+    let rounds = lossy.build(&registry).unwrap().simulate(20000, 0.0, 1);
+    let detected_fraction = rounds.len() as f64 / 20000.0;
+    assert!((detected_fraction - 0.4).abs() < 0.02);
+
+    let result = lossy.run_with_seed(&registry, 1).unwrap();
+    assert!(result.is_considered_secure);
+    assert!((result.quantum_bit_error_rate.unwrap() - 0.05).abs() < 0.02);
+
+    let custom = &file.scenarios[1];
+    let result = custom.run_with_seed(&registry, 1).unwrap();
+    assert_eq!(result.key_length, Some(500));
+}
+
+#[test]
+fn experiment_file_reports_every_problem() {
+    let registry = ProtocolRegistry::default();
+    let error = ExperimentFile::from_toml(
+        r#"
+        [[scenario]]
+        name = "a"
+        protocol = "BB85"
+        number_of_qubits = 0
+        detector = { efficiency = 1.5 }
+
+        [[scenario]]
+        name = "a"
+        protocol = "BB84"
+        number_of_qubits = 10
+        "#,
+        &registry,
+    )
+    .unwrap_err();
+    let lines = error.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4, "{}", error);
+    assert!(lines[0].starts_with("scenario `a`: `protocol` `BB85` is not registered"));
+    assert!(lines[2].contains("`detector.efficiency` must be between 0.0 and 1.0"));
+    assert_eq!(lines[3], "scenario `a` is defined twice");
+
+    let error = ExperimentFile::from_toml("[[scenario]]\nname = 1\n", &registry).unwrap_err();
+    assert!(error.contains("line 2"), "{}", error);
+}
+
+#[test]
+fn continuous_variable_scenarios_use_the_registry() {
+    let registry = ProtocolRegistry::default();
+    let file = ExperimentFile::from_toml(
+        r#"
+        [[scenario]]
+        name = "cv"
+        protocol = "GG02-heterodyne"
+        number_of_qubits = 40000
+        channel = { distance_km = 10 }
+        continuous_variable = { excess_noise = 0.05, modulation_variance = 10 }
+        "#,
+        &registry,
+    )
+    .unwrap();
+    let scenario = &file.scenarios[0];
+    assert!(scenario.is_continuous(&registry));
+    assert!(scenario.build(&registry).is_err());
+    let result = scenario.run_continuous_with_seed(&registry, 3).unwrap();
+    let estimate = result.estimate.unwrap();
+    assert!(
+        (estimate.excess_noise - 0.05).abs() < 0.05,
+        "{:?}",
+        estimate
+    );
+    assert!(result.is_considered_secure);
+
+    let error = ExperimentFile::from_toml(
+        r#"
+        [[scenario]]
+        name = "cv"
+        protocol = "GG02-homodyne"
+        number_of_qubits = 100
+        backend = "statistical"
+        post_processing = { block_size = 10 }
+
+        [[scenario]]
+        name = "dv"
+        protocol = "BB84"
+        number_of_qubits = 100
+        continuous_variable = { excess_noise = -1 }
+        "#,
+        &registry,
+    )
+    .unwrap_err();
+    let lines = error.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4, "{}", error);
+    assert!(lines[0].contains("`backend` does not apply"));
+    assert!(lines[1].contains("`post_processing` does not apply"));
+    assert!(lines[2].contains("`continuous_variable` only applies"));
+    assert!(lines[3].contains("`continuous_variable.excess_noise` must be a non-negative number"));
+}
+
+#[test]
+fn custom_protocols_are_checked_against_the_registry_and_their_sifting() {
+    let registry = ProtocolRegistry::default();
+    let error = ExperimentFile::from_toml(
+        r#"
+        [[protocol]]
+        name = "BB84"
+        alice_basis = [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]]
+        bob_basis = [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]]
+
+        [[protocol]]
+        name = "B92-3"
+        alice_basis = [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]], [[[1, 0], [0, 0]], [[0, 0], [1, 0]]]]
+        bob_basis = [
+            [[[1, 0], [0, 0]], [[0, 0], [1, 0]]],
+            [[[1, 0], [0, 0]], [[0, 0], [1, 0]]],
+            [[[1, 0], [0, 0]], [[0, 0], [1, 0]]],
+        ]
+        preparation = "fixed_zero"
+        sifting = "b92"
+
+        [[scenario]]
+        name = "b92"
+        protocol = "B92-3"
+        number_of_qubits = 10
+        "#,
+        &registry,
+    )
+    .unwrap_err();
+    let lines = error.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2, "{}", error);
+    assert_eq!(
+        lines[0],
+        "protocol `BB84` is already registered and cannot be redefined"
+    );
+    assert_eq!(
+        lines[1],
+        "protocol `B92-3`: `bob_basis` must contain exactly 2 bases with `b92` sifting, found 3"
+    );
+}
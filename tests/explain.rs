@@ -0,0 +1,119 @@
+use num_complex::Complex64;
+use qkd::explain::{ket, Explanation};
+use qkd::protocols::{QkdProtocol, SixState, B92, BB84, RFI};
+use qkd::transcript::RoundRole;
+use std::f64::consts::FRAC_1_SQRT_2;
+
+#[test]
+fn kets_are_named_up_to_a_global_phase() {
+    let s = FRAC_1_SQRT_2;
+    let state = |a: (f64, f64), b: (f64, f64)| (Complex64::new(a.0, a.1), Complex64::new(b.0, b.1));
+
+    assert_eq!(ket(state((1.0, 0.0), (0.0, 0.0))), "|0⟩");
+    assert_eq!(ket(state((0.0, 0.0), (-1.0, 0.0))), "|1⟩");
+    assert_eq!(ket(state((s, 0.0), (-s, 0.0))), "|−⟩");
+    assert_eq!(ket(state((0.0, s), (-s, 0.0))), "|+i⟩");
+    assert_eq!(
+        ket(state((0.6, 0.0), (0.8, 0.0))),
+        "(0.600+0.000i)|0⟩ + (0.800+0.000i)|1⟩"
+    );
+}
+
+#[test]
+fn explanation_describes_the_same_execution_as_a_run() {
+    for protocol in [&BB84 as &dyn QkdProtocol, &SixState, &B92] {
+        for seed in 0..5 {
+            let qkd = protocol.build();
+            let explanation = Explanation::new(protocol.name(), &qkd, 200, 0.3, seed);
+            let result = qkd.run_with_seed(200, 0.3, seed);
+
+            assert_eq!(explanation.rounds.len(), 200);
+            assert_eq!(
+                explanation.is_considered_secure,
+                result.is_considered_secure
+            );
+            if result.is_considered_secure {
+                assert_eq!(Some(explanation.key_bits.len()), result.key_length);
+            }
+            let roles = |role| {
+                explanation
+                    .rounds
+                    .iter()
+                    .filter(|round| round.role == role)
+                    .count()
+            };
+            assert_eq!(roles(RoundRole::Check), explanation.check_bits.len());
+            assert_eq!(roles(RoundRole::Key), explanation.key_bits.len());
+            assert!(explanation.rounds.iter().any(|round| round.eve.is_some()));
+        }
+    }
+}
+
+#[test]
+fn bb84_rounds_use_the_z_and_x_states() {
+    let explanation = Explanation::new("BB84", &BB84.build(), 50, 0.0, 4);
+
+    for round in &explanation.rounds {
+        let expected = match (round.alice_basis.as_str(), round.alice_value) {
+            ("Z", false) => "|0⟩",
+            ("Z", true) => "|1⟩",
+            ("X", false) => "|+⟩",
+            ("X", true) => "|−⟩",
+            (basis, _) => panic!("unexpected basis {}", basis),
+        };
+        assert_eq!(round.sent_state, expected);
+        assert!(round.eve.is_none());
+        assert_eq!(
+            round.alice_basis == round.bob_basis,
+            round.role != RoundRole::Discarded
+        );
+    }
+    assert!(explanation
+        .check_bits
+        .iter()
+        .all(|check| check.alice_value == check.bob_value));
+    assert!(explanation.to_string().contains("Step 4: key extraction"));
+}
+
+#[test]
+fn rfi_walkthrough_follows_its_sifting_strategy() {
+    let qkd = RFI.build();
+    assert!(!qkd.uses_qber_threshold());
+    assert!(BB84.build().uses_qber_threshold());
+
+    let explanation = Explanation::new("RFI", &qkd, 200, 0.0, 3);
+    assert!(explanation.sifting_rules.contains("X or Y"));
+    assert!(explanation.security_check.contains("C = "));
+    let text = explanation.to_string();
+    assert!(!text.contains("always agree"));
+    assert!(!text.contains("QBER threshold"));
+    assert_eq!(
+        explanation.is_considered_secure,
+        qkd.run_with_seed(200, 0.0, 3).is_considered_secure
+    );
+
+    let bb84 = Explanation::new("BB84", &BB84.build(), 50, 0.0, 3).to_string();
+    assert!(bb84.contains("always agree") && bb84.contains("QBER threshold 0"));
+}
+
+#[test]
+fn b92_walkthrough_shows_the_bits_derived_from_the_bases() {
+    let explanation = Explanation::new("B92", &B92.build(), 60, 0.0, 5);
+    assert!(explanation.sifting_rules.contains("conclusive"));
+    assert!(!explanation
+        .sifting_rules
+        .starts_with("Half of the kept rounds"));
+
+    for round in &explanation.rounds {
+        assert_eq!(round.alice_value, round.alice_basis == "X");
+        let sent = if round.alice_value { "|+⟩" } else { "|0⟩" };
+        assert_eq!(round.sent_state, sent);
+        assert_eq!(round.bob_value, round.role != RoundRole::Discarded);
+    }
+    let key_bits = &explanation.key_bits;
+    assert!(key_bits.iter().any(|bit| bit.alice_value));
+    assert!(key_bits.iter().all(|bit| {
+        bit.alice_value == bit.bob_value
+            && bit.alice_value == explanation.rounds[bit.round].alice_value
+    }));
+}
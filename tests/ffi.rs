@@ -0,0 +1,134 @@
+#![cfg(feature = "cdylib")]
+
+use qkd::ffi::*;
+use std::ffi::CString;
+use std::ptr;
+
+unsafe fn protocol(name: &str) -> *mut QkdProtocol {
+    let name = CString::new(name).unwrap();
+    let mut protocol = ptr::null_mut();
+    assert_eq!(
+        qkd_protocol_new(name.as_ptr(), &mut protocol),
+        QkdStatus::Ok
+    );
+    protocol
+}
+
+unsafe fn run(protocol: *const QkdProtocol) -> (QkdResult, Vec<u8>) {
+    let mut run = ptr::null_mut();
+    assert_eq!(qkd_protocol_run(protocol, &mut run), QkdStatus::Ok);
+    let mut result = QkdResult {
+        elapsed_time_us: 0,
+        is_considered_secure: false,
+        key_length: 0,
+        quantum_bit_error_rate: 0.0,
+        eve_knowledge: 0.0,
+    };
+    assert_eq!(qkd_run_result(run, &mut result), QkdStatus::Ok);
+    let (mut key, mut length) = (ptr::null(), 0);
+    assert_eq!(qkd_run_key(run, &mut key, &mut length), QkdStatus::Ok);
+    let key = std::slice::from_raw_parts(key, length).to_vec();
+    qkd_run_free(run);
+    (result, key)
+}
+
+#[test]
+fn seeded_runs_return_the_same_result_and_key() {
+    unsafe {
+        let protocol = protocol("BB84");
+        assert_eq!(
+            qkd_protocol_set_number_of_qubits(protocol, 20_000),
+            QkdStatus::Ok
+        );
+        assert_eq!(
+            qkd_protocol_set_depolarization(protocol, 0.02),
+            QkdStatus::Ok
+        );
+        assert_eq!(
+            qkd_protocol_set_qber_threshold(protocol, 0.05),
+            QkdStatus::Ok
+        );
+        assert_eq!(qkd_protocol_set_seed(protocol, 9), QkdStatus::Ok);
+
+        let (result, key) = run(protocol);
+        assert!(result.is_considered_secure);
+        assert!(result.quantum_bit_error_rate > 0.0);
+        assert!(!key.is_empty() && key.len() * 8 < result.key_length);
+        let (again, again_key) = run(protocol);
+        assert_eq!(again.key_length, result.key_length);
+        assert_eq!(again.quantum_bit_error_rate, result.quantum_bit_error_rate);
+        assert_eq!(again_key, key);
+        qkd_protocol_free(protocol);
+    }
+}
+
+#[test]
+fn aborted_runs_have_no_key() {
+    unsafe {
+        let protocol = protocol("SixState");
+        assert_eq!(
+            qkd_protocol_set_interception_rate(protocol, 1.0),
+            QkdStatus::Ok
+        );
+        let (result, key) = run(protocol);
+        assert!(!result.is_considered_secure);
+        assert_eq!(result.key_length, 0);
+        assert!(result.quantum_bit_error_rate.is_nan());
+        assert!(key.is_empty());
+        qkd_protocol_free(protocol);
+    }
+}
+
+#[test]
+fn invalid_arguments_are_reported() {
+    unsafe {
+        let mut handle = ptr::null_mut();
+        let name = CString::new("E91").unwrap();
+        assert_eq!(
+            qkd_protocol_new(name.as_ptr(), &mut handle),
+            QkdStatus::UnknownProtocol
+        );
+        assert!(handle.is_null());
+        assert_eq!(
+            qkd_protocol_new(ptr::null(), &mut handle),
+            QkdStatus::NullPointer
+        );
+        assert_eq!(
+            qkd_protocol_set_loss(ptr::null_mut(), 0.1),
+            QkdStatus::NullPointer
+        );
+        assert_eq!(
+            qkd_protocol_run(ptr::null(), &mut ptr::null_mut()),
+            QkdStatus::NullPointer
+        );
+
+        let protocol = protocol("B92");
+        assert_eq!(
+            qkd_protocol_set_loss(protocol, 1.5),
+            QkdStatus::InvalidArgument
+        );
+        assert_eq!(
+            qkd_protocol_set_distance_km(protocol, -1.0),
+            QkdStatus::InvalidArgument
+        );
+        assert_eq!(
+            qkd_protocol_set_detector(protocol, 0.5, f64::NAN),
+            QkdStatus::InvalidArgument
+        );
+        qkd_protocol_free(protocol);
+        qkd_protocol_free(ptr::null_mut());
+        qkd_run_free(ptr::null_mut());
+    }
+    assert_eq!(qkd_abi_version(), QKD_ABI_VERSION);
+}
+
+#[test]
+fn the_distributed_header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/qkd.h"));
+    let distributed = include_str!("../include/qkd.h");
+    assert!(
+        generated == distributed,
+        "`include/qkd.h` is outdated, copy it from `{}/qkd.h`",
+        env!("OUT_DIR")
+    );
+}
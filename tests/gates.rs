@@ -0,0 +1,76 @@
+use num_complex::Complex64;
+use qkd::types::{ComplexMatrix, Qubit};
+use qkd::utils::{
+    measurement_basis, preparation_basis, rx, ry, rz, seeded_rng, H, H_Y, I, S, T, X, Y, Z,
+};
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+fn assert_close(a: ComplexMatrix, b: ComplexMatrix) {
+    for row in 0..2 {
+        for column in 0..2 {
+            assert!(
+                (a.0[row][column] - b.0[row][column]).norm() < 1e-12,
+                "{:?} != {:?}",
+                a,
+                b
+            );
+        }
+    }
+}
+
+fn scaled(matrix: ComplexMatrix, factor: Complex64) -> ComplexMatrix {
+    ComplexMatrix(matrix.0.map(|row| row.map(|entry| entry * factor)))
+}
+
+#[test]
+fn gates_satisfy_their_algebraic_relations() {
+    assert_close(S * S, Z);
+    assert_close(T * T, S);
+    assert_close(X * Y, scaled(Z, Complex64::i()));
+    assert_close(H * Z * H, X);
+    assert_close(rx(PI), scaled(X, -Complex64::i()));
+    assert_close(ry(PI), scaled(Y, -Complex64::i()));
+    assert_close(rz(PI), scaled(Z, -Complex64::i()));
+    assert_close(
+        rz(FRAC_PI_4),
+        scaled(T, Complex64::from_polar(1.0, -PI / 8.0)),
+    );
+
+    for gate in [I, X, Y, Z, H, H_Y, S, T, rx(0.3), ry(1.1), rz(2.5)] {
+        assert!(gate.is_unitary());
+        assert_close(gate * gate.adjoint(), I);
+    }
+    assert_close(H_Y.adjoint(), H_Y.invert().unwrap());
+    assert!(!(I + X).is_unitary());
+}
+
+#[test]
+fn bloch_bases_generalize_the_fixed_bases() {
+    assert_close(preparation_basis(FRAC_PI_2, 0.0), H);
+    assert_close(preparation_basis(FRAC_PI_2, FRAC_PI_2), H_Y);
+    assert_close(
+        measurement_basis(FRAC_PI_2, FRAC_PI_2),
+        H_Y.invert().unwrap(),
+    );
+}
+
+#[test]
+fn states_are_measured_deterministically_in_their_own_basis() {
+    let mut rng = seeded_rng(1, 0);
+    for (theta, phi) in [(0.4, 0.0), (1.3, 2.2), (2.9, -0.7)] {
+        for value in [false, true] {
+            let mut qubit = Qubit::new();
+            if value {
+                qubit.apply_transformation(&X);
+            }
+            qubit.apply_transformation(&preparation_basis(theta, phi));
+            qubit.apply_transformation(&measurement_basis(theta, phi));
+            let one_probability = qubit.get_one_coef().norm_sqr();
+            assert!((one_probability - f64::from(u8::from(value))).abs() < 1e-12);
+            assert_eq!(
+                qkd::participants::default_measure(&mut qubit, &mut rng),
+                value
+            );
+        }
+    }
+}
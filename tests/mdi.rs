@@ -0,0 +1,113 @@
+use qkd::explain::Explanation;
+use qkd::mdi::{BellState, MDI};
+use qkd::physical::{Channel, PhysicalLayer};
+use qkd::protocols::{ProtocolRegistry, QkdProtocol, BB84};
+use qkd::transcript::Transcript;
+use qkd::utils::{H, I};
+
+#[test]
+fn announced_states_correlate_bits_in_each_basis() {
+    assert_eq!(BellState::PsiPlus.correlation(&I, &I).round(), -1.0);
+    assert_eq!(BellState::PsiMinus.correlation(&I, &I).round(), -1.0);
+    assert_eq!(BellState::PsiPlus.correlation(&H, &H).round(), 1.0);
+    assert_eq!(BellState::PsiMinus.correlation(&H, &H).round(), -1.0);
+    assert!(BellState::PsiPlus.correlation(&I, &H).abs() < 1e-9);
+}
+
+#[test]
+fn honest_relay_announces_half_of_the_rounds_without_errors() {
+    let mdi = MDI.build();
+    let rounds = mdi.simulate(20_000, 0.0, 1);
+    assert!((rounds.len() as f64 / 20_000.0 - 0.5).abs() < 0.02);
+    assert!(rounds.iter().all(|round| round.announcement.is_some()));
+    // Equal bits in the Z basis are projected onto |Φ±⟩, which are never announced.
+    assert!(rounds.iter().all(|round| round.alice_basis != 0
+        || round.bob_basis != 0
+        || round.alice_value != round.bob_value));
+
+    let result = mdi.run_with_seed(20_000, 0.0, 1);
+    assert!(result.is_considered_secure);
+    assert_eq!(result.quantum_bit_error_rate, Some(0.0));
+    assert_eq!(result.eve_knowledge, 0.0);
+    let key_rate = result.key_length.unwrap() as f64 / 20_000.0;
+    assert!((key_rate - 0.125).abs() < 0.01, "{}", key_rate);
+}
+
+#[test]
+fn malicious_relay_causes_errors() {
+    let mdi = MDI.build();
+    assert!(!mdi.run_with_seed(20_000, 1.0, 2).is_considered_secure);
+
+    let result = mdi.with_qber_threshold(1.0).run_with_seed(20_000, 1.0, 2);
+    let qber = result.quantum_bit_error_rate.unwrap();
+    assert!(qber > 0.15 && qber < 0.35, "{}", qber);
+    assert!(result.eve_knowledge > 0.5, "{:?}", result);
+}
+
+#[test]
+fn losses_affect_both_arms_of_the_relay() {
+    let physical_layer = PhysicalLayer {
+        channel: Channel::fiber(50.0),
+        ..PhysicalLayer::default()
+    };
+    let mdi = MDI
+        .build()
+        .with_physical_layer(physical_layer)
+        .run_with_seed(100_000, 0.0, 3);
+    let bb84 = BB84
+        .build()
+        .with_physical_layer(physical_layer)
+        .run_with_seed(100_000, 0.0, 3);
+    let ratio = mdi.key_length.unwrap() as f64 / bb84.key_length.unwrap() as f64;
+    assert!((ratio - 0.5).abs() < 0.1, "{}", ratio);
+}
+
+#[test]
+fn registered_protocol_is_reproducible_and_streamable() {
+    let registry = ProtocolRegistry::default();
+    let mdi = registry.get("MDI").unwrap().build();
+    assert!(mdi.has_relay());
+    assert!(!mdi.supports_statistical_backend());
+    assert_eq!(mdi.simulate(5_000, 0.3, 4), mdi.simulate(5_000, 0.3, 4));
+    let streamed = mdi.run_streaming_with_seed(20_000, 0.0, 4, 4_096);
+    assert!(streamed.is_considered_secure);
+    assert_eq!(streamed.quantum_bit_error_rate, Some(0.0));
+}
+
+#[test]
+fn transcripts_keep_the_announcements() {
+    let mdi = MDI.build().with_qber_threshold(0.5);
+    let (result, transcript) = Transcript::record("MDI", &mdi, 2_000, 0.2, 5);
+    let path = std::env::temp_dir().join(format!("qkd-mdi-{}.csv", std::process::id()));
+    transcript.write_to_path(&path).unwrap();
+    let read = Transcript::read_from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read, transcript);
+    let replayed = read.replay(&mdi).unwrap();
+    assert_eq!(replayed.key_length, result.key_length);
+    assert_eq!(
+        replayed.quantum_bit_error_rate,
+        result.quantum_bit_error_rate
+    );
+}
+
+#[test]
+fn walkthrough_shows_the_relay() {
+    let mdi = MDI.build();
+    let explanation = Explanation::new("MDI", &mdi, 40, 0.5, 6);
+    assert!(explanation.has_relay);
+    assert!(explanation.rounds.iter().all(|round| {
+        round.announcement.is_some()
+            && round.bob_sent_state.is_some()
+            && round
+                .eve
+                .as_ref()
+                .is_none_or(|eve| eve.resent_state.is_none())
+    }));
+    assert!(explanation.to_string().contains("Bell-state"));
+    assert_eq!(
+        explanation.is_considered_secure,
+        mdi.run_with_seed(40, 0.5, 6).is_considered_secure
+    );
+}
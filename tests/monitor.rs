@@ -0,0 +1,55 @@
+mod common;
+
+use qkd::monitor::{histogram, MonitoredConfiguration, SweepProgress};
+use qkd::output::ResultRecord;
+use std::time::Duration;
+
+fn configuration(interception_rate: f64, runs: usize) -> MonitoredConfiguration {
+    MonitoredConfiguration {
+        protocol: "BB84".to_string(),
+        number_of_qubits: 1000,
+        interception_rate,
+        distance_km: None,
+        parameters: None,
+        runs,
+    }
+}
+
+fn record(id: usize, interception_rate: f64) -> ResultRecord {
+    common::bb84_record(id, 1000, interception_rate)
+}
+
+#[test]
+fn progress_counts_runs_and_aborts_per_configuration() {
+    let mut progress = SweepProgress::new(vec![configuration(0.0, 3), configuration(1.0, 3)]);
+    assert_eq!(progress.total_runs(), 6);
+    assert_eq!(progress.estimated_time_left(Duration::from_secs(1)), None);
+
+    for id in [4, 0, 3, 1] {
+        progress.record(id / 3, &record(id, if id < 3 { 0.0 } else { 1.0 }));
+    }
+
+    assert_eq!(progress.completed_runs(), 4);
+    assert_eq!(progress.aborted_runs(), 2);
+    let honest = &progress.configurations[0];
+    assert_eq!(honest.completed_runs, 2);
+    assert_eq!(honest.key_lengths.len(), 2);
+    assert_eq!(honest.mean_quantum_bit_error_rate(), Some(0.0));
+    assert!(honest.mean_key_length().unwrap() > 0.0);
+    assert!(!honest.is_finished());
+    assert_eq!(progress.configurations[1].mean_key_length(), None);
+    assert_eq!(
+        progress.estimated_time_left(Duration::from_secs(8)),
+        Some(Duration::from_secs(4))
+    );
+}
+
+#[test]
+fn histograms_split_the_range_into_equal_bins() {
+    assert!(histogram(&[], 4).is_empty());
+    assert_eq!(histogram(&[2.0, 2.0], 4), vec![(2.0, 2)]);
+    assert_eq!(
+        histogram(&[0.0, 0.1, 0.5, 0.9, 1.0], 4),
+        vec![(0.0, 2), (0.25, 0), (0.5, 1), (0.75, 2)]
+    );
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use qkd::output::{read_records, OutputFormat, ResultRecord, ResultWriter, RunMetadata};
+use qkd::protocol::QKDResult;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A run with the given outcome; aborted runs have no key metrics.
+fn record(id: usize, is_considered_secure: bool) -> ResultRecord {
+    let result = QKDResult {
+        elapsed_time: Duration::from_micros(1_500 + id as u64),
+        is_considered_secure,
+        key_length: is_considered_secure.then_some(240),
+        quantum_bit_error_rate: is_considered_secure.then_some(0.0125),
+        eve_knowledge: if is_considered_secure { 0.05 } else { 0.0 },
+    };
+    ResultRecord {
+        scenario: Some("lossy".to_string()),
+        distance_km: id.is_multiple_of(2).then_some(10.0),
+        ..common::record(id, "BB84", 1_000, 0.25, result)
+    }
+}
+
+/// Creates a directory for the files of a test.
+fn directory(name: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("qkd-output-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/// Writes the records in the given format.
+fn write(path: &Path, format: OutputFormat, records: &[ResultRecord]) {
+    let metadata = RunMetadata::new(11, json!({ "protocols": ["BB84"] }));
+    let mut writer = ResultWriter::create(path, format, &metadata).unwrap();
+    for record in records {
+        writer.write(record).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[test]
+fn text_formats_round_trip() {
+    let records = [record(0, true), record(1, false), record(2, false)];
+    let directory = directory("text");
+
+    for (format, file) in [
+        (OutputFormat::Csv, "results.csv"),
+        (OutputFormat::Json, "results.json"),
+        (OutputFormat::Jsonl, "results.jsonl"),
+    ] {
+        let path = directory.join(file);
+        write(&path, format, &records);
+        let read = read_records(&path).unwrap();
+
+        let expected = records
+            .iter()
+            .cloned()
+            .map(|mut record| {
+                // The CSV format has no scenario column.
+                if format == OutputFormat::Csv {
+                    record.scenario = None;
+                }
+                record
+            })
+            .collect::<Vec<ResultRecord>>();
+        assert_eq!(read, expected, "{:?}", format);
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn csv_leaves_the_metrics_of_aborted_runs_empty() {
+    let directory = directory("csv");
+    let path = directory.join("results.csv");
+    write(
+        &path,
+        OutputFormat::Csv,
+        &[record(0, true), record(1, false)],
+    );
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    let lines = content.lines().collect::<Vec<&str>>();
+    assert_eq!(
+        lines[1],
+        "0,BB84,1000,0.25,1500,true,240,0.05,0.0125,10,,,,,,,"
+    );
+    assert_eq!(lines[2], "1,BB84,1000,0.25,1501,false,,,,,,,,,,,");
+}
+
+#[test]
+fn csv_files_of_previous_versions_are_read() {
+    let directory = directory("legacy");
+    let path = directory.join("results.csv");
+    std::fs::write(
+        &path,
+        "id,PROTOCOL,number_of_qubits,interception_rate,time_μs,is_considered_secure,key_length,eve_knowledge,QBER\n\
+         1,BB84,1000,0.25,1501,false,0,0,-1\n",
+    )
+    .unwrap();
+    let read = read_records(&path).unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    let mut expected = record(1, false);
+    expected.scenario = None;
+    expected.distance_km = None;
+    assert_eq!(read, [expected]);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_is_written_in_row_groups() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+    use qkd::output::PARQUET_ROWS_PER_GROUP;
+
+    let records = (0..PARQUET_ROWS_PER_GROUP + 10)
+        .map(|id| record(id, !id.is_multiple_of(3)))
+        .collect::<Vec<ResultRecord>>();
+    let directory = directory("parquet");
+    let path = directory.join("results.parquet");
+    write(&path, OutputFormat::Parquet, &records);
+
+    let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata();
+    assert_eq!(metadata.num_row_groups(), 2);
+    assert_eq!(metadata.file_metadata().num_rows(), records.len() as i64);
+    let key_value = metadata
+        .file_metadata()
+        .key_value_metadata()
+        .unwrap()
+        .iter()
+        .find(|key_value| key_value.key == "qkd.metadata")
+        .unwrap();
+    let run_metadata =
+        serde_json::from_str::<serde_json::Value>(key_value.value.as_ref().unwrap()).unwrap();
+    assert_eq!(run_metadata["seed"], 11);
+
+    let rows = reader
+        .get_row_iter(None)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(rows.len(), records.len());
+    for (row, record) in rows.iter().zip(&records) {
+        let column = |name: &str| {
+            row.get_column_iter()
+                .find(|(column, _)| column.as_str() == name)
+                .map(|(_, field)| field.clone())
+                .unwrap()
+        };
+        assert_eq!(column("id"), Field::Long(record.id as i64));
+        assert_eq!(column("scenario"), Field::Str("lossy".to_string()));
+        assert_eq!(
+            column("is_considered_secure"),
+            Field::Bool(record.result.is_considered_secure)
+        );
+        match record.result.key_length {
+            Some(key_length) => {
+                assert_eq!(column("key_length"), Field::Long(key_length as i64));
+                assert_eq!(column("qber"), Field::Double(0.0125));
+            }
+            None => {
+                assert_eq!(column("key_length"), Field::Null);
+                assert_eq!(column("qber"), Field::Null);
+            }
+        }
+        let distance = record.distance_km.map_or(Field::Null, Field::Double);
+        assert_eq!(column("distance_km"), distance);
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,42 @@
+use qkd::backend::Backend;
+use qkd::protocol::{QKDResult, ROUNDS_PER_CHUNK};
+use qkd::protocols::ProtocolRegistry;
+use rayon::ThreadPoolBuilder;
+use std::time::Duration;
+
+const NUMBER_OF_QUBITS: usize = 3 * ROUNDS_PER_CHUNK + 100;
+
+/// Runs `f` in a dedicated pool with the given number of threads.
+fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap()
+        .install(f)
+}
+
+/// Clears the only field of a result that depends on the machine.
+fn without_timing(result: QKDResult) -> QKDResult {
+    QKDResult {
+        elapsed_time: Duration::ZERO,
+        ..result
+    }
+}
+
+#[test]
+fn seeded_runs_do_not_depend_on_the_number_of_threads() {
+    let registry = ProtocolRegistry::default();
+    for protocol in registry.iter() {
+        for backend in [Backend::StateVector, Backend::Statistical] {
+            let qkd = protocol.build().with_backend(backend);
+            let run = |threads| {
+                with_threads(threads, || {
+                    let result = qkd.run_with_seed(NUMBER_OF_QUBITS, 0.1, 9);
+                    let rounds = qkd.simulate(NUMBER_OF_QUBITS, 0.1, 9);
+                    (without_timing(result), rounds)
+                })
+            };
+            assert_eq!(run(1), run(4), "{} ({:?})", protocol.name(), backend);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use qkd::output::{read_records, OutputFormat, ResultRecord, ResultWriter, RunMetadata};
+use qkd::plot::Chart;
+use qkd::protocol::QKDResult;
+use serde_json::json;
+use std::time::Duration;
+
+fn record(id: usize, protocol: &str, number_of_qubits: usize, distance_km: f64) -> ResultRecord {
+    let is_considered_secure = !id.is_multiple_of(3);
+    let result = QKDResult {
+        elapsed_time: Duration::from_micros(id as u64),
+        is_considered_secure,
+        key_length: is_considered_secure.then_some(number_of_qubits / 4),
+        quantum_bit_error_rate: is_considered_secure.then_some(0.05 + 0.01 * id as f64),
+        eve_knowledge: if is_considered_secure { 0.1 } else { 0.0 },
+    };
+    ResultRecord {
+        distance_km: Some(distance_km),
+        ..common::record(id, protocol, number_of_qubits, 0.25, result)
+    }
+}
+
+fn records() -> Vec<ResultRecord> {
+    let mut records = Vec::new();
+    for protocol in ["BB84", "B92"] {
+        for number_of_qubits in [10, 100, 10000] {
+            for distance_km in [0.0, 50.0] {
+                for _ in 0..3 {
+                    let id = records.len();
+                    records.push(record(id, protocol, number_of_qubits, distance_km));
+                }
+            }
+        }
+    }
+    records
+}
+
+#[test]
+fn results_are_read_back_from_every_text_format() {
+    let records = records();
+    let directory = std::env::temp_dir().join(format!("qkd-plot-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let metadata = RunMetadata::new(1, json!({}));
+
+    for (format, file) in [
+        (OutputFormat::Csv, "results.csv"),
+        (OutputFormat::Json, "results.json"),
+        (OutputFormat::Jsonl, "results.jsonl"),
+    ] {
+        let path = directory.join(file);
+        let mut writer = ResultWriter::create(&path, format, &metadata).unwrap();
+        for record in &records {
+            writer.write(record).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let read = read_records(&path).unwrap();
+        assert_eq!(read.len(), records.len());
+        assert_eq!(read[1].result.key_length, records[1].result.key_length);
+        assert_eq!(read[3].result.quantum_bit_error_rate, None);
+        assert_eq!(read[5].protocol, "BB84");
+        assert_eq!(read[5].distance_km, records[5].distance_km);
+        assert_eq!(
+            Chart::KeyRateVsDistance.figure(&read, 0.95),
+            Chart::KeyRateVsDistance.figure(&records, 0.95)
+        );
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn charts_have_one_curve_per_protocol_with_error_bars() {
+    let records = records();
+
+    let figure = Chart::DetectionVsQubits.figure(&records, 0.95).unwrap();
+    assert!(figure.log_x);
+    assert_eq!(figure.series.len(), 4);
+    assert_eq!(figure.series[0].label, "BB84, 0 km");
+    assert_eq!(figure.series[0].points.len(), 3);
+    assert!(figure.series[0].points[0].y_interval.is_some());
+
+    let figure = Chart::KeyRateVsDistance.figure(&records, 0.95).unwrap();
+    assert_eq!(figure.series[0].label, "BB84, n=10");
+    assert_eq!(figure.series[0].points[1].x, 50.0);
+
+    let svg = Chart::EveKnowledgeVsQber
+        .figure(&records, 0.95)
+        .unwrap()
+        .to_svg();
+    assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<polyline").count(), 12);
+
+    let without_distance = records
+        .into_iter()
+        .map(|record| ResultRecord {
+            distance_km: None,
+            ..record
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        Chart::KeyRateVsDistance.figure(&without_distance, 0.95),
+        None
+    );
+}
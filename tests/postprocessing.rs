@@ -0,0 +1,114 @@
+use qkd::postprocessing::{amplify, reconcile, secure_key_length, PostProcessing};
+use qkd::protocols::{ProtocolRegistry, QkdProtocol, BB84};
+use qkd::transcript::{RoundRole, Transcript};
+use qkd::utils::seeded_rng;
+use rand::Rng;
+use std::io::ErrorKind;
+
+fn noisy_keys(length: usize, error_rate: f64, seed: u64) -> (Vec<bool>, Vec<bool>) {
+    let mut rng = seeded_rng(seed, 0);
+    let alice = (0..length)
+        .map(|_| rng.random::<bool>())
+        .collect::<Vec<bool>>();
+    let bob = alice
+        .iter()
+        .map(|&bit| bit ^ rng.random_bool(error_rate))
+        .collect();
+    (alice, bob)
+}
+
+#[test]
+fn reconciliation_corrects_every_error_at_low_qber() {
+    for seed in 0..10 {
+        let (alice, bob) = noisy_keys(10_000, 0.03, seed);
+        let reconciliation = reconcile(&alice, &bob, 0.03, 4, &mut seeded_rng(seed, 1));
+
+        assert_eq!(reconciliation.key, alice);
+        assert!(reconciliation.leaked_bits < alice.len() / 2);
+    }
+}
+
+#[test]
+fn reconciliation_of_identical_keys_only_leaks_block_parities() {
+    let (alice, _) = noisy_keys(1000, 0.0, 3);
+    let reconciliation = reconcile(&alice, &alice, 0.0, 4, &mut seeded_rng(3, 1));
+
+    assert_eq!(reconciliation.key, alice);
+    assert_eq!(reconciliation.leaked_bits, 4);
+}
+
+#[test]
+fn amplification_is_deterministic_and_compresses() {
+    let (alice, _) = noisy_keys(1000, 0.0, 5);
+    let key = amplify(&alice, 300, 7);
+
+    assert_eq!(key.len(), 300);
+    assert_eq!(key, amplify(&alice, 300, 7));
+    assert_ne!(key, amplify(&alice, 300, 8));
+
+    let mut flipped = alice.clone();
+    flipped[999] = !flipped[999];
+    assert_ne!(key, amplify(&flipped, 300, 7));
+}
+
+#[test]
+fn secure_key_length_subtracts_leakage_and_security_margin() {
+    assert_eq!(secure_key_length(1000, 0.0, 4, 0.5), 994);
+    assert_eq!(secure_key_length(1000, 0.0, 100, 1.0 / 1024.0), 880);
+    assert_eq!(secure_key_length(1000, 0.5, 0, 0.5), 0);
+    assert_eq!(secure_key_length(10, 0.0, 20, 0.5), 0);
+}
+
+#[test]
+fn transcripts_are_reconciled_into_matching_keys() {
+    let registry = ProtocolRegistry::default();
+    let qkd = BB84.build().with_qber_threshold(0.05);
+    let (result, transcript) = Transcript::record("BB84", &qkd, 40_000, 0.05, 11);
+    assert!(result.is_considered_secure);
+
+    let sifted_key = transcript
+        .sifted_key(&registry.get("BB84").unwrap().build())
+        .unwrap();
+    assert_eq!(Some(sifted_key.alice.len()), result.key_length);
+    assert!(sifted_key.estimated_qber > 0.0);
+
+    let processed = PostProcessing::builder().build().run(&sifted_key, 11);
+    assert!(processed.mismatched_bits > 0);
+    assert_eq!(processed.residual_errors, 0);
+    assert!(processed.keys_match);
+    assert_eq!(
+        processed.key.len(),
+        secure_key_length(
+            processed.sifted_length,
+            processed.estimated_qber,
+            processed.leaked_bits,
+            1e-10
+        )
+    );
+    assert!(processed.key.len() > sifted_key.alice.len() / 2);
+}
+
+#[test]
+fn replaying_with_different_roles_is_rejected() {
+    let qkd = BB84.build();
+    let (result, mut transcript) = Transcript::record("BB84", &qkd, 2_000, 0.0, 5);
+    let replayed = transcript.replay(&qkd).unwrap();
+    assert_eq!(replayed.key_length, result.key_length);
+    assert_eq!(
+        replayed.quantum_bit_error_rate,
+        result.quantum_bit_error_rate
+    );
+
+    let round = transcript
+        .rounds
+        .iter_mut()
+        .find(|round| round.role == RoundRole::Key)
+        .unwrap();
+    round.role = RoundRole::Check;
+    for error in [
+        transcript.replay(&qkd).unwrap_err(),
+        transcript.sifted_key(&qkd).unwrap_err(),
+    ] {
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "python")]
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::wrap_pymodule;
+use std::ffi::CString;
+
+/// Runs Python statements with the extension module imported as `qkd`.
+fn run(code: &str) -> PyResult<()> {
+    Python::initialize();
+    Python::attach(|py| {
+        let globals = PyDict::new(py);
+        globals.set_item("qkd", wrap_pymodule!(qkd::python::qkd)(py))?;
+        py.run(&CString::new(code).unwrap(), Some(&globals), None)
+    })
+}
+
+#[test]
+fn protocol_entry_points_return_results() {
+    run(r#"
+result = qkd.run_bb84(1000, 0.0, seed=3)
+assert result.is_considered_secure
+assert result.key_length > 0
+assert result.quantum_bit_error_rate == 0.0
+assert result.to_dict()["key_length"] == result.key_length
+assert qkd.run_bb84(1000, 0.0, seed=3).key_length == result.key_length
+assert not qkd.run_six_state(1000, 1.0, seed=3).is_considered_secure
+assert qkd.run_b92(1000).to_dict().keys() == {"time_us", "is_considered_secure", "key_length", "eve_knowledge", "qber"}
+assert qkd.protocols() == ["B92", "BB84", "MDI", "RFI", "SixState"]
+"#)
+    .unwrap();
+}
+
+#[test]
+fn channel_and_attack_are_configurable() {
+    run(r#"
+channel = qkd.Channel(distance_km=25.0, depolarization=0.01)
+assert abs(channel.transmittance() - 10 ** -0.5) < 1e-12
+lossy = qkd.QKD("BB84", channel=channel, detector=qkd.Detector(efficiency=0.5), qber_threshold=0.1)
+ideal = qkd.QKD("BB84", backend="statistical")
+assert lossy.run(20000, seed=1).key_length < ideal.run(20000, seed=1).key_length / 4
+
+eve = qkd.QKD("SixState", eve_basis=["Z"], qber_threshold=1.0)
+assert eve.run(6000, 1.0, seed=2).quantum_bit_error_rate > 0.25
+
+assert qkd.QKD("RFI").qber_threshold == 0.0
+for call in (
+    lambda: qkd.QKD("E91"),
+    lambda: qkd.QKD("BB84", eve_basis=["W"]),
+    lambda: qkd.QKD("RFI", qber_threshold=0.1),
+    lambda: qkd.Channel(loss=2.0),
+):
+    try:
+        call()
+        raise AssertionError("expected a ValueError")
+    except ValueError:
+        pass
+"#)
+    .unwrap();
+}
+
+/// Run with `cargo test --features python -- --include-ignored` where NumPy is installed.
+#[test]
+#[ignore = "requires the numpy Python package"]
+fn repetitions_are_returned_as_numpy_arrays() {
+    run(r#"
+import numpy as np
+results = qkd.QKD("BB84", qber_threshold=0.05).run_many(500, 0.2, repetitions=20, seed=4)
+assert results["key_length"].shape == (20,)
+assert results["is_considered_secure"].dtype == np.bool_
+assert np.isnan(results["qber"][~results["is_considered_secure"]]).all()
+"#)
+    .unwrap();
+}
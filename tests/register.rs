@@ -0,0 +1,69 @@
+use num_complex::Complex64;
+use qkd::types::{Qubit, Register};
+use qkd::utils::{seeded_rng, H, X};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+fn assert_amplitudes(register: &Register, expected: &[f64]) {
+    assert_eq!(register.amplitudes().len(), expected.len());
+    for (amplitude, expected) in register.amplitudes().iter().zip(expected) {
+        assert!((amplitude - Complex64::new(*expected, 0.0)).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn kronecker_product_orders_qubits_from_most_significant() {
+    let mut one = Qubit::new();
+    one.apply_transformation(&X);
+    let register = Register::from_qubits(&[Qubit::new(), one, Qubit::new()]);
+    assert_eq!(register.number_of_qubits(), 3);
+    assert_amplitudes(&register, &[0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(register.one_probability(1), 1.0);
+    assert_eq!(register.one_probability(2), 0.0);
+
+    assert!(Register::from_amplitudes(vec![Complex64::new(1.0, 0.0); 3]).is_none());
+    let plus = Register::from_amplitudes(vec![Complex64::new(2.0, 0.0); 2]).unwrap();
+    assert_amplitudes(&plus, &[FRAC_1_SQRT_2, FRAC_1_SQRT_2]);
+}
+
+#[test]
+fn bell_state_measurements_are_correlated() {
+    let mut bell = Register::new(2);
+    bell.apply_transformation(0, &H);
+    bell.apply_controlled(&[0], 1, &X);
+    assert_amplitudes(&bell, &[FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2]);
+
+    let mut ones = 0;
+    for stream in 0..100 {
+        let mut rng = seeded_rng(5, stream);
+        let mut register = bell.clone();
+        let first = register.measure(0, &mut rng);
+        assert_eq!(register.measure(1, &mut rng), first);
+        ones += usize::from(first);
+    }
+    assert!((30..=70).contains(&ones));
+}
+
+#[test]
+fn controlled_gates_require_every_control() {
+    let mut register = Register::new(3);
+    register.apply_transformation(0, &X);
+    register.apply_controlled(&[0, 1], 2, &X);
+    assert_eq!(register.one_probability(2), 0.0);
+
+    register.apply_transformation(1, &X);
+    register.apply_controlled(&[0, 1], 2, &X);
+    assert_amplitudes(&register, &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn one_qubit_registers_match_qubits() {
+    let mut qubit = Qubit::new();
+    let mut register = Register::from(qubit.clone());
+    qubit.apply_transformation(&H);
+    register.apply_transformation(0, &H);
+
+    let converted = register.qubit().unwrap();
+    assert_eq!(converted.get_zero_coef(), qubit.get_zero_coef());
+    assert_eq!(converted.get_one_coef(), qubit.get_one_coef());
+    assert!(Register::new(2).qubit().is_none());
+}
@@ -0,0 +1,69 @@
+use qkd::physical::{Channel, PhysicalLayer};
+use qkd::protocol::SiftingStrategy;
+use qkd::protocols::{QkdProtocol, RfiEstimate, RfiSifting, BB84, RFI};
+use qkd::utils::seeded_rng;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_3};
+
+fn rotated(rotation_rad: f64) -> PhysicalLayer {
+    PhysicalLayer {
+        channel: Channel {
+            rotation_rad,
+            ..Channel::default()
+        },
+        ..PhysicalLayer::default()
+    }
+}
+
+#[test]
+fn c_parameter_does_not_depend_on_the_frame_rotation() {
+    for rotation_rad in [0.0, 0.4, FRAC_PI_3, FRAC_PI_2] {
+        let results = RFI
+            .build()
+            .with_physical_layer(rotated(rotation_rad))
+            .simulate(20_000, 0.0, 4);
+        let discussion_result = RfiSifting.sift(results, &mut seeded_rng(4, 0));
+        let estimate = RfiEstimate::from_discussion(&discussion_result).unwrap();
+        assert_eq!(estimate.quantum_bit_error_rate, 0.0);
+        assert!((estimate.c_parameter - 2.0).abs() < 0.1, "{:?}", estimate);
+        assert!(estimate.key_rate > 0.9, "{:?}", estimate);
+    }
+}
+
+#[test]
+fn rfi_tolerates_frame_rotations_that_abort_bb84() {
+    let physical_layer = rotated(FRAC_PI_2);
+    let result = RFI
+        .build()
+        .with_physical_layer(physical_layer)
+        .run_with_seed(20_000, 0.0, 5);
+    assert!(result.is_considered_secure);
+    assert_eq!(result.quantum_bit_error_rate, Some(0.0));
+
+    let result = BB84
+        .build()
+        .with_physical_layer(physical_layer)
+        .with_qber_threshold(0.11)
+        .run_with_seed(20_000, 0.0, 5);
+    assert!(!result.is_considered_secure);
+}
+
+#[test]
+fn rfi_aborts_under_intercept_resend() {
+    let result = RFI
+        .build()
+        .with_physical_layer(rotated(0.3))
+        .run_with_seed(20_000, 1.0, 6);
+    assert!(!result.is_considered_secure);
+}
+
+#[test]
+fn key_rate_bounds_follow_the_closed_forms() {
+    assert_eq!(RfiEstimate::new(0.0, 2.0).key_rate, 1.0);
+    assert_eq!(RfiEstimate::new(0.0, 0.0).key_rate, 0.0);
+
+    // Depolarizing noise gives C = 2 (1 - 2e)², and a positive key rate up to
+    // the e ≈ 12.6% of the Six-State protocol.
+    let estimate = |e: f64| RfiEstimate::new(e, 2.0 * (1.0 - 2.0 * e).powi(2));
+    assert!(estimate(0.12).key_rate > 0.0);
+    assert!(estimate(0.13).key_rate < 0.0);
+}
@@ -0,0 +1,14 @@
+use qkd::utils::{random_seed, set_seed_source};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+#[test]
+fn random_seeds_come_from_the_injected_source() {
+    let mut expected = StdRng::seed_from_u64(3);
+    set_seed_source(Some(Box::new(StdRng::seed_from_u64(3))));
+    assert_eq!(random_seed(), expected.next_u64());
+    assert_eq!(random_seed(), expected.next_u64());
+
+    set_seed_source(None);
+    assert_ne!(random_seed(), random_seed());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "serde")]
+
+use qkd::backend::Backend;
+use qkd::config::{ExperimentConfig, ProtocolConfig, SiftingConfig};
+use qkd::protocol::QKDResult;
+use qkd::protocols::ProtocolRegistry;
+use qkd::transcript::Transcript;
+use qkd::utils::I;
+use std::time::Duration;
+
+#[test]
+fn qkd_result_schema_is_stable() {
+    let result = QKDResult {
+        elapsed_time: Duration::from_micros(1500),
+        is_considered_secure: false,
+        key_length: None,
+        quantum_bit_error_rate: None,
+        eve_knowledge: 0.0,
+    };
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "elapsed_time_ns": 1_500_000,
+            "is_considered_secure": false,
+            "key_length": null,
+            "quantum_bit_error_rate": null,
+            "eve_knowledge": 0.0,
+        })
+    );
+    assert_eq!(serde_json::from_value::<QKDResult>(json).unwrap(), result);
+}
+
+#[test]
+fn transcript_round_trips() {
+    let registry = ProtocolRegistry::default();
+    let protocol = registry.get("BB84").unwrap();
+    let (_, transcript) = Transcript::record("BB84", &protocol.build(), 100, 0.5, 7);
+    let json = serde_json::to_string(&transcript).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Transcript>(&json).unwrap(),
+        transcript
+    );
+}
+
+#[test]
+fn experiment_runs_custom_protocol_from_json() {
+    let protocol: ProtocolConfig = serde_json::from_str(
+        r#"{
+            "name": "ZX",
+            "alice_basis": [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]],
+            "bob_basis": [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]],
+            "sifting": "basis_match"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(protocol.alice_basis, vec![I]);
+    assert_eq!(protocol.sifting, SiftingConfig::BasisMatch);
+
+    let experiment: ExperimentConfig = serde_json::from_str(
+        r#"{"protocol": "ZX", "number_of_qubits": 1000, "seed": 1, "backend": "statistical"}"#,
+    )
+    .unwrap();
+    assert_eq!(experiment.backend, Backend::Statistical);
+
+    let mut registry = ProtocolRegistry::default();
+    registry.register(protocol);
+    let result = experiment.run(&registry).unwrap();
+    assert!(result.is_considered_secure);
+    assert_eq!(result.quantum_bit_error_rate, Some(0.0));
+}
+
+#[test]
+fn experiment_records_transcript_from_json() {
+    let experiment: ExperimentConfig =
+        serde_json::from_str(r#"{"protocol": "BB84", "number_of_qubits": 200, "seed": 9}"#)
+            .unwrap();
+    let registry = ProtocolRegistry::default();
+    let (result, transcript) = experiment.record(&registry).unwrap();
+    assert_eq!(transcript.seed, 9);
+    assert_eq!(transcript.rounds.len(), 200);
+    assert_eq!(
+        result.key_length,
+        experiment.run(&registry).unwrap().key_length
+    );
+}
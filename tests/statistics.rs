@@ -0,0 +1,79 @@
+mod common;
+
+use qkd::output::{AggregateRecord, OutputRecord};
+use qkd::protocol::QKDResult;
+use qkd::statistics::{normal_quantile, student_t_quantile, Proportion, Summary};
+use std::time::Duration;
+
+const TOLERANCE: f64 = 1e-3;
+
+#[test]
+fn quantiles_match_reference_tables() {
+    assert!((normal_quantile(0.025) + 1.959964).abs() < TOLERANCE);
+    for (p, degrees_of_freedom, expected) in [
+        (0.05, 1, 12.7062),
+        (0.05, 2, 4.3027),
+        (0.05, 4, 2.7764),
+        (0.05, 29, 2.0452),
+        (0.01, 9, 3.2498),
+    ] {
+        let t = student_t_quantile(p, degrees_of_freedom);
+        assert!(
+            (t - expected).abs() < TOLERANCE,
+            "t({}, {}) = {}",
+            p,
+            degrees_of_freedom,
+            t
+        );
+    }
+}
+
+#[test]
+fn intervals_match_reference_values() {
+    let summary = Summary::new(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.95).unwrap();
+    assert_eq!(summary.mean, 3.0);
+    let (low, high) = summary.confidence_interval.unwrap();
+    assert!((low - 1.0367).abs() < TOLERANCE && (high - 4.9633).abs() < TOLERANCE);
+    assert_eq!(
+        Summary::new(&[2.0], 0.95).unwrap().confidence_interval,
+        None
+    );
+
+    let (low, high) = Proportion::wilson(3, 10, 0.95).confidence_interval;
+    assert!((low - 0.1078).abs() < TOLERANCE && (high - 0.6032).abs() < TOLERANCE);
+    assert_eq!(Proportion::wilson(0, 10, 0.95).confidence_interval.0, 0.0);
+}
+
+#[test]
+fn records_are_aggregated_per_configuration() {
+    let record = |id: usize, interception_rate: f64, key_length: Option<usize>| {
+        let result = QKDResult {
+            elapsed_time: Duration::from_micros(10),
+            is_considered_secure: key_length.is_some(),
+            key_length,
+            quantum_bit_error_rate: key_length.map(|_| 0.0),
+            eve_knowledge: 0.0,
+        };
+        common::record(id, "BB84", 100, interception_rate, result)
+    };
+    let records = [
+        record(0, 0.0, Some(20)),
+        record(1, 0.5, None),
+        record(2, 0.0, Some(30)),
+        record(3, 0.5, None),
+    ];
+
+    let aggregates = AggregateRecord::group(&records, 0.95);
+    assert_eq!(aggregates.len(), 2);
+    assert_eq!(aggregates[0].statistics.key_length.unwrap().mean, 25.0);
+    assert_eq!(aggregates[1].statistics.abort.estimate, 1.0);
+
+    let json = aggregates[1].to_json();
+    assert_eq!(json["runs"], 2);
+    assert!(json["qber_mean"].is_null());
+    assert_eq!(json["time_us_mean"], 10.0);
+    assert_eq!(
+        AggregateRecord::csv_header().len(),
+        aggregates[1].to_csv_row().len()
+    );
+}
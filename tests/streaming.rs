@@ -0,0 +1,68 @@
+use qkd::protocol::{QKDResult, ROUNDS_PER_CHUNK};
+use qkd::protocols::{QkdProtocol, BB84};
+use std::time::Duration;
+
+/// Clears the only field of a result that depends on the machine.
+fn without_timing(result: QKDResult) -> QKDResult {
+    QKDResult {
+        elapsed_time: Duration::ZERO,
+        ..result
+    }
+}
+
+#[test]
+fn a_single_block_gives_the_same_result_as_a_run() {
+    let qkd = BB84.build();
+    for block_size in [10_000, 50_000] {
+        for interception_rate in [0.0, 0.05, 1.0] {
+            assert_eq!(
+                without_timing(qkd.run_streaming_with_seed(
+                    10_000,
+                    interception_rate,
+                    3,
+                    block_size
+                )),
+                without_timing(qkd.run_with_seed(10_000, interception_rate, 3))
+            );
+        }
+    }
+}
+
+#[test]
+fn keys_accumulate_across_blocks() {
+    let qkd = BB84.build();
+    let number_of_qubits = 4 * ROUNDS_PER_CHUNK + 100;
+    let result = qkd.run_streaming_with_seed(number_of_qubits, 0.0, 5, ROUNDS_PER_CHUNK);
+
+    // Every block keeps the rounds with matching bases and reveals half of them.
+    let expected = qkd
+        .simulate(number_of_qubits, 0.0, 5)
+        .chunks(ROUNDS_PER_CHUNK)
+        .map(|block| {
+            let sifted = block
+                .iter()
+                .filter(|round| round.alice_basis == round.bob_basis)
+                .count();
+            sifted - sifted / 2
+        })
+        .sum::<usize>();
+    assert!(result.is_considered_secure);
+    assert_eq!(result.key_length, Some(expected));
+    assert_eq!(result.quantum_bit_error_rate, Some(0.0));
+    assert!(expected > number_of_qubits / 5);
+}
+
+#[test]
+fn a_noisy_block_aborts_the_run() {
+    let qkd = BB84.build();
+    let result = qkd.run_streaming_with_seed(20_000, 1.0, 7, ROUNDS_PER_CHUNK);
+    assert!(!result.is_considered_secure);
+    assert_eq!(result.key_length, None);
+    assert_eq!(result.quantum_bit_error_rate, None);
+
+    let tolerant =
+        qkd.with_qber_threshold(1.0)
+            .run_streaming_with_seed(20_000, 1.0, 7, ROUNDS_PER_CHUNK);
+    assert!(tolerant.is_considered_secure);
+    assert!(tolerant.quantum_bit_error_rate.unwrap() > 0.2);
+}
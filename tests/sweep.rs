@@ -0,0 +1,34 @@
+use qkd::sweep::{expand, Sweep};
+
+#[test]
+fn expressions_expand_to_their_values() {
+    assert_eq!(expand("0.25").unwrap(), vec![0.25]);
+    assert_eq!(
+        expand("0:0.3:0.05").unwrap(),
+        vec![0.0, 0.05, 0.1, 0.15, 0.2, 0.25, 0.3]
+    );
+    assert_eq!(expand("1:0:-0.5").unwrap(), vec![1.0, 0.5, 0.0]);
+    assert_eq!(expand("linspace(0, 0.3, 31)").unwrap().len(), 31);
+    assert_eq!(expand("linspace(0, 0.3, 31)").unwrap()[7], 0.07);
+    assert_eq!(
+        "logspace(2,7,6)".parse::<Sweep<usize>>().unwrap(),
+        Sweep(vec![100, 1_000, 10_000, 100_000, 1_000_000, 10_000_000])
+    );
+}
+
+#[test]
+fn malformed_expressions_are_rejected() {
+    for expression in [
+        "",
+        "0:1",
+        "0:1:0",
+        "1:0:0.1",
+        "linspace(0,1)",
+        "logspace(0,1,0)",
+        "a:b:c",
+    ] {
+        assert!(expand(expression).is_err(), "`{}` was accepted", expression);
+    }
+    assert!("0:1:0.5".parse::<Sweep<usize>>().is_err());
+    assert!("-1".parse::<Sweep<usize>>().is_err());
+}
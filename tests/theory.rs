@@ -0,0 +1,55 @@
+mod common;
+
+use qkd::output::ResultRecord;
+use qkd::protocols::ProtocolRegistry;
+use qkd::theory::{ComparisonRecord, Metric};
+
+fn simulate(registry: &ProtocolRegistry, interception_rate: f64) -> Vec<ResultRecord> {
+    let mut records = Vec::new();
+    for protocol in registry.iter() {
+        for seed in 0..40 {
+            records.push(common::record(
+                records.len(),
+                protocol.name(),
+                1000,
+                interception_rate,
+                protocol.run_with_seed(1000, interception_rate, seed),
+            ));
+        }
+    }
+    records
+}
+
+#[test]
+fn predictions_match_intercept_resend_closed_forms() {
+    let registry = ProtocolRegistry::default();
+    let prediction = |name: &str| {
+        registry
+            .get(name)
+            .unwrap()
+            .theoretical_prediction(1.0)
+            .unwrap()
+    };
+
+    assert_eq!(prediction("BB84").quantum_bit_error_rate, 0.25);
+    assert_eq!(prediction("BB84").sift_rate, 0.5);
+    assert!((prediction("SixState").quantum_bit_error_rate - 1.0 / 3.0).abs() < 1e-12);
+    assert!((prediction("B92").quantum_bit_error_rate - 1.0 / 3.0).abs() < 1e-12);
+    assert_eq!(prediction("B92").sift_rate, 3.0 / 8.0);
+    assert_eq!(prediction("BB84").abort_probability(1000, 1.0, 0.0), 1.0);
+    assert!(prediction("BB84").abort_probability(1000, 1.0, 0.5) < 1e-9);
+}
+
+#[test]
+fn simulation_agrees_with_theory() {
+    let registry = ProtocolRegistry::default();
+    for interception_rate in [0.0, 0.005] {
+        let records = simulate(&registry, interception_rate);
+        let comparisons = ComparisonRecord::compare(&records, &registry, 0.999);
+        assert_eq!(comparisons.len(), 3 * Metric::ALL.len());
+        for comparison in &comparisons {
+            assert!(comparison.observed.is_some(), "{:?}", comparison);
+            assert!(!comparison.is_significant(), "{:?}", comparison);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use qkd::wasm::{record_transcript, run_experiment, seed_random, MAX_SAFE_SEED};
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Parses a JSON object into a JavaScript object.
+fn object(json: &str) -> JsValue {
+    js_sys::JSON::parse(json).unwrap()
+}
+
+/// Converts a JavaScript value returned by the bindings to JSON.
+fn to_json(value: JsValue) -> Value {
+    serde_wasm_bindgen::from_value(value).unwrap()
+}
+
+/// Returns the result of a run without its elapsed time.
+fn without_timing(mut result: Value) -> Value {
+    result["elapsed_time_ns"] = Value::Null;
+    result
+}
+
+#[wasm_bindgen_test]
+fn run_experiment_returns_the_seed_that_repeats_it() {
+    let run = to_json(
+        run_experiment(object(r#"{ "protocol": "BB84", "number_of_qubits": 500 }"#)).unwrap(),
+    );
+    let seed = run["seed"].as_u64().unwrap();
+    assert!(seed <= MAX_SAFE_SEED);
+    assert_eq!(run["result"]["is_considered_secure"], true);
+
+    let repeated = to_json(
+        run_experiment(object(&format!(
+            r#"{{ "protocol": "BB84", "number_of_qubits": 500, "seed": {} }}"#,
+            seed
+        )))
+        .unwrap(),
+    );
+    assert_eq!(repeated["seed"], seed);
+    assert_eq!(
+        without_timing(repeated["result"].clone()),
+        without_timing(run["result"].clone())
+    );
+
+    assert!(run_experiment(object(r#"{ "protocol": "Unknown" }"#)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn record_transcript_returns_every_round_with_its_role() {
+    let experiment = r#"{ "protocol": "BB84", "number_of_qubits": 200, "seed": 3 }"#;
+    let recorded = to_json(record_transcript(object(experiment)).unwrap());
+    let run = to_json(run_experiment(object(experiment)).unwrap());
+
+    let transcript = &recorded["transcript"];
+    assert_eq!(transcript["protocol"], "BB84");
+    assert_eq!(transcript["seed"], 3);
+    let rounds = transcript["rounds"].as_array().unwrap();
+    assert_eq!(rounds.len(), 200);
+    assert!(rounds
+        .iter()
+        .all(|round| ["discarded", "check", "key"].contains(&round["role"].as_str().unwrap())));
+    assert!(rounds.iter().any(|round| round["role"] == "key"));
+    assert_eq!(
+        without_timing(recorded["result"].clone()),
+        without_timing(run["result"].clone())
+    );
+}
+
+#[wasm_bindgen_test]
+fn seeds_are_limited_to_max_safe_seed() {
+    let unseeded = r#"{ "protocol": "BB84", "number_of_qubits": 100 }"#;
+    let seed = || {
+        to_json(run_experiment(object(unseeded)).unwrap())["seed"]
+            .as_u64()
+            .unwrap()
+    };
+
+    seed_random(Some(7));
+    let seeds = (0..20).map(|_| seed()).collect::<Vec<u64>>();
+    seed_random(Some(7));
+    assert_eq!((0..20).map(|_| seed()).collect::<Vec<u64>>(), seeds);
+    seed_random(None);
+    assert!(seeds.iter().all(|&seed| seed <= MAX_SAFE_SEED));
+    assert!(seeds.iter().any(|&seed| seed > u64::from(u32::MAX)));
+
+    let experiment = |seed: u64| {
+        let experiment = object(unseeded);
+        js_sys::Reflect::set(
+            &experiment,
+            &"seed".into(),
+            &js_sys::BigInt::from(seed).into(),
+        )
+        .unwrap();
+        run_experiment(experiment)
+    };
+    assert_eq!(
+        to_json(experiment(MAX_SAFE_SEED).unwrap())["seed"],
+        MAX_SAFE_SEED
+    );
+    assert!(experiment(MAX_SAFE_SEED + 1).is_err());
+}